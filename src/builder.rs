@@ -0,0 +1,898 @@
+//! Construction of function bodies with the `IRBuilder`.
+//!
+//! `Function::new()` and `BasicBlock::new()` only give empty shells; filling in
+//! instructions by hand means spelling out every field of every instruction
+//! struct, including the ones that only exist for some LLVM versions. The
+//! `IRBuilder` appends instructions to a `Function` instead, allocating fresh
+//! numbered `Name`s for their results, filling in the version-specific fields,
+//! and checking operand types as it goes.
+//!
+//! ```
+//! use llvm_ir::builder::IRBuilder;
+//! use llvm_ir::{Function, IntPredicate, Module, Name};
+//!
+//! let mut module = Module::from_ir_str("").unwrap();
+//! let i32_ty = module.types.i32();
+//! let mut func = Function::new("max");
+//! func.return_type = i32_ty.clone();
+//!
+//! let mut builder = IRBuilder::new(&mut func, &module.types);
+//! let a = builder.add_param(i32_ty.clone());
+//! let b = builder.add_param(i32_ty);
+//! let entry = builder.append_block();
+//! builder.position_at_end(&entry);
+//! let cond = builder.icmp(IntPredicate::SGT, a.clone(), b.clone());
+//! let max = builder.select(cond, a, b);
+//! builder.ret(max);
+//!
+//! assert_eq!(func.basic_blocks.len(), 1);
+//! assert_eq!(func.basic_blocks[0].name, Name::from(2));
+//! assert_eq!(func.basic_blocks[0].instrs.len(), 2);
+//! module.functions.push(func);
+//! ```
+//!
+//! Misuse of the builder (for instance, adding two operands of different
+//! types, or appending an instruction with no current block) results in a
+//! panic, naming the offending instruction and types.
+
+use crate::constant::{Constant, ConstantRef};
+use crate::function::{CallingConvention, Function, Parameter};
+use crate::instruction::*;
+use crate::module::{AddrSpace, Alignments};
+use crate::terminator::*;
+use crate::types::{Type, TypeRef, Types};
+use crate::{BasicBlock, FPPredicate, IntPredicate, Name, Operand};
+use either::Either;
+use std::fmt::Display;
+
+/// Appends instructions and terminators to a `Function`.
+///
+/// The builder has a current block (see [`position_at_end`](#method.position_at_end)),
+/// to which instructions are appended. Terminator methods (`br`, `ret`, etc)
+/// replace the current block's terminator, which for a block created with
+/// [`append_block`](#method.append_block) is initially `unreachable`.
+///
+/// Methods for instructions producing a value return a
+/// `Operand::LocalOperand` referring to the result, which can be used as an
+/// operand to later instructions.
+pub struct IRBuilder<'a> {
+    function: &'a mut Function,
+    types: &'a Types,
+    /// Index of the current block in `function.basic_blocks`
+    cur_block: Option<usize>,
+    /// The next `Name::Number` to hand out
+    next_number: usize,
+}
+
+macro_rules! int_binops {
+    ($($method:ident => $inst:ident { $($(#[$attr:meta])* $field:ident: $value:expr),* }),* $(,)?) => {
+        $(
+            #[doc = concat!("Append the `", stringify!($method), "` of two integer (or integer vector) operands of the same type.")]
+            pub fn $method(&mut self, operand0: Operand, operand1: Operand) -> Operand {
+                self.check_binop(stringify!($method), &operand0, &operand1, is_int);
+                let dest = self.fresh_name();
+                self.append_value($inst {
+                    operand0,
+                    operand1,
+                    dest,
+                    $($(#[$attr])* $field: $value,)*
+                    debugloc: None,
+                })
+            }
+        )*
+    };
+}
+
+macro_rules! fp_binops {
+    ($($method:ident => $inst:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Append the `", stringify!($method), "` of two floating-point (or floating-point vector) operands of the same type.")]
+            pub fn $method(&mut self, operand0: Operand, operand1: Operand) -> Operand {
+                self.check_binop(stringify!($method), &operand0, &operand1, is_fp);
+                let dest = self.fresh_name();
+                self.append_value($inst {
+                    operand0,
+                    operand1,
+                    dest,
+                    #[cfg(feature = "llvm-18-or-greater")]
+                    fast_math_flags: FastMathFlags::default(),
+                    debugloc: None,
+                })
+            }
+        )*
+    };
+}
+
+macro_rules! casts {
+    ($($method:ident => $inst:ident, $check:ident { $($(#[$attr:meta])* $field:ident: $value:expr),* }),* $(,)?) => {
+        $(
+            #[doc = concat!("Append a `", stringify!($method), "` of `operand` to `to_type`.")]
+            pub fn $method(&mut self, operand: Operand, to_type: TypeRef) -> Operand {
+                let from_type = self.types.type_of(&operand);
+                if !$check(&from_type, &to_type) {
+                    panic!(
+                        "IRBuilder::{}: can't cast {} to {}",
+                        stringify!($method),
+                        from_type,
+                        to_type,
+                    );
+                }
+                let dest = self.fresh_name();
+                self.append_value($inst {
+                    operand,
+                    to_type,
+                    dest,
+                    $($(#[$attr])* $field: $value,)*
+                    debugloc: None,
+                })
+            }
+        )*
+    };
+}
+
+impl<'a> IRBuilder<'a> {
+    /// Create a builder appending to `function`, using `types` to compute the
+    /// types of the values it creates (usually `&module.types`).
+    ///
+    /// Fresh names are numbered starting after the largest `Name::Number`
+    /// already used in `function`. The builder is initially positioned at the
+    /// end of the function's last block, if it has any.
+    pub fn new(function: &'a mut Function, types: &'a Types) -> Self {
+        let next_number = Self::max_number(function).map_or(0, |n| n + 1);
+        let cur_block = function.basic_blocks.len().checked_sub(1);
+        Self {
+            function,
+            types,
+            cur_block,
+            next_number,
+        }
+    }
+
+    fn max_number(function: &Function) -> Option<usize> {
+        let params = function.parameters.iter().map(|p| &p.name);
+        let blocks = function.basic_blocks.iter().flat_map(|bb| {
+            std::iter::once(&bb.name)
+                .chain(bb.instrs.iter().filter_map(|i| i.try_get_result()))
+                .chain(bb.term.try_get_result())
+        });
+        params
+            .chain(blocks)
+            .filter_map(|name| match name {
+                Name::Number(n) => Some(*n),
+                Name::Name(_) => None,
+            })
+            .max()
+    }
+
+    /// The `Function` being built
+    pub fn function(&self) -> &Function {
+        self.function
+    }
+
+    /// The `Types` used by this builder
+    pub fn types(&self) -> &'a Types {
+        self.types
+    }
+
+    /// Get a fresh `Name::Number`, not used anywhere else in the function
+    pub fn fresh_name(&mut self) -> Name {
+        let name = Name::Number(self.next_number);
+        self.next_number += 1;
+        name
+    }
+
+    /// Add a parameter of type `ty` to the function, with a fresh name, and
+    /// get an operand referring to it.
+    pub fn add_param(&mut self, ty: TypeRef) -> Operand {
+        let name = self.fresh_name();
+        self.function.parameters.push(Parameter {
+            name: name.clone(),
+            ty: ty.clone(),
+            attributes: vec![],
+        });
+        Operand::LocalOperand { name, ty }
+    }
+
+    /// Get an operand referring to the function's `index`th parameter.
+    ///
+    /// Panics if the function has no such parameter.
+    pub fn param(&self, index: usize) -> Operand {
+        let param = self.function.parameters.get(index).unwrap_or_else(|| {
+            panic!(
+                "IRBuilder::param: function {:?} has only {} parameters",
+                self.function.name,
+                self.function.parameters.len()
+            )
+        });
+        Operand::LocalOperand {
+            name: param.name.clone(),
+            ty: param.ty.clone(),
+        }
+    }
+
+    /// Get an operand for the integer constant `value` with the given number of bits
+    pub fn const_int(&self, bits: u32, value: u64) -> Operand {
+        Operand::ConstantOperand(ConstantRef::new(Constant::Int { bits, value }))
+    }
+
+    /// Append a new, empty block (terminated by `unreachable`) with a fresh
+    /// name to the function, and get its name.
+    ///
+    /// This does not move the builder; see [`position_at_end`](#method.position_at_end).
+    pub fn append_block(&mut self) -> Name {
+        let name = self.fresh_name();
+        self.append_named_block(name.clone());
+        name
+    }
+
+    /// Append a new, empty block (terminated by `unreachable`) with the given
+    /// name to the function.
+    ///
+    /// Panics if the function already has a block with that name.
+    pub fn append_named_block(&mut self, name: Name) {
+        if self.function.get_bb_by_name(&name).is_some() {
+            panic!(
+                "IRBuilder::append_named_block: function {:?} already has a block named {}",
+                self.function.name, name
+            );
+        }
+        self.function.basic_blocks.push(BasicBlock::new(name));
+    }
+
+    /// Position the builder at the end of the block with the given name, so
+    /// that subsequent instructions are appended to it.
+    ///
+    /// Panics if the function has no block with that name.
+    pub fn position_at_end(&mut self, block: &Name) {
+        let index = self
+            .function
+            .basic_blocks
+            .iter()
+            .position(|bb| &bb.name == block)
+            .unwrap_or_else(|| {
+                panic!(
+                    "IRBuilder::position_at_end: function {:?} has no block named {}",
+                    self.function.name, block
+                )
+            });
+        self.cur_block = Some(index);
+    }
+
+    /// The name of the block the builder is currently positioned in, if any
+    pub fn current_block(&self) -> Option<&Name> {
+        self.cur_block
+            .map(|index| &self.function.basic_blocks[index].name)
+    }
+
+    /// `what` is only formatted if the builder isn't positioned in a block
+    fn cur_block_mut(&mut self, what: &dyn Display) -> &mut BasicBlock {
+        match self.cur_block {
+            Some(index) => &mut self.function.basic_blocks[index],
+            None => panic!(
+                "IRBuilder::{}: builder isn't positioned in a block; call position_at_end() first",
+                what
+            ),
+        }
+    }
+
+    /// Append `inst` to the current block, and get an operand referring to its result
+    fn append_value(&mut self, inst: impl Into<Instruction>) -> Operand {
+        let inst = inst.into();
+        let ty = self.types.type_of(&inst);
+        let name = inst
+            .try_get_result()
+            .cloned()
+            .expect("append_value: instruction should have a result");
+        self.append(inst);
+        Operand::LocalOperand { name, ty }
+    }
+
+    fn append(&mut self, inst: impl Into<Instruction>) {
+        let inst = inst.into();
+        self.cur_block_mut(&inst).instrs.push(inst);
+    }
+
+    fn terminate(&mut self, term: impl Into<Terminator>) {
+        let term = term.into();
+        let block = self.cur_block_mut(&term);
+        block.term = term;
+    }
+
+    fn check_binop(
+        &self,
+        what: &str,
+        operand0: &Operand,
+        operand1: &Operand,
+        is_valid: fn(&Type) -> bool,
+    ) {
+        let ty0 = self.types.type_of(operand0);
+        let ty1 = self.types.type_of(operand1);
+        if ty0 != ty1 {
+            panic!(
+                "IRBuilder::{}: operands have different types: {} and {}",
+                what, ty0, ty1
+            );
+        }
+        if !is_valid(scalar_type(&ty0)) {
+            panic!("IRBuilder::{}: invalid operand type {}", what, ty0);
+        }
+    }
+
+    /// Check that `address` is a pointer, and (for LLVM 14 and lower) that it
+    /// points to `pointee`
+    fn check_pointer(&self, what: &str, address: &Operand, pointee: &TypeRef) {
+        let ty = self.types.type_of(address);
+        match ty.as_ref() {
+            #[cfg(feature = "llvm-14-or-lower")]
+            Type::PointerType { pointee_type, .. } => {
+                if pointee_type != pointee {
+                    panic!(
+                        "IRBuilder::{}: expected a pointer to {}, got {}",
+                        what, pointee, ty
+                    );
+                }
+            },
+            #[cfg(feature = "llvm-15-or-greater")]
+            Type::PointerType { .. } => {
+                let _ = pointee;
+            },
+            _ => panic!("IRBuilder::{}: expected a pointer, got {}", what, ty),
+        }
+    }
+
+    int_binops! {
+        add => Add {
+            #[cfg(feature = "llvm-17-or-greater")] nuw: false,
+            #[cfg(feature = "llvm-17-or-greater")] nsw: false
+        },
+        sub => Sub {
+            #[cfg(feature = "llvm-17-or-greater")] nuw: false,
+            #[cfg(feature = "llvm-17-or-greater")] nsw: false
+        },
+        mul => Mul {
+            #[cfg(feature = "llvm-17-or-greater")] nuw: false,
+            #[cfg(feature = "llvm-17-or-greater")] nsw: false
+        },
+        udiv => UDiv { #[cfg(feature = "llvm-17-or-greater")] exact: false },
+        sdiv => SDiv { #[cfg(feature = "llvm-17-or-greater")] exact: false },
+        urem => URem {},
+        srem => SRem {},
+        and => And {},
+        or => Or { #[cfg(feature = "llvm-18-or-greater")] disjoint: false },
+        xor => Xor {},
+        shl => Shl {
+            #[cfg(feature = "llvm-17-or-greater")] nuw: false,
+            #[cfg(feature = "llvm-17-or-greater")] nsw: false
+        },
+        lshr => LShr { #[cfg(feature = "llvm-17-or-greater")] exact: false },
+        ashr => AShr { #[cfg(feature = "llvm-17-or-greater")] exact: false },
+    }
+
+    fp_binops! {
+        fadd => FAdd,
+        fsub => FSub,
+        fmul => FMul,
+        fdiv => FDiv,
+        frem => FRem,
+    }
+
+    casts! {
+        trunc => Trunc, is_narrowing {
+            #[cfg(feature = "llvm-19-or-greater")] nuw: false,
+            #[cfg(feature = "llvm-19-or-greater")] nsw: false
+        },
+        zext => ZExt, is_widening { #[cfg(feature = "llvm-18-or-greater")] nneg: false },
+        sext => SExt, is_widening {},
+        ptrtoint => PtrToInt, is_ptr_to_int {},
+        inttoptr => IntToPtr, is_int_to_ptr {},
+        bitcast => BitCast, is_bitcastable {},
+    }
+
+    /// Append an `icmp` of two integer or pointer (or vector) operands of the same type.
+    pub fn icmp(
+        &mut self,
+        predicate: IntPredicate,
+        operand0: Operand,
+        operand1: Operand,
+    ) -> Operand {
+        self.check_binop("icmp", &operand0, &operand1, |ty| {
+            is_int(ty) || matches!(ty, Type::PointerType { .. })
+        });
+        let dest = self.fresh_name();
+        self.append_value(ICmp {
+            predicate,
+            operand0,
+            operand1,
+            dest,
+            debugloc: None,
+        })
+    }
+
+    /// Append an `fcmp` of two floating-point (or floating-point vector) operands of the same type.
+    pub fn fcmp(
+        &mut self,
+        predicate: FPPredicate,
+        operand0: Operand,
+        operand1: Operand,
+    ) -> Operand {
+        self.check_binop("fcmp", &operand0, &operand1, is_fp);
+        let dest = self.fresh_name();
+        self.append_value(FCmp {
+            predicate,
+            operand0,
+            operand1,
+            dest,
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::default(),
+            debugloc: None,
+        })
+    }
+
+    /// Append a `select` between two values of the same type, on an `i1` condition.
+    pub fn select(
+        &mut self,
+        condition: Operand,
+        true_value: Operand,
+        false_value: Operand,
+    ) -> Operand {
+        let cond_ty = self.types.type_of(&condition);
+        if *scalar_type(&cond_ty) != (Type::IntegerType { bits: 1 }) {
+            panic!(
+                "IRBuilder::select: expected an i1 condition, got {}",
+                cond_ty
+            );
+        }
+        let true_ty = self.types.type_of(&true_value);
+        let false_ty = self.types.type_of(&false_value);
+        if true_ty != false_ty {
+            panic!(
+                "IRBuilder::select: values have different types: {} and {}",
+                true_ty, false_ty
+            );
+        }
+        let dest = self.fresh_name();
+        self.append_value(Select {
+            condition,
+            true_value,
+            false_value,
+            dest,
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::default(),
+            debugloc: None,
+        })
+    }
+
+    /// Append an `alloca` of a single value of type `allocated_type`.
+    pub fn alloca(&mut self, allocated_type: TypeRef, alignment: u32) -> Operand {
+        let dest = self.fresh_name();
+        self.append_value(Alloca {
+            allocated_type,
+            num_elements: self.const_int(32, 1),
+            dest,
+            alignment,
+            debugloc: None,
+        })
+    }
+
+    /// Append a (non-volatile, non-atomic) `load` of a value of type `ty` from `address`.
+    pub fn load(&mut self, ty: TypeRef, address: Operand, alignment: u32) -> Operand {
+        self.check_pointer("load", &address, &ty);
+        let dest = self.fresh_name();
+        self.append_value(Load {
+            address,
+            dest,
+            #[cfg(feature = "llvm-15-or-greater")]
+            loaded_ty: ty,
+            volatile: false,
+            atomicity: None,
+            alignment,
+            debugloc: None,
+        })
+    }
+
+    /// Append a (non-volatile, non-atomic) `store` of `value` to `address`.
+    pub fn store(&mut self, value: Operand, address: Operand, alignment: u32) {
+        let ty = self.types.type_of(&value);
+        self.check_pointer("store", &address, &ty);
+        self.append(Store {
+            address,
+            value,
+            volatile: false,
+            atomicity: None,
+            alignment,
+            debugloc: None,
+        })
+    }
+
+    /// Append a `getelementptr` indexing into a value of type
+    /// `source_element_type` at `address`.
+    pub fn gep(
+        &mut self,
+        source_element_type: TypeRef,
+        address: Operand,
+        indices: Vec<Operand>,
+    ) -> Operand {
+        self.gep_impl(source_element_type, address, indices, false)
+    }
+
+    /// Append a `getelementptr inbounds`; see [`gep`](#method.gep).
+    pub fn inbounds_gep(
+        &mut self,
+        source_element_type: TypeRef,
+        address: Operand,
+        indices: Vec<Operand>,
+    ) -> Operand {
+        self.gep_impl(source_element_type, address, indices, true)
+    }
+
+    fn gep_impl(
+        &mut self,
+        source_element_type: TypeRef,
+        address: Operand,
+        indices: Vec<Operand>,
+        in_bounds: bool,
+    ) -> Operand {
+        self.check_pointer("gep", &address, &source_element_type);
+        for index in &indices {
+            let ty = self.types.type_of(index);
+            if !is_int(scalar_type(&ty)) {
+                panic!("IRBuilder::gep: expected an integer index, got {}", ty);
+            }
+        }
+        let dest = self.fresh_name();
+        self.append_value(GetElementPtr {
+            address,
+            indices,
+            dest,
+            in_bounds,
+            // implied by `inbounds`
+            #[cfg(feature = "llvm-19-or-greater")]
+            nusw: in_bounds,
+            #[cfg(feature = "llvm-19-or-greater")]
+            nuw: false,
+            debugloc: None,
+            #[cfg(feature = "llvm-14-or-greater")]
+            source_element_type,
+        })
+    }
+
+    /// Append a `call` of `function`, which has the function type `function_ty`.
+    ///
+    /// Returns `None` if the function returns void.
+    pub fn call(
+        &mut self,
+        function_ty: TypeRef,
+        function: Operand,
+        arguments: Vec<Operand>,
+    ) -> Option<Operand> {
+        let (result_type, param_types, is_var_arg) = match function_ty.as_ref() {
+            Type::FuncType {
+                result_type,
+                param_types,
+                is_var_arg,
+            } => (result_type, param_types, *is_var_arg),
+            _ => panic!(
+                "IRBuilder::call: expected a function type, got {}",
+                function_ty
+            ),
+        };
+        self.check_pointer("call", &function, &function_ty);
+        if arguments.len() < param_types.len()
+            || (!is_var_arg && arguments.len() > param_types.len())
+        {
+            panic!(
+                "IRBuilder::call: function of type {} called with {} arguments",
+                function_ty,
+                arguments.len()
+            );
+        }
+        for (arg, param_ty) in arguments.iter().zip(param_types) {
+            let arg_ty = self.types.type_of(arg);
+            if &arg_ty != param_ty {
+                panic!(
+                    "IRBuilder::call: expected an argument of type {}, got {}",
+                    param_ty, arg_ty
+                );
+            }
+        }
+        let dest = match result_type.as_ref() {
+            Type::VoidType => None,
+            _ => Some(self.fresh_name()),
+        };
+        let call = Call {
+            function: Either::Right(function),
+            #[cfg(feature = "llvm-15-or-greater")]
+            function_ty: function_ty.clone(),
+            arguments: arguments.into_iter().map(|arg| (arg, vec![])).collect(),
+            return_attributes: vec![],
+            dest,
+            function_attributes: vec![],
+            is_tail_call: false,
+            #[cfg(feature = "llvm-18-or-greater")]
+            tail_call_kind: None,
+            calling_convention: CallingConvention::C,
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::default(),
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles: vec![],
+            debugloc: None,
+        };
+        if call.dest.is_some() {
+            Some(self.append_value(call))
+        } else {
+            self.append(call);
+            None
+        }
+    }
+
+    /// Append a `phi` of type `ty` to the current block, with the given
+    /// incoming values and the blocks they come from.
+    ///
+    /// The `phi` is placed after any other `phi`s at the start of the block,
+    /// rather than at the end of the block. More incoming values can be added
+    /// later with [`add_incoming`](#method.add_incoming).
+    pub fn phi(&mut self, ty: TypeRef, incoming_values: Vec<(Operand, Name)>) -> Operand {
+        for (value, _) in &incoming_values {
+            self.check_incoming(value, &ty);
+        }
+        let dest = self.fresh_name();
+        let phi: Instruction = Phi {
+            incoming_values,
+            dest: dest.clone(),
+            to_type: ty.clone(),
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::default(),
+            debugloc: None,
+        }
+        .into();
+        let instrs = &mut self.cur_block_mut(&phi).instrs;
+        let index = instrs
+            .iter()
+            .position(|inst| !matches!(inst, Instruction::Phi(_)))
+            .unwrap_or(instrs.len());
+        instrs.insert(index, phi);
+        Operand::LocalOperand { name: dest, ty }
+    }
+
+    /// Add an incoming value from `block` to the `phi` previously created by
+    /// [`phi`](#method.phi).
+    ///
+    /// This is useful for loops, where the incoming value along the back edge
+    /// is only computed after the `phi`.
+    pub fn add_incoming(&mut self, phi: &Operand, value: Operand, block: Name) {
+        let name = match phi {
+            Operand::LocalOperand { name, .. } => name,
+            _ => panic!("IRBuilder::add_incoming: expected a phi, got {}", phi),
+        };
+        let phi = self
+            .function
+            .basic_blocks
+            .iter_mut()
+            .flat_map(|bb| bb.instrs.iter_mut())
+            .find_map(|inst| match inst {
+                Instruction::Phi(phi) if &phi.dest == name => Some(phi),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("IRBuilder::add_incoming: no phi named {}", name));
+        let ty = phi.to_type.clone();
+        let value_ty = self.types.type_of(&value);
+        if value_ty != ty {
+            panic!(
+                "IRBuilder::add_incoming: expected an incoming value of type {}, got {}",
+                ty, value_ty
+            );
+        }
+        phi.incoming_values.push((value, block));
+    }
+
+    fn check_incoming(&self, value: &Operand, ty: &TypeRef) {
+        let value_ty = self.types.type_of(value);
+        if &value_ty != ty {
+            panic!(
+                "IRBuilder::phi: expected an incoming value of type {}, got {}",
+                ty, value_ty
+            );
+        }
+    }
+
+    /// Terminate the current block with `ret` of `value`.
+    pub fn ret(&mut self, value: Operand) {
+        let ty = self.types.type_of(&value);
+        if ty != self.function.return_type {
+            panic!(
+                "IRBuilder::ret: function {:?} returns {}, not {}",
+                self.function.name, self.function.return_type, ty
+            );
+        }
+        self.terminate(Ret {
+            return_operand: Some(value),
+            debugloc: None,
+        })
+    }
+
+    /// Terminate the current block with `ret void`.
+    pub fn ret_void(&mut self) {
+        if *self.function.return_type != Type::VoidType {
+            panic!(
+                "IRBuilder::ret_void: function {:?} returns {}",
+                self.function.name, self.function.return_type
+            );
+        }
+        self.terminate(Ret {
+            return_operand: None,
+            debugloc: None,
+        })
+    }
+
+    /// Terminate the current block with an unconditional branch to `dest`.
+    pub fn br(&mut self, dest: Name) {
+        self.terminate(Br {
+            dest,
+            debugloc: None,
+        })
+    }
+
+    /// Terminate the current block with a branch on the `i1` `condition`.
+    pub fn cond_br(&mut self, condition: Operand, true_dest: Name, false_dest: Name) {
+        let ty = self.types.type_of(&condition);
+        if *ty != (Type::IntegerType { bits: 1 }) {
+            panic!("IRBuilder::cond_br: expected an i1 condition, got {}", ty);
+        }
+        self.terminate(CondBr {
+            condition,
+            true_dest,
+            false_dest,
+            debugloc: None,
+        })
+    }
+
+    /// Terminate the current block with a `switch` on the integer `operand`.
+    pub fn switch(
+        &mut self,
+        operand: Operand,
+        default_dest: Name,
+        dests: Vec<(ConstantRef, Name)>,
+    ) {
+        let ty = self.types.type_of(&operand);
+        if !is_int(&ty) {
+            panic!("IRBuilder::switch: expected an integer operand, got {}", ty);
+        }
+        for (value, _) in &dests {
+            let value_ty = self.types.type_of(value);
+            if value_ty != ty {
+                panic!(
+                    "IRBuilder::switch: expected a case value of type {}, got {}",
+                    ty, value_ty
+                );
+            }
+        }
+        self.terminate(Switch {
+            operand,
+            dests,
+            default_dest,
+            debugloc: None,
+        })
+    }
+
+    /// Terminate the current block with `unreachable`.
+    pub fn unreachable(&mut self) {
+        self.terminate(Unreachable { debugloc: None })
+    }
+}
+
+/// For vector types, the element type; otherwise, the type itself
+pub(crate) fn scalar_type(ty: &TypeRef) -> &Type {
+    match ty.as_ref() {
+        Type::VectorType { element_type, .. } => element_type.as_ref(),
+        ty => ty,
+    }
+}
+
+pub(crate) fn is_int(ty: &Type) -> bool {
+    matches!(ty, Type::IntegerType { .. })
+}
+
+pub(crate) fn is_fp(ty: &Type) -> bool {
+    matches!(ty, Type::FPType(_))
+}
+
+fn num_elements(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::VectorType { num_elements, .. } => Some(*num_elements),
+        _ => None,
+    }
+}
+
+fn int_bits(ty: &TypeRef) -> Option<u32> {
+    match scalar_type(ty) {
+        Type::IntegerType { bits } => Some(*bits),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_narrowing(from: &TypeRef, to: &TypeRef) -> bool {
+    num_elements(from) == num_elements(to)
+        && matches!((int_bits(from), int_bits(to)), (Some(f), Some(t)) if f > t)
+}
+
+pub(crate) fn is_widening(from: &TypeRef, to: &TypeRef) -> bool {
+    num_elements(from) == num_elements(to)
+        && matches!((int_bits(from), int_bits(to)), (Some(f), Some(t)) if f < t)
+}
+
+pub(crate) fn is_ptr_to_int(from: &TypeRef, to: &TypeRef) -> bool {
+    num_elements(from) == num_elements(to)
+        && matches!(scalar_type(from), Type::PointerType { .. })
+        && is_int(scalar_type(to))
+}
+
+pub(crate) fn is_int_to_ptr(from: &TypeRef, to: &TypeRef) -> bool {
+    is_ptr_to_int(to, from)
+}
+
+fn fp_bits(ty: &TypeRef) -> Option<u32> {
+    match scalar_type(ty) {
+        Type::FPType(fpt) => Some(Alignments::fpt_size(*fpt)),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_fp_narrowing(from: &TypeRef, to: &TypeRef) -> bool {
+    num_elements(from) == num_elements(to)
+        && matches!((fp_bits(from), fp_bits(to)), (Some(f), Some(t)) if f > t)
+}
+
+pub(crate) fn is_fp_widening(from: &TypeRef, to: &TypeRef) -> bool {
+    num_elements(from) == num_elements(to)
+        && matches!((fp_bits(from), fp_bits(to)), (Some(f), Some(t)) if f < t)
+}
+
+pub(crate) fn is_fp_to_int(from: &TypeRef, to: &TypeRef) -> bool {
+    num_elements(from) == num_elements(to) && is_fp(scalar_type(from)) && is_int(scalar_type(to))
+}
+
+pub(crate) fn is_int_to_fp(from: &TypeRef, to: &TypeRef) -> bool {
+    is_fp_to_int(to, from)
+}
+
+pub(crate) fn is_addr_space_cast(from: &TypeRef, to: &TypeRef) -> bool {
+    num_elements(from) == num_elements(to)
+        && match (scalar_type(from), scalar_type(to)) {
+            (Type::PointerType { addr_space: f, .. }, Type::PointerType { addr_space: t, .. }) => {
+                f != t
+            },
+            _ => false,
+        }
+}
+
+/// Size in bits of an integer or floating-point type, or a vector of those
+fn primitive_bits(ty: &TypeRef) -> Option<u64> {
+    match ty.as_ref() {
+        Type::IntegerType { bits } => Some(u64::from(*bits)),
+        Type::FPType(fpt) => Some(u64::from(Alignments::fpt_size(*fpt))),
+        Type::VectorType {
+            element_type,
+            num_elements,
+            ..
+        } => primitive_bits(element_type).map(|bits| bits * *num_elements as u64),
+        _ => None,
+    }
+}
+
+/// For pointer types (or vectors of pointers), the address space
+fn addr_space(ty: &TypeRef) -> Option<AddrSpace> {
+    match scalar_type(ty) {
+        Type::PointerType { addr_space, .. } => Some(*addr_space),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_bitcastable(from: &TypeRef, to: &TypeRef) -> bool {
+    match (addr_space(from), addr_space(to)) {
+        (Some(f), Some(t)) => f == t && num_elements(from) == num_elements(to),
+        (None, None) => {
+            matches!((primitive_bits(from), primitive_bits(to)), (Some(f), Some(t)) if f == t)
+        },
+        _ => false,
+    }
+}
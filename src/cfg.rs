@@ -0,0 +1,238 @@
+//! Control-flow graph and dominator tree of a `Function`.
+//!
+//! Both are [`FunctionAnalysis`](../pass/trait.FunctionAnalysis.html)es, so
+//! passes can get them from the
+//! [`AnalysisManager`](../pass/struct.AnalysisManager.html) instead of
+//! recomputing them.
+//!
+//! Blocks are identified by their index in `Function::basic_blocks`; the entry
+//! block is index 0.
+
+use crate::function::Function;
+use crate::name::Name;
+use crate::pass::{AnalysisManager, FunctionAnalysis};
+use crate::types::Types;
+use std::collections::{BTreeSet, HashMap};
+
+/// Successors and predecessors of each block of a `Function`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ControlFlowGraph {
+    /// Map from block name to block index
+    indices: HashMap<Name, usize>,
+    /// Successors of each block, with duplicates (one per edge). Successor
+    /// names which aren't blocks of the function are left out.
+    succs: Vec<Vec<usize>>,
+    /// Predecessors of each block, with duplicates (one per edge)
+    preds: Vec<Vec<usize>>,
+}
+
+impl ControlFlowGraph {
+    pub fn new(func: &Function) -> Self {
+        let indices: HashMap<Name, usize> = func
+            .basic_blocks
+            .iter()
+            .enumerate()
+            .map(|(i, bb)| (bb.name.clone(), i))
+            .collect();
+        let succs: Vec<Vec<usize>> = func
+            .basic_blocks
+            .iter()
+            .map(|bb| {
+                bb.term
+                    .successors()
+                    .into_iter()
+                    .filter_map(|name| indices.get(name).copied())
+                    .collect()
+            })
+            .collect();
+        let mut preds = vec![vec![]; succs.len()];
+        for (from, tos) in succs.iter().enumerate() {
+            for &to in tos {
+                preds[to].push(from);
+            }
+        }
+        Self {
+            indices,
+            succs,
+            preds,
+        }
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.succs.len()
+    }
+
+    /// Index of the block with the given name, if the function has one
+    pub fn index_of(&self, name: &Name) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Successors of `block`, with duplicates (one per edge)
+    pub fn succs(&self, block: usize) -> &[usize] {
+        &self.succs[block]
+    }
+
+    /// Predecessors of `block`, with duplicates (one per edge)
+    pub fn preds(&self, block: usize) -> &[usize] {
+        &self.preds[block]
+    }
+
+    /// Blocks reachable from the entry, in reverse postorder
+    pub fn reverse_postorder(&self) -> Vec<usize> {
+        let mut postorder = Vec::with_capacity(self.num_blocks());
+        if self.num_blocks() == 0 {
+            return postorder;
+        }
+        let mut visited = vec![false; self.num_blocks()];
+        // stack of (block, index of the next successor to visit)
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((block, next)) = stack.last_mut() {
+            match self.succs[*block].get(*next) {
+                Some(&succ) => {
+                    *next += 1;
+                    if !visited[succ] {
+                        visited[succ] = true;
+                        stack.push((succ, 0));
+                    }
+                },
+                None => {
+                    postorder.push(*block);
+                    stack.pop();
+                },
+            }
+        }
+        postorder.reverse();
+        postorder
+    }
+}
+
+impl FunctionAnalysis for ControlFlowGraph {
+    type Result = Self;
+
+    fn run(function: &Function, _: &Types, _: &mut AnalysisManager) -> Self {
+        Self::new(function)
+    }
+}
+
+/// Dominator tree, computed with the algorithm from Cooper, Harvey and
+/// Kennedy, "A Simple, Fast Dominance Algorithm"
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct DominatorTree {
+    /// Immediate dominator of each block; `None` for the entry block and for
+    /// unreachable blocks
+    idoms: Vec<Option<usize>>,
+    /// Position of each reachable block in reverse postorder
+    rpo_numbers: Vec<Option<usize>>,
+}
+
+impl DominatorTree {
+    pub fn new(cfg: &ControlFlowGraph) -> Self {
+        let rpo = cfg.reverse_postorder();
+        let mut rpo_numbers = vec![None; cfg.num_blocks()];
+        for (i, &block) in rpo.iter().enumerate() {
+            rpo_numbers[block] = Some(i);
+        }
+        let mut idoms: Vec<Option<usize>> = vec![None; cfg.num_blocks()];
+        if let Some(&entry) = rpo.first() {
+            idoms[entry] = Some(entry);
+        }
+        let intersect = |idoms: &[Option<usize>], mut a: usize, mut b: usize| {
+            while a != b {
+                while rpo_numbers[a] > rpo_numbers[b] {
+                    a = idoms[a].expect("processed blocks have an idom");
+                }
+                while rpo_numbers[b] > rpo_numbers[a] {
+                    b = idoms[b].expect("processed blocks have an idom");
+                }
+            }
+            a
+        };
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in rpo.iter().skip(1) {
+                let new_idom = cfg
+                    .preds(block)
+                    .iter()
+                    .filter(|&&pred| idoms[pred].is_some())
+                    .fold(None, |idom, &pred| match idom {
+                        None => Some(pred),
+                        Some(idom) => Some(intersect(&idoms, idom, pred)),
+                    });
+                if new_idom.is_some() && idoms[block] != new_idom {
+                    idoms[block] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        if let Some(&entry) = rpo.first() {
+            idoms[entry] = None;
+        }
+        Self { idoms, rpo_numbers }
+    }
+
+    /// Children of each block in the dominator tree
+    pub fn children(&self) -> Vec<Vec<usize>> {
+        let mut children = vec![vec![]; self.idoms.len()];
+        for (block, idom) in self.idoms.iter().enumerate() {
+            if let Some(idom) = idom {
+                children[*idom].push(block);
+            }
+        }
+        children
+    }
+
+    /// Dominance frontier of each block, computed as in Cooper, Harvey and
+    /// Kennedy. Unreachable blocks have empty frontiers and aren't in any.
+    pub fn dominance_frontiers(&self, cfg: &ControlFlowGraph) -> Vec<BTreeSet<usize>> {
+        let mut frontiers = vec![BTreeSet::new(); self.idoms.len()];
+        for block in 0 .. self.idoms.len() {
+            let preds: BTreeSet<usize> = cfg
+                .preds(block)
+                .iter()
+                .copied()
+                .filter(|&pred| self.is_reachable(pred))
+                .collect();
+            if preds.len() < 2 || !self.is_reachable(block) {
+                continue;
+            }
+            for pred in preds {
+                let mut runner = Some(pred);
+                while let Some(r) = runner {
+                    if Some(r) == self.idoms[block] {
+                        break;
+                    }
+                    frontiers[r].insert(block);
+                    runner = self.idoms[r];
+                }
+            }
+        }
+        frontiers
+    }
+
+    /// Is `block` reachable from the entry block?
+    pub fn is_reachable(&self, block: usize) -> bool {
+        self.rpo_numbers[block].is_some()
+    }
+
+    /// Does `a` dominate `b`? Every block dominates itself.
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        let mut cur = Some(b);
+        while let Some(block) = cur {
+            if block == a {
+                return true;
+            }
+            cur = self.idoms[block];
+        }
+        false
+    }
+}
+
+impl FunctionAnalysis for DominatorTree {
+    type Result = Self;
+
+    fn run(function: &Function, types: &Types, analyses: &mut AnalysisManager) -> Self {
+        Self::new(&analyses.get::<ControlFlowGraph>(function, types))
+    }
+}
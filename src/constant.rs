@@ -52,8 +52,12 @@ pub enum Constant {
     #[cfg(feature = "llvm-12-or-greater")]
     Poison(TypeRef),
     /// The address of the given (non-entry) [`BasicBlock`](../struct.BasicBlock.html). See [LLVM 14 docs on Addresses of Basic Blocks](https://releases.llvm.org/14.0.0/docs/LangRef.html#addresses-of-basic-blocks).
-    /// `BlockAddress` needs more fields, but the necessary getter functions are apparently not exposed in the LLVM C API (only the C++ API)
-    BlockAddress, // --TODO ideally we want BlockAddress { function: Name, block: Name },
+    BlockAddress {
+        /// Name of the function containing the block
+        function: Name,
+        /// Name of the block within that function
+        block: Name,
+    },
     /// Global variable or function
     GlobalReference {
         name: Name,
@@ -155,30 +159,115 @@ pub enum Constant {
     Select(Select),
 }
 
-/// All of these `Float` variants should have data associated with them, but
-/// Rust only has `f32` and `f64` floating-point types, and furthermore,
-/// it's not clear how to get 16-, 80-, or 128-bit FP constant values through
-/// the LLVM C API (the getters seem to only be exposed in the C++ API?)
+/// Floating-point constant values.
+///
+/// `Single` and `Double` hold the value directly. The other formats have no
+/// native Rust type, so they hold the exact bit pattern of the value instead;
+/// use `to_f64()` for an (in general lossy) numeric approximation.
 #[derive(PartialEq, Clone, Debug)]
 #[allow(non_camel_case_types)]
 pub enum Float {
-    Half, // TODO perhaps Half(u16)
+    /// Bit pattern of an IEEE 754 binary16 value
+    Half(u16),
+    /// Bit pattern of a bfloat16 value (the upper 16 bits of an `f32`)
     #[cfg(feature = "llvm-11-or-greater")]
-    BFloat, // TODO perhaps BFloat(u16)
+    BFloat(u16),
     Single(f32),
     Double(f64),
-    Quadruple, // TODO perhaps Quadruple(u128)
-    X86_FP80,  // TODO perhaps X86_FP80((u16, u64)) with the most-significant bits on the left
-    PPC_FP128, // TODO perhaps PPC_FP128((u64, u64)) with the most-significant bits on the left
+    /// Bit pattern of an IEEE 754 binary128 value
+    Quadruple(u128),
+    /// Bit pattern of an x87 80-bit extended-precision value, with the
+    /// most-significant bits on the left: the sign and 15-bit exponent, then
+    /// the 64-bit significand (including its explicit integer bit)
+    X86_FP80(u16, u64),
+    /// Bit patterns of the two `f64`s of a PowerPC double-double value, whose
+    /// sum is the value: first the low-order `f64`, then the high-order one.
+    /// LLVM's own 128-bit representation (as printed after `0xM`) has the
+    /// high-order `f64` in its low-order bits.
+    PPC_FP128(u64, u64),
+}
+
+impl Float {
+    /// Convert the value to an `f64`.
+    ///
+    /// This is exact for `Single`, `Double`, `Half`, and `BFloat`, but rounds
+    /// (or overflows to infinity, or underflows to zero) for the wider formats.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Float::Half(bits) => {
+                let sign = if bits >> 15 == 1 { -1.0 } else { 1.0 };
+                let exp = i32::from((bits >> 10) & 0x1f);
+                let mantissa = bits & 0x3ff;
+                match exp {
+                    0 => sign * ldexp(f64::from(mantissa), 1 - 15 - 10),
+                    0x1f if mantissa == 0 => sign * f64::INFINITY,
+                    0x1f => f64::NAN,
+                    _ => sign * ldexp(f64::from(0x400 | mantissa), exp - 15 - 10),
+                }
+            },
+            #[cfg(feature = "llvm-11-or-greater")]
+            Float::BFloat(bits) => f64::from(f32::from_bits(u32::from(*bits) << 16)),
+            Float::Single(f) => f64::from(*f),
+            Float::Double(d) => *d,
+            Float::Quadruple(bits) => {
+                let sign = if bits >> 127 == 1 { -1.0 } else { 1.0 };
+                let exp = ((bits >> 112) & 0x7fff) as i32;
+                let mantissa = bits & ((1 << 112) - 1);
+                match exp {
+                    0 => sign * ldexp(mantissa as f64, 1 - 16383 - 112),
+                    0x7fff if mantissa == 0 => sign * f64::INFINITY,
+                    0x7fff => f64::NAN,
+                    _ => sign * ldexp(((1 << 112) | mantissa) as f64, exp - 16383 - 112),
+                }
+            },
+            Float::X86_FP80(sign_exp, significand) => {
+                let sign = if sign_exp >> 15 == 1 { -1.0 } else { 1.0 };
+                let exp = i32::from(sign_exp & 0x7fff);
+                match exp {
+                    0x7fff if significand << 1 == 0 => sign * f64::INFINITY,
+                    0x7fff => f64::NAN,
+                    _ => sign * ldexp(*significand as f64, exp.max(1) - 16383 - 63),
+                }
+            },
+            Float::PPC_FP128(lo_double, hi_double) => {
+                f64::from_bits(*hi_double) + f64::from_bits(*lo_double)
+            },
+        }
+    }
+}
+
+/// Compute `x * 2^exp`, without overflowing or underflowing in intermediate
+/// steps when `exp` is outside the range of `f64` exponents
+fn ldexp(mut x: f64, mut exp: i32) -> f64 {
+    while exp > 1023 {
+        x *= 2f64.powi(1023);
+        exp -= 1023;
+    }
+    while exp < -1022 {
+        x *= 2f64.powi(-1022);
+        exp += 1022;
+    }
+    x * 2f64.powi(exp)
 }
 
 impl std::hash::Hash for Float {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
         match self {
+            Float::Half(bits) => bits.hash(state),
+            #[cfg(feature = "llvm-11-or-greater")]
+            Float::BFloat(bits) => bits.hash(state),
             Float::Single(f) => ordered_float::OrderedFloat(*f).hash(state),
             Float::Double(f) => ordered_float::OrderedFloat(*f).hash(state),
-            _ => {},
+            Float::Quadruple(bits) => bits.hash(state),
+            Float::X86_FP80(sign_exp, significand) => {
+                sign_exp.hash(state);
+                significand.hash(state);
+            },
+            Float::PPC_FP128(lo_double, hi_double) => {
+                lo_double.hash(state);
+                hi_double.hash(state);
+            },
         }
     }
 }
@@ -186,14 +275,14 @@ impl std::hash::Hash for Float {
 impl Typed for Float {
     fn get_type(&self, types: &Types) -> TypeRef {
         types.fp(match self {
-            Float::Half => FPType::Half,
+            Float::Half(_) => FPType::Half,
             #[cfg(feature = "llvm-11-or-greater")]
-            Float::BFloat => FPType::BFloat,
+            Float::BFloat(_) => FPType::BFloat,
             Float::Single(_) => FPType::Single,
             Float::Double(_) => FPType::Double,
-            Float::Quadruple => FPType::FP128,
-            Float::X86_FP80 => FPType::X86_FP80,
-            Float::PPC_FP128 => FPType::PPC_FP128,
+            Float::Quadruple(_) => FPType::FP128,
+            Float::X86_FP80(..) => FPType::X86_FP80,
+            Float::PPC_FP128(..) => FPType::PPC_FP128,
         })
     }
 }
@@ -201,14 +290,26 @@ impl Typed for Float {
 impl Display for Float {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Float::Half => write!(f, "half"),
+            // these use the same hex syntax as LLVM's own printer, which
+            // always prints these formats as hex
+            Float::Half(bits) => write!(f, "half 0xH{:04X}", bits),
             #[cfg(feature = "llvm-11-or-greater")]
-            Float::BFloat => write!(f, "bfloat"),
+            Float::BFloat(bits) => write!(f, "bfloat 0xR{:04X}", bits),
             Float::Single(s) => write!(f, "float {}", s),
             Float::Double(d) => write!(f, "double {}", d),
-            Float::Quadruple => write!(f, "quadruple"),
-            Float::X86_FP80 => write!(f, "x86_fp80"),
-            Float::PPC_FP128 => write!(f, "ppc_fp128"),
+            // LLVM prints the low 64 bits of fp128 and ppc_fp128 first
+            Float::Quadruple(bits) => write!(
+                f,
+                "fp128 0xL{:016X}{:016X}",
+                *bits as u64,
+                (bits >> 64) as u64
+            ),
+            Float::X86_FP80(sign_exp, significand) => {
+                write!(f, "x86_fp80 0xK{:04X}{:016X}", sign_exp, significand)
+            },
+            Float::PPC_FP128(lo_double, hi_double) => {
+                write!(f, "ppc_fp128 0xM{:016X}{:016X}", hi_double, lo_double)
+            },
         }
     }
 }
@@ -243,7 +344,10 @@ impl Typed for Constant {
             Constant::Undef(t) => t.clone(),
             #[cfg(feature="llvm-12-or-greater")]
             Constant::Poison(t) => t.clone(),
-            Constant::BlockAddress { .. } => types.label_type(),
+            #[cfg(feature="llvm-14-or-lower")]
+            Constant::BlockAddress { .. } => types.pointer_to(types.i8()),
+            #[cfg(feature="llvm-15-or-greater")]
+            Constant::BlockAddress { .. } => types.pointer(),
             #[cfg(feature="llvm-14-or-lower")]
             Constant::GlobalReference { ty, .. } => types.pointer_to(ty.clone()),
             #[cfg(feature="llvm-15-or-greater")]
@@ -413,7 +517,13 @@ impl Display for Constant {
             Constant::Undef(ty) => write!(f, "{} undef", ty),
             #[cfg(feature = "llvm-12-or-greater")]
             Constant::Poison(ty) => write!(f, "{} poison", ty),
-            Constant::BlockAddress => write!(f, "blockaddr"),
+            Constant::BlockAddress { function, block } => {
+                let function = match function {
+                    Name::Name(n) => String::clone(n),
+                    Name::Number(n) => n.to_string(),
+                };
+                write!(f, "blockaddress(@{}, {})", function, block)
+            },
             Constant::GlobalReference { name, ty } => {
                 let name = match name {
                     Name::Name(n) => String::clone(n),
@@ -1012,28 +1122,44 @@ impl_constexpr!(ExtractValue, ExtractValue);
 #[cfg(feature = "llvm-14-or-lower")]
 impl Typed for ExtractValue {
     fn get_type(&self, types: &Types) -> TypeRef {
-        ev_type(types.type_of(&self.aggregate), self.indices.iter().copied())
+        ev_type(
+            types.type_of(&self.aggregate),
+            self.indices.iter().copied(),
+            types,
+        )
     }
 }
 
 #[cfg(feature = "llvm-14-or-lower")]
-fn ev_type(cur_type: TypeRef, mut indices: impl Iterator<Item = u32>) -> TypeRef {
-    match indices.next() {
-        None => cur_type,
-        Some(index) => match cur_type.as_ref() {
-            Type::ArrayType { element_type, .. } => ev_type(element_type.clone(), indices),
-            Type::StructType { element_types, .. } => ev_type(
-                element_types
-                    .get(index as usize)
-                    .expect("ExtractValue index out of range")
-                    .clone(),
-                indices,
-            ),
-            _ => panic!(
-                "ExtractValue from something that's not ArrayType or StructType; its type is {:?}",
-                cur_type
-            ),
+fn ev_type(cur_type: TypeRef, mut indices: impl Iterator<Item = u32>, types: &Types) -> TypeRef {
+    let index = match indices.next() {
+        None => return cur_type,
+        Some(index) => index,
+    };
+    let cur_type = match cur_type.as_ref() {
+        Type::NamedStructType { name } => match types.named_struct_def(name) {
+            None => panic!("Named struct without a definition (name {:?})", name),
+            Some(NamedStructDef::Opaque) => {
+                panic!("ExtractValue from an opaque struct type (name {:?})", name)
+            },
+            Some(NamedStructDef::Defined(ty)) => ty.clone(),
         },
+        _ => cur_type,
+    };
+    match cur_type.as_ref() {
+        Type::ArrayType { element_type, .. } => ev_type(element_type.clone(), indices, types),
+        Type::StructType { element_types, .. } => ev_type(
+            element_types
+                .get(index as usize)
+                .expect("ExtractValue index out of range")
+                .clone(),
+            indices,
+            types,
+        ),
+        _ => panic!(
+            "ExtractValue from something that's not ArrayType, StructType, or NamedStructType; its type is {:?}",
+            cur_type
+        ),
     }
 }
 
@@ -1084,6 +1210,16 @@ pub struct GetElementPtr {
     pub address: ConstantRef,
     pub indices: Vec<ConstantRef>,
     pub in_bounds: bool,
+    /// "No unsigned signed wrap". Implied by `in_bounds`.
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub nusw: bool,
+    /// "No unsigned wrap"
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub nuw: bool,
+    /// If present, the half-open range `[start, end)` of offsets (relative to
+    /// the result pointer) which may be accessed through the result pointer.
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub inrange: Option<(i64, i64)>,
 }
 
 impl_constexpr!(GetElementPtr, GetElementPtr);
@@ -1145,12 +1281,23 @@ fn gep_type<'c>(
 
 impl Display for GetElementPtr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "getelementptr{} ({}",
-            if self.in_bounds { " inbounds" } else { "" },
-            &self.address
-        )?;
+        write!(f, "getelementptr")?;
+        if self.in_bounds {
+            write!(f, " inbounds")?;
+        }
+        #[cfg(feature = "llvm-19-or-greater")]
+        {
+            if self.nusw && !self.in_bounds {
+                write!(f, " nusw")?;
+            }
+            if self.nuw {
+                write!(f, " nuw")?;
+            }
+            if let Some((start, end)) = self.inrange {
+                write!(f, " inrange({}, {})", start, end)?;
+            }
+        }
+        write!(f, " ({}", &self.address)?;
         for idx in &self.indices {
             write!(f, ", {}", idx)?;
         }
@@ -1420,10 +1567,26 @@ impl Display for Select {
 // from_llvm //
 // ********* //
 
+use crate::from_llvm::*;
 use crate::llvm_sys::*;
 use crate::module::ModuleContext;
 use std::collections::hash_map::Entry;
 
+/// The LLVM C API has no getter for the value of a half, bfloat, fp128,
+/// x86_fp80, or ppc_fp128 constant, but LLVM always prints these as a hex
+/// literal (e.g., `half 0xH3C00`), so we recover the exact bits from that.
+/// `kind` is the letter LLVM uses after the `0x` for the given format.
+fn float_hex_bits(constant: LLVMValueRef, kind: char) -> u128 {
+    let printed = unsafe { print_to_string(constant) };
+    printed
+        .split_whitespace()
+        .last()
+        .and_then(|lit| lit.strip_prefix("0x"))
+        .and_then(|lit| lit.strip_prefix(kind))
+        .and_then(|hex| u128::from_str_radix(hex, 16).ok())
+        .unwrap_or_else(|| panic!("Expected a hex float literal with prefix 0x{}, got {:?}", kind, printed))
+}
+
 impl Constant {
     pub(crate) fn from_llvm_ref(constant: LLVMValueRef, ctx: &mut ModuleContext) -> ConstantRef {
         if let Some(constantref) = ctx.constants.get(&constant) {
@@ -1457,9 +1620,9 @@ impl Constant {
             LLVMValueKind::LLVMConstantFPValueKind => {
                 match ctx.types.type_from_llvm_ref( unsafe { LLVMTypeOf(constant) } ).as_ref() {
                     Type::FPType(fptype) => Constant::Float(match fptype {
-                        FPType::Half => Float::Half,
+                        FPType::Half => Float::Half(float_hex_bits(constant, 'H') as u16),
                         #[cfg(feature="llvm-11-or-greater")]
-                        FPType::BFloat => Float::BFloat,
+                        FPType::BFloat => Float::BFloat(float_hex_bits(constant, 'R') as u16),
                         FPType::Single => Float::Single( unsafe {
                             let mut b = 0;
                             let b_ptr: *mut std::os::raw::c_int = &mut b;
@@ -1470,9 +1633,20 @@ impl Constant {
                             let b_ptr: *mut std::os::raw::c_int = &mut b;
                             LLVMConstRealGetDouble(constant, b_ptr)
                         } ),
-                        FPType::FP128 => Float::Quadruple,
-                        FPType::X86_FP80 => Float::X86_FP80,
-                        FPType::PPC_FP128 => Float::PPC_FP128,
+                        FPType::FP128 => {
+                            let bits = float_hex_bits(constant, 'L');
+                            // LLVM prints the low 64 bits first
+                            Float::Quadruple(bits.rotate_left(64))
+                        },
+                        FPType::X86_FP80 => {
+                            let bits = float_hex_bits(constant, 'K');
+                            Float::X86_FP80((bits >> 64) as u16, bits as u64)
+                        },
+                        FPType::PPC_FP128 => {
+                            let bits = float_hex_bits(constant, 'M');
+                            // LLVM prints the high-order double first
+                            Float::PPC_FP128(bits as u64, (bits >> 64) as u64)
+                        },
                     }),
                     ty => panic!("Expected Constant::Float to have type Type::FPType; got {:?}", ty),
                 }
@@ -1565,7 +1739,15 @@ impl Constant {
                 Constant::TokenNone
             },
             LLVMValueKind::LLVMBlockAddressValueKind => {
-                Constant::BlockAddress
+                // operand 0 is the function, and operand 1 is the block
+                let func = unsafe { LLVMGetOperand(constant, 0) };
+                let bb = unsafe { LLVMValueAsBasicBlock(LLVMGetOperand(constant, 1)) };
+                Constant::BlockAddress {
+                    function: ctx.global_names.get(&func)
+                        .unwrap_or_else(|| panic!("Function not found in ctx.global_names: {:?}", unsafe { get_value_name(func) }))
+                        .clone(),
+                    block: ctx.bb_name(func, bb),
+                }
             },
             LLVMValueKind::LLVMConstantExprValueKind => {
                 use llvm_sys::LLVMOpcode;
@@ -1792,10 +1974,33 @@ impl GetElementPtr {
                     .collect()
             },
             in_bounds: unsafe { LLVMIsInBounds(expr) } != 0,
+            #[cfg(feature = "llvm-19-or-greater")]
+            nusw: unsafe { LLVMGEPGetNoWrapFlags(expr) } & (1 << 1) != 0, // bits as defined by the `LLVMGEPNoWrapFlags` enum in the LLVM C API
+            #[cfg(feature = "llvm-19-or-greater")]
+            nuw: unsafe { LLVMGEPGetNoWrapFlags(expr) } & (1 << 2) != 0,
+            #[cfg(feature = "llvm-19-or-greater")]
+            inrange: gep_inrange(expr),
         }
     }
 }
 
+/// The LLVM C API has no getter for the `inrange` range of a constant GEP, so
+/// we recover it from LLVM's textual representation of the constant, which
+/// looks like `ptr getelementptr inbounds inrange(-16, 16) (...)`.
+#[cfg(feature = "llvm-19-or-greater")]
+fn gep_inrange(expr: LLVMValueRef) -> Option<(i64, i64)> {
+    let printed = unsafe { print_to_string(expr) };
+    let mut rest = printed.split_once("getelementptr ")?.1;
+    for flag in &["inbounds ", "nusw ", "nuw "] {
+        rest = rest.strip_prefix(flag).unwrap_or(rest);
+    }
+    let (start, end) = rest.strip_prefix("inrange(")?.split_once(')')?.0.split_once(", ")?;
+    Some((
+        start.parse().expect("Failed to parse inrange start"),
+        end.parse().expect("Failed to parse inrange end"),
+    ))
+}
+
 // These constexprs have the property that their result type is ambiguous from
 //   knowing only their operands.
 macro_rules! typed_unop_from_llvm {
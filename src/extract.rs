@@ -0,0 +1,340 @@
+//! Carving functions out of a `Module`; see
+//! [`Module::extract()`](../module/struct.Module.html#method.extract).
+
+use crate::constant::{Constant, ConstantRef};
+use crate::function::{Function, FunctionDeclaration};
+use crate::instruction::Instruction;
+use crate::module::{Linkage, Module};
+use crate::name::Name;
+use crate::operand::Operand;
+use crate::terminator::Terminator;
+use crate::types::{NamedStructDef, Type, TypeRef, Types};
+use crate::visit::{walk, Visitor};
+use std::collections::{BTreeSet, HashSet};
+
+/// Options for [`Module::extract()`](../module/struct.Module.html#method.extract)
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct ExtractOptions {
+    /// If `true`, functions referenced by the extracted code are copied too
+    /// (transitively), rather than turned into `FunctionDeclaration`s.
+    /// Functions which are only declared in the original module remain
+    /// declarations either way.
+    pub copy_callees: bool,
+}
+
+pub(crate) fn extract(module: &Module, names: &[&str], options: &ExtractOptions) -> Module {
+    let mut extractor = Extractor {
+        module,
+        options,
+        definitions: HashSet::new(),
+        declarations: HashSet::new(),
+        worklist: vec![],
+        types: vec![],
+    };
+    for name in names {
+        if module.get_func_by_name(name).is_none() {
+            panic!(
+                "Module::extract: no function named {:?} is defined in the module",
+                name
+            );
+        }
+        extractor.define(Name::from(*name));
+    }
+    while let Some(name) = extractor.worklist.pop() {
+        extractor.process(&name);
+    }
+
+    let Extractor {
+        definitions,
+        declarations,
+        types: used_types,
+        ..
+    } = extractor;
+    let name_of = |func_name: &str| Name::from(func_name);
+    let functions: Vec<Function> = module
+        .functions
+        .iter()
+        .filter(|f| definitions.contains(&name_of(&f.name)))
+        .cloned()
+        .collect();
+    let declared_funcs = module
+        .functions
+        .iter()
+        .filter(|f| {
+            let name = name_of(&f.name);
+            declarations.contains(&name) && !definitions.contains(&name)
+        })
+        .map(declaration_of);
+    let func_declarations: Vec<FunctionDeclaration> = module
+        .func_declarations
+        .iter()
+        .filter(|d| declarations.contains(&name_of(&d.name)))
+        .cloned()
+        .chain(declared_funcs)
+        .collect();
+    let global_vars: Vec<_> = module
+        .global_vars
+        .iter()
+        .filter(|g| definitions.contains(&g.name))
+        .cloned()
+        .collect();
+    let global_aliases: Vec<_> = module
+        .global_aliases
+        .iter()
+        .filter(|a| definitions.contains(&a.name))
+        .cloned()
+        .collect();
+    let global_ifuncs: Vec<_> = module
+        .global_ifuncs
+        .iter()
+        .filter(|i| definitions.contains(&i.name))
+        .cloned()
+        .collect();
+    let used_comdats: HashSet<&String> = functions
+        .iter()
+        .filter_map(|f| f.comdat.as_ref())
+        .chain(global_vars.iter().filter_map(|g| g.comdat.as_ref()))
+        .collect();
+    let comdats = module
+        .comdats
+        .iter()
+        .filter(|c| used_comdats.contains(&c.name))
+        .cloned()
+        .collect();
+
+    let mut structs = BTreeSet::new();
+    for ty in &used_types {
+        named_structs_in(ty, &module.types, &mut structs);
+    }
+    let mut types = module.types.clone();
+    let unused_structs: Vec<String> = types
+        .all_struct_names()
+        .filter(|name| !structs.contains(*name))
+        .cloned()
+        .collect();
+    for name in unused_structs {
+        types.remove_named_struct_def(&name);
+    }
+
+    Module {
+        name: module.name.clone(),
+        source_file_name: module.source_file_name.clone(),
+        data_layout: module.data_layout.clone(),
+        target_triple: module.target_triple.clone(),
+        functions,
+        func_declarations,
+        global_vars,
+        global_aliases,
+        global_ifuncs,
+        inline_assembly: module.inline_assembly.clone(),
+        comdats,
+        types,
+    }
+}
+
+struct Extractor<'m> {
+    module: &'m Module,
+    options: &'m ExtractOptions,
+    /// Functions, global variables, aliases and ifuncs to copy
+    definitions: HashSet<Name>,
+    /// Functions to declare but not define
+    declarations: HashSet<Name>,
+    /// Definitions whose references haven't been followed yet
+    worklist: Vec<Name>,
+    /// Types used by the extracted code
+    types: Vec<TypeRef>,
+}
+
+impl<'m> Extractor<'m> {
+    fn define(&mut self, name: Name) {
+        if self.definitions.insert(name.clone()) {
+            self.worklist.push(name);
+        }
+    }
+
+    /// Handle a reference to the global symbol `name`. References from
+    /// aliases and ifuncs, and `blockaddress`es, need the definition; other
+    /// references to functions only need a declaration, unless
+    /// `copy_callees`.
+    fn reference(&mut self, name: &Name, needs_definition: bool) {
+        let func_name = match name {
+            Name::Name(s) => s.as_str().to_owned(),
+            Name::Number(n) => n.to_string(),
+        };
+        if let Some(func) = self.module.get_func_by_name(&func_name) {
+            if needs_definition || self.options.copy_callees {
+                self.define(name.clone());
+            } else if self.declarations.insert(name.clone()) {
+                self.types.push(self.module.type_of(func));
+            }
+        } else if let Some(decl) = self.module.get_func_decl_by_name(&func_name) {
+            if self.declarations.insert(name.clone()) {
+                self.types.push(decl.return_type.clone());
+                self.types
+                    .extend(decl.parameters.iter().map(|p| p.ty.clone()));
+            }
+        } else {
+            // global variables, aliases and ifuncs are always copied
+            self.define(name.clone());
+        }
+    }
+
+    /// Follow the references of the definition of `name`
+    fn process(&mut self, name: &Name) {
+        let module = self.module;
+        let mut refs = References {
+            types: &module.types,
+            names: vec![],
+            used_types: vec![],
+        };
+        let mut needs_definition = false;
+        if let Some(func) = module
+            .functions
+            .iter()
+            .find(|f| name == &Name::from(f.name.as_str()))
+        {
+            refs.used_types.push(module.type_of(func));
+            refs.visit_function(func);
+        } else if let Some(global) = module.get_global_var_by_name(name) {
+            refs.used_types.push(global.value_type.clone());
+            refs.used_types.push(global.ty.clone());
+            refs.visit_global_variable(global);
+        } else if let Some(alias) = module.get_global_alias_by_name(name) {
+            refs.used_types.push(alias.ty.clone());
+            refs.visit_global_alias(alias);
+            needs_definition = true;
+        } else if let Some(ifunc) = module.get_global_ifunc_by_name(name) {
+            refs.used_types.push(ifunc.ty.clone());
+            refs.visit_global_ifunc(ifunc);
+            needs_definition = true;
+        } else {
+            panic!("Module::extract: reference to unknown global {}", name);
+        }
+        self.types.extend(refs.used_types);
+        for (referenced, needs_body) in &refs.names {
+            self.reference(referenced, needs_definition || *needs_body);
+        }
+    }
+}
+
+/// Collects the global symbols referenced by, and the types used in, the IR
+/// it visits
+struct References<'t> {
+    types: &'t Types,
+    /// Referenced symbols, and whether the reference needs the symbol's
+    /// definition (as a `blockaddress` does, since it names one of the
+    /// function's blocks)
+    names: Vec<(Name, bool)>,
+    used_types: Vec<TypeRef>,
+}
+
+impl Visitor for References<'_> {
+    fn visit_instruction(&mut self, instruction: &Instruction) {
+        self.used_types.push(self.types.type_of(instruction));
+        match instruction {
+            Instruction::Alloca(alloca) => self.used_types.push(alloca.allocated_type.clone()),
+            #[cfg(feature = "llvm-14-or-greater")]
+            Instruction::GetElementPtr(gep) => {
+                self.used_types.push(gep.source_element_type.clone())
+            },
+            #[cfg(feature = "llvm-15-or-greater")]
+            Instruction::Load(load) => self.used_types.push(load.loaded_ty.clone()),
+            #[cfg(feature = "llvm-15-or-greater")]
+            Instruction::Call(call) => self.used_types.push(call.function_ty.clone()),
+            _ => {},
+        }
+        walk::instruction(self, instruction)
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator) {
+        #[cfg(feature = "llvm-15-or-greater")]
+        match terminator {
+            Terminator::Invoke(invoke) => self.used_types.push(invoke.function_ty.clone()),
+            Terminator::CallBr(callbr) => self.used_types.push(callbr.function_ty.clone()),
+            _ => {},
+        }
+        walk::terminator(self, terminator)
+    }
+
+    fn visit_operand(&mut self, operand: &Operand) {
+        self.used_types.push(self.types.type_of(operand));
+        walk::operand(self, operand)
+    }
+
+    fn visit_constant(&mut self, constant: &ConstantRef) {
+        self.used_types.push(self.types.type_of(constant));
+        match constant.as_ref() {
+            Constant::GlobalReference { name, ty } => {
+                self.names.push((name.clone(), false));
+                self.used_types.push(ty.clone());
+            },
+            Constant::BlockAddress { function, .. } => self.names.push((function.clone(), true)),
+            _ => {},
+        }
+        walk::constant(self, constant)
+    }
+}
+
+/// Add to `structs` the names of the named structs which `ty` refers to,
+/// directly or through other structs' definitions
+fn named_structs_in(ty: &TypeRef, types: &Types, structs: &mut BTreeSet<String>) {
+    match ty.as_ref() {
+        #[cfg(feature = "llvm-14-or-lower")]
+        Type::PointerType { pointee_type, .. } => named_structs_in(pointee_type, types, structs),
+        Type::FuncType {
+            result_type,
+            param_types,
+            ..
+        } => {
+            named_structs_in(result_type, types, structs);
+            for param_type in param_types {
+                named_structs_in(param_type, types, structs);
+            }
+        },
+        Type::VectorType { element_type, .. } | Type::ArrayType { element_type, .. } => {
+            named_structs_in(element_type, types, structs)
+        },
+        Type::StructType { element_types, .. } => {
+            for element_type in element_types {
+                named_structs_in(element_type, types, structs);
+            }
+        },
+        Type::NamedStructType { name } => {
+            if !structs.insert(name.clone()) {
+                return;
+            }
+            if let Some(NamedStructDef::Defined(def)) = types.named_struct_def(name) {
+                named_structs_in(def, types, structs);
+            }
+        },
+        #[cfg(feature = "llvm-16-or-greater")]
+        Type::TargetExtType {
+            contained_types, ..
+        } => {
+            for contained_type in contained_types {
+                named_structs_in(contained_type, types, structs);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// A declaration of the function, for a module which doesn't contain its body
+pub(crate) fn declaration_of(func: &Function) -> FunctionDeclaration {
+    FunctionDeclaration {
+        name: func.name.clone(),
+        parameters: func.parameters.clone(),
+        is_var_arg: func.is_var_arg,
+        return_type: func.return_type.clone(),
+        function_attributes: func.function_attributes.clone(),
+        return_attributes: func.return_attributes.clone(),
+        // declarations can't have local, weak, or linkonce linkage
+        linkage: Linkage::External,
+        visibility: func.visibility,
+        dll_storage_class: func.dll_storage_class,
+        calling_convention: func.calling_convention,
+        alignment: func.alignment,
+        garbage_collector_name: func.garbage_collector_name.clone(),
+        debugloc: func.debugloc.clone(),
+    }
+}
@@ -77,7 +77,8 @@ wrap_maybe_null!(LLVMGetSection, LLVMValueRef, get_section);
 wrap_maybe_null!(LLVMGetGC, LLVMValueRef, get_gc);
 wrap!(LLVMGetBasicBlockName, LLVMBasicBlockRef, get_bb_name);
 wrap!(LLVMPrintValueToString, LLVMValueRef, print_to_string);
-// wrap!(LLVMPrintTypeToString, LLVMTypeRef, print_type_to_string);
+#[cfg(feature = "llvm-16-or-greater")]
+wrap!(LLVMPrintTypeToString, LLVMTypeRef, print_type_to_string);
 wrap_with_len!(
     LLVMGetStringAttributeKind,
     LLVMAttributeRef,
@@ -95,6 +96,12 @@ wrap_with_len_maybe_null!(
     get_debugloc_directory
 );
 #[cfg(feature = "llvm-18-or-greater")]
+wrap_with_len!(
+    LLVMGetOperandBundleTag,
+    LLVMOperandBundleRef,
+    get_operand_bundle_tag
+);
+#[cfg(feature = "llvm-18-or-greater")]
 wrap_with_len!(
     LLVMGetInlineAsmAsmString,
     LLVMValueRef,
@@ -1,7 +1,12 @@
 use crate::debugloc::{DebugLoc, HasDebugLoc};
-use crate::module::{Comdat, DLLStorageClass, Linkage, Visibility};
+use crate::instruction::{Instruction, Phi};
+use crate::module::{DLLStorageClass, Linkage, Visibility};
+use crate::pass::AnalysisManager;
+use crate::terminator::{Br, Terminator};
 use crate::types::{TypeRef, Typed, Types};
-use crate::{BasicBlock, ConstantRef, Name};
+use crate::visit::{walk_mut, Visitor, VisitorMut};
+use crate::{BasicBlock, ConstantRef, Name, Operand};
+use std::collections::{BTreeSet, HashSet};
 
 /// See [LLVM 14 docs on Functions](https://releases.llvm.org/14.0.0/docs/LangRef.html#functions)
 #[derive(PartialEq, Clone, Debug, Hash)]
@@ -18,11 +23,16 @@ pub struct Function {
     pub dll_storage_class: DLLStorageClass, // llvm-hs-pure has Option<DLLStorageClass>, but the llvm_sys api doesn't look like it can fail
     pub calling_convention: CallingConvention,
     pub section: Option<String>,
-    pub comdat: Option<Comdat>, // llvm-hs-pure has Option<String>, I'm not sure why
+    /// Name of the comdat this function belongs to, if any. The comdat itself
+    /// can be found in `Module.comdats`.
+    pub comdat: Option<String>,
     pub alignment: u32,
     /// See [LLVM 14 docs on Garbage Collector Strategy Names](https://releases.llvm.org/14.0.0/docs/LangRef.html#gc)
     pub garbage_collector_name: Option<String>,
-    // pub prefix: Option<ConstantRef>,  // appears to not be exposed in the LLVM C API, only the C++ API
+    /// See [LLVM 14 docs on Prefix Data](https://releases.llvm.org/14.0.0/docs/LangRef.html#prefixdata)
+    pub prefix_data: Option<ConstantRef>,
+    /// See [LLVM 14 docs on Prologue Data](https://releases.llvm.org/14.0.0/docs/LangRef.html#prologuedata)
+    pub prologue_data: Option<ConstantRef>,
     /// Personalities are used for exception handling. See [LLVM 14 docs on Personality Function](https://releases.llvm.org/14.0.0/docs/LangRef.html#personalityfn)
     pub personality_function: Option<ConstantRef>,
     pub debugloc: Option<DebugLoc>,
@@ -69,12 +79,583 @@ impl Function {
             comdat: None,
             alignment: 4,
             garbage_collector_name: None,
+            prefix_data: None,
+            prologue_data: None,
             personality_function: None,
             debugloc: None,
         }
     }
 }
 
+/// Typed accessors for well-known string function attributes. Each returns
+/// `None` if the attribute is absent or its value can't be parsed.
+impl Function {
+    /// Get the value of the string function attribute with the given kind
+    /// (e.g., `"target-cpu"`), if present. Attributes without a value have
+    /// value `""`.
+    pub fn string_attribute(&self, kind: &str) -> Option<&str> {
+        self.function_attributes.iter().find_map(|attr| match attr {
+            FunctionAttribute::StringAttribute { kind: k, value } if k == kind => {
+                Some(value.as_str())
+            },
+            _ => None,
+        })
+    }
+
+    /// The `"target-cpu"` attribute, e.g. `x86-64`
+    pub fn target_cpu(&self) -> Option<&str> {
+        self.string_attribute("target-cpu")
+    }
+
+    /// The `"tune-cpu"` attribute
+    pub fn tune_cpu(&self) -> Option<&str> {
+        self.string_attribute("tune-cpu")
+    }
+
+    /// The `"target-features"` attribute, e.g. `+avx2,-sse4a`
+    pub fn target_features(&self) -> Option<TargetFeatures> {
+        self.string_attribute("target-features").map(TargetFeatures::parse)
+    }
+
+    /// The `"frame-pointer"` attribute
+    pub fn frame_pointer(&self) -> Option<FramePointerKind> {
+        self.string_attribute("frame-pointer").and_then(FramePointerKind::parse)
+    }
+
+    /// The `"no-trapping-math"` attribute
+    pub fn no_trapping_math(&self) -> Option<bool> {
+        self.string_attribute("no-trapping-math").and_then(|value| value.parse().ok())
+    }
+
+    /// The `"stack-probe-size"` attribute, in bytes
+    pub fn stack_probe_size(&self) -> Option<u64> {
+        self.string_attribute("stack-probe-size").and_then(|value| value.parse().ok())
+    }
+
+    /// The `"denormal-fp-math"` attribute, which applies to all floating-point
+    /// types unless overridden by `"denormal-fp-math-f32"`
+    pub fn denormal_fp_math(&self) -> Option<DenormalMode> {
+        self.string_attribute("denormal-fp-math").and_then(DenormalMode::parse)
+    }
+
+    /// The `"denormal-fp-math-f32"` attribute, which overrides
+    /// `"denormal-fp-math"` for 32-bit floats
+    pub fn denormal_fp_math_f32(&self) -> Option<DenormalMode> {
+        self.string_attribute("denormal-fp-math-f32").and_then(DenormalMode::parse)
+    }
+}
+
+/// Utilities for rewriting the body of a function. These keep the incoming
+/// lists of `Phi`s and the targets of terminators consistent with the changes
+/// they make.
+impl Function {
+    /// Replace every use of the local value `name`, in any instruction or
+    /// terminator (including `Phi` incoming values), with `new`.
+    ///
+    /// `new` should have the same type as the value it replaces; this isn't
+    /// checked.
+    pub fn replace_all_uses(&mut self, name: &Name, new: Operand) {
+        struct Replacer<'n> {
+            name: &'n Name,
+            new: Operand,
+        }
+        impl VisitorMut for Replacer<'_> {
+            fn visit_operand(&mut self, operand: &mut Operand) {
+                if matches!(operand, Operand::LocalOperand { name, .. } if name == self.name) {
+                    *operand = self.new.clone();
+                }
+            }
+        }
+        let mut replacer = Replacer { name, new };
+        for bb in &mut self.basic_blocks {
+            replacer.visit_basic_block(bb);
+        }
+    }
+
+    /// Is the local value `name` used by any instruction or terminator?
+    pub fn has_uses(&self, name: &Name) -> bool {
+        self.has_uses_outside(name, None)
+    }
+
+    /// Whether `name` is used anywhere other than in the block named `block`
+    /// (if given) and in the `Phi` entries for values coming from it
+    fn has_uses_outside(&self, name: &Name, block: Option<&Name>) -> bool {
+        struct Finder<'n> {
+            name: &'n Name,
+            block: Option<&'n Name>,
+            found: bool,
+        }
+        impl Visitor for Finder<'_> {
+            fn visit_operand(&mut self, operand: &Operand) {
+                if matches!(operand, Operand::LocalOperand { name, .. } if name == self.name) {
+                    self.found = true;
+                }
+            }
+
+            fn visit_phi(&mut self, phi: &Phi) {
+                for (value, from) in &phi.incoming_values {
+                    if Some(from) != self.block {
+                        self.visit_operand(value);
+                    }
+                }
+            }
+        }
+        let mut finder = Finder {
+            name,
+            block,
+            found: false,
+        };
+        for bb in self.basic_blocks.iter().filter(|bb| Some(&bb.name) != block) {
+            finder.visit_basic_block(bb);
+        }
+        finder.found
+    }
+
+    /// Remove the instruction at `index` in the block named `block`, and
+    /// return it.
+    ///
+    /// Panics if the instruction's result is still used; replace its uses
+    /// first (e.g. with [`replace_all_uses()`](#method.replace_all_uses)).
+    pub fn remove_instruction(&mut self, block: &Name, index: usize) -> Instruction {
+        let b = self.bb_index(block, "remove_instruction");
+        let inst = self.basic_blocks[b].instrs.get(index).unwrap_or_else(|| {
+            panic!(
+                "Function::remove_instruction: block {} has only {} instructions",
+                block,
+                self.basic_blocks[b].instrs.len()
+            )
+        });
+        if let Some(dest) = inst.try_get_result() {
+            if self.has_uses(dest) {
+                panic!(
+                    "Function::remove_instruction: {} is still used; replace its uses first",
+                    dest
+                );
+            }
+        }
+        self.basic_blocks[b].instrs.remove(index)
+    }
+
+    /// Remove the block named `block`, and return it. The block's successors
+    /// no longer list it as an incoming block in their `Phi`s.
+    ///
+    /// Panics if any other block still branches to `block`, or if any value
+    /// defined in `block` is still used outside it, other than by the `Phi`
+    /// entries which are removed.
+    pub fn remove_block(&mut self, block: &Name) -> BasicBlock {
+        let b = self.bb_index(block, "remove_block");
+        if let Some(pred) = self
+            .basic_blocks
+            .iter()
+            .find(|bb| &bb.name != block && bb.term.successors().contains(&block))
+        {
+            panic!(
+                "Function::remove_block: {} still branches to {}; redirect it first",
+                pred.name, block
+            );
+        }
+        let defined = self.basic_blocks[b]
+            .instrs
+            .iter()
+            .filter_map(|inst| inst.try_get_result())
+            .chain(self.basic_blocks[b].term.try_get_result());
+        for name in defined {
+            if self.has_uses_outside(name, Some(block)) {
+                panic!(
+                    "Function::remove_block: {} (defined in {}) is still used",
+                    name, block
+                );
+            }
+        }
+        let removed = self.basic_blocks.remove(b);
+        for succ in removed.term.successors() {
+            for phi in self.phis_mut(succ) {
+                phi.incoming_values.retain(|(_, from)| from != block);
+            }
+        }
+        removed
+    }
+
+    /// Split the block named `block` before the instruction at `index`: the
+    /// instructions from `index` on, and the terminator, move to a new block
+    /// named `new_name`, placed right after `block`, and `block` gets an
+    /// unconditional branch to the new block. `Phi`s in the successors now
+    /// list the new block as the incoming block, rather than `block`.
+    ///
+    /// `index` may be the number of instructions in the block, in which case
+    /// the new block just contains the terminator. Panics if `new_name` is
+    /// already used for a block, or if a `Phi` would move to the new block.
+    pub fn split_block_at(&mut self, block: &Name, index: usize, new_name: Name) {
+        let b = self.bb_index(block, "split_block_at");
+        if self.get_bb_by_name(&new_name).is_some() {
+            panic!(
+                "Function::split_block_at: function {:?} already has a block named {}",
+                self.name, new_name
+            );
+        }
+        let bb = &mut self.basic_blocks[b];
+        if index > bb.instrs.len() {
+            panic!(
+                "Function::split_block_at: block {} has only {} instructions",
+                block,
+                bb.instrs.len()
+            );
+        }
+        if bb.instrs[index ..]
+            .iter()
+            .any(|inst| matches!(inst, Instruction::Phi(_)))
+        {
+            panic!(
+                "Function::split_block_at: can't move a phi out of {}",
+                block
+            );
+        }
+        let instrs = bb.instrs.split_off(index);
+        let term = std::mem::replace(
+            &mut bb.term,
+            Terminator::Br(Br {
+                dest: new_name.clone(),
+                debugloc: None,
+            }),
+        );
+        let succs: Vec<Name> = term.successors().into_iter().cloned().collect();
+        self.basic_blocks.insert(
+            b + 1,
+            BasicBlock {
+                name: new_name.clone(),
+                instrs,
+                term,
+            },
+        );
+        for succ in dedup(&succs) {
+            self.rename_incoming_block(succ, block, &new_name);
+        }
+    }
+
+    /// Merge the block named `second` into the block named `first`, which
+    /// must end in an unconditional branch to `second`, and must be its only
+    /// predecessor. `second`'s `Phi`s are replaced by their (single) incoming
+    /// values, its other instructions and its terminator are appended to
+    /// `first`, and `second` is removed. `Phi`s in `second`'s successors now
+    /// list `first` as the incoming block.
+    pub fn merge_blocks(&mut self, first: &Name, second: &Name) {
+        let f = self.bb_index(first, "merge_blocks");
+        let s = self.bb_index(second, "merge_blocks");
+        if !matches!(&self.basic_blocks[f].term, Terminator::Br(br) if &br.dest == second) {
+            panic!(
+                "Function::merge_blocks: {} doesn't end in an unconditional branch to {}",
+                first, second
+            );
+        }
+        let num_preds: usize = self
+            .basic_blocks
+            .iter()
+            .map(|bb| {
+                bb.term
+                    .successors()
+                    .into_iter()
+                    .filter(|&succ| succ == second)
+                    .count()
+            })
+            .sum();
+        if num_preds != 1 {
+            panic!(
+                "Function::merge_blocks: {} has predecessors other than {}",
+                second, first
+            );
+        }
+        let replacements: Vec<(Name, Operand)> = self.basic_blocks[s]
+            .instrs
+            .iter()
+            .filter_map(|inst| match inst {
+                Instruction::Phi(phi) => Some(phi),
+                _ => None,
+            })
+            .map(|phi| {
+                let value = phi
+                    .incoming_values
+                    .iter()
+                    .find(|(_, from)| from == first)
+                    .map(|(value, _)| value.clone())
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "Function::merge_blocks: {} has no incoming value from {}",
+                            phi.dest, first
+                        )
+                    });
+                (phi.dest.clone(), value)
+            })
+            .collect();
+        for (name, value) in replacements {
+            self.replace_all_uses(&name, value);
+        }
+        let removed = self.basic_blocks.remove(s);
+        let f = self.bb_index(first, "merge_blocks");
+        let first_bb = &mut self.basic_blocks[f];
+        first_bb.instrs.extend(
+            removed
+                .instrs
+                .into_iter()
+                .filter(|inst| !matches!(inst, Instruction::Phi(_))),
+        );
+        first_bb.term = removed.term;
+        let succs: Vec<Name> = first_bb.term.successors().into_iter().cloned().collect();
+        for succ in dedup(&succs) {
+            self.rename_incoming_block(succ, second, first);
+        }
+    }
+
+    /// Make the block named `from` branch to `new_to` wherever it branched to
+    /// `old_to`. `old_to`'s `Phi`s drop their incoming values from `from`.
+    ///
+    /// If `new_to` has `Phi`s, they must already have an incoming value from
+    /// `from` (which is then used for the new edges as well); add one first
+    /// if `from` isn't already a predecessor of `new_to`. Panics if `from`
+    /// doesn't branch to `old_to`.
+    pub fn redirect_edge(&mut self, from: &Name, old_to: &Name, new_to: &Name) {
+        let f = self.bb_index(from, "redirect_edge");
+        if old_to == new_to {
+            return;
+        }
+        let succs = self.basic_blocks[f].term.successors();
+        let num_edges = succs.iter().filter(|&&succ| succ == old_to).count();
+        // number of edges from `from` to `new_to` once we're done
+        let num_edges_after = num_edges + succs.iter().filter(|&&succ| succ == new_to).count();
+        if num_edges == 0 {
+            panic!(
+                "Function::redirect_edge: {} doesn't branch to {}",
+                from, old_to
+            );
+        }
+        // check the `Phi`s of `new_to` before changing anything
+        if let Some(bb) = self.get_bb_by_name(new_to) {
+            for inst in &bb.instrs {
+                if let Instruction::Phi(phi) = inst {
+                    if !phi.incoming_values.iter().any(|(_, bb)| bb == from) {
+                        panic!(
+                            "Function::redirect_edge: {} in {} has no incoming value from {}",
+                            phi.dest, new_to, from
+                        );
+                    }
+                }
+            }
+        }
+        for succ in self.basic_blocks[f].term.successors_mut() {
+            if succ == old_to {
+                *succ = new_to.clone();
+            }
+        }
+        for phi in self.phis_mut(old_to) {
+            phi.incoming_values.retain(|(_, bb)| bb != from);
+        }
+        for phi in self.phis_mut(new_to) {
+            let value = phi
+                .incoming_values
+                .iter()
+                .find(|(_, bb)| bb == from)
+                .map(|(value, _)| value.clone())
+                .expect("checked above");
+            let num_incoming = phi
+                .incoming_values
+                .iter()
+                .filter(|(_, bb)| bb == from)
+                .count();
+            for _ in num_incoming .. num_edges_after {
+                phi.incoming_values.push((value.clone(), from.clone()));
+            }
+        }
+    }
+
+    /// Reassign the `Name::Number`s of the function's parameters, blocks and
+    /// instruction results sequentially, in definition order, and update every
+    /// use. This is what LLVM requires of unnamed values in text IR, and what
+    /// the parser produces, but inserting or removing instructions breaks it.
+    /// `Name::Name`s are left unchanged.
+    ///
+    /// `blockaddress` constants referring to the function's blocks are only
+    /// updated within the function itself; use
+    /// [`Module::renumber()`](../module/struct.Module.html#method.renumber)
+    /// to also update those elsewhere, e.g. in global variable initializers.
+    pub fn renumber(&mut self) {
+        let renames = self.rename_locals(false);
+        self.rename_own_block_addresses(renames);
+    }
+
+    /// Rename all of the function's parameters, blocks and instruction
+    /// results, including those with `Name::Name`s, to sequential
+    /// `Name::Number`s in definition order, and update every use. After this,
+    /// functions which differ only in the names of their locals compare equal.
+    ///
+    /// As for [`renumber()`](#method.renumber), `blockaddress` constants are
+    /// only updated within the function; see
+    /// [`Module::canonicalize_names()`](../module/struct.Module.html#method.canonicalize_names).
+    pub fn canonicalize_names(&mut self) {
+        let renames = self.rename_locals(true);
+        self.rename_own_block_addresses(renames);
+    }
+
+    fn rename_own_block_addresses(&mut self, renames: HashMap<Name, Name>) {
+        let mut renames_by_function = HashMap::new();
+        renames_by_function.insert(Name::from(self.name.as_str()), renames);
+        BlockAddressRenamer {
+            renames: &renames_by_function,
+        }
+        .visit_function(self);
+    }
+
+    /// Promote `Alloca`s to SSA values, as LLVM's `mem2reg` pass does: loads
+    /// are replaced with the value last stored, with `Phi`s inserted where
+    /// control flow merges (at the iterated dominance frontiers of the
+    /// stores), and the allocas and their loads and stores are deleted.
+    ///
+    /// Only allocas in the entry block are promoted, and only those of a
+    /// single integer, floating-point or pointer value whose address doesn't
+    /// escape: it may only be used as the address of non-volatile,
+    /// non-atomic loads and stores of the allocated type, or by lifetime
+    /// markers (which are deleted with it). Loads before any store read
+    /// `undef`. Inserted `Phi`s are given the `DebugLoc` of their alloca;
+    /// other instructions keep theirs.
+    ///
+    /// Returns `true` if any alloca was promoted.
+    pub fn mem2reg(&mut self, types: &Types) -> bool {
+        crate::mem2reg::mem2reg(self, types, &mut AnalysisManager::new())
+    }
+
+    /// Rename the function's locals, except in `blockaddress` constants,
+    /// returning the renames made
+    pub(crate) fn rename_locals(&mut self, rename_named: bool) -> HashMap<Name, Name> {
+        let mut renames: HashMap<Name, Name> = HashMap::new();
+        let mut next = 0;
+        let params = self.parameters.iter().map(|p| &p.name);
+        let locals = self.basic_blocks.iter().flat_map(|bb| {
+            std::iter::once(&bb.name)
+                .chain(bb.instrs.iter().filter_map(|inst| inst.try_get_result()))
+                .chain(bb.term.try_get_result())
+        });
+        for name in params.chain(locals) {
+            if rename_named || matches!(name, Name::Number(_)) {
+                renames.insert(name.clone(), Name::Number(next));
+                next += 1;
+            }
+        }
+        renames.retain(|old, new| old != new);
+        if renames.is_empty() {
+            return renames;
+        }
+        let rename = |name: &mut Name| {
+            if let Some(new) = renames.get(name) {
+                *name = new.clone();
+            }
+        };
+
+        struct Renamer<'r> {
+            renames: &'r HashMap<Name, Name>,
+        }
+        impl VisitorMut for Renamer<'_> {
+            fn visit_operand(&mut self, operand: &mut Operand) {
+                if let Operand::LocalOperand { name, .. } = operand {
+                    if let Some(new) = self.renames.get(name) {
+                        *name = new.clone();
+                    }
+                }
+            }
+        }
+        let mut renamer = Renamer { renames: &renames };
+
+        for param in &mut self.parameters {
+            rename(&mut param.name);
+        }
+        for bb in &mut self.basic_blocks {
+            renamer.visit_basic_block(bb);
+            rename(&mut bb.name);
+            for inst in &mut bb.instrs {
+                if let Some(dest) = inst.try_get_result_mut() {
+                    rename(dest);
+                }
+                if let Instruction::Phi(phi) = inst {
+                    for (_, from) in &mut phi.incoming_values {
+                        rename(from);
+                    }
+                }
+            }
+            if let Some(result) = bb.term.try_get_result_mut() {
+                rename(result);
+            }
+            for succ in bb.term.successors_mut() {
+                rename(succ);
+            }
+        }
+        renames
+    }
+
+    fn bb_index(&self, name: &Name, what: &str) -> usize {
+        self.basic_blocks
+            .iter()
+            .position(|bb| &bb.name == name)
+            .unwrap_or_else(|| {
+                panic!(
+                    "Function::{}: function {:?} has no block named {}",
+                    what, self.name, name
+                )
+            })
+    }
+
+    /// The `Phi`s of the block named `block`, or none if there is no such block
+    fn phis_mut<'s>(&'s mut self, block: &'s Name) -> impl Iterator<Item = &'s mut Phi> {
+        self.basic_blocks
+            .iter_mut()
+            .filter(move |bb| &bb.name == block)
+            .flat_map(|bb| bb.instrs.iter_mut())
+            .filter_map(|inst| match inst {
+                Instruction::Phi(phi) => Some(phi),
+                _ => None,
+            })
+    }
+
+    /// In the `Phi`s of the block named `block`, replace the incoming block
+    /// `old` with `new`
+    fn rename_incoming_block(&mut self, block: &Name, old: &Name, new: &Name) {
+        for phi in self.phis_mut(block) {
+            for (_, from) in &mut phi.incoming_values {
+                if from == old {
+                    *from = new.clone();
+                }
+            }
+        }
+    }
+}
+
+/// The distinct names in `names`, in order of first appearance
+fn dedup(names: &[Name]) -> Vec<&Name> {
+    let mut seen = HashSet::new();
+    names.iter().filter(|name| seen.insert(*name)).collect()
+}
+
+/// Updates `blockaddress` constants after functions' locals have been renamed
+/// by `rename_locals()`
+pub(crate) struct BlockAddressRenamer<'r> {
+    /// Map from function name to the renames made in that function
+    pub(crate) renames: &'r HashMap<Name, HashMap<Name, Name>>,
+}
+
+impl VisitorMut for BlockAddressRenamer<'_> {
+    fn visit_constant(&mut self, constant: &mut ConstantRef) {
+        walk_mut::constant(self, constant);
+        if let Constant::BlockAddress { function, block } = constant.as_ref() {
+            let new = self
+                .renames
+                .get(function)
+                .and_then(|renames| renames.get(block));
+            if let Some(new) = new {
+                *constant = ConstantRef::new(Constant::BlockAddress {
+                    function: function.clone(),
+                    block: new.clone(),
+                });
+            }
+        }
+    }
+}
+
 /// See [LLVM 14 docs on Functions](https://releases.llvm.org/14.0.0/docs/LangRef.html#functions)
 #[derive(PartialEq, Clone, Debug, Hash)]
 pub struct FunctionDeclaration {
@@ -82,6 +663,7 @@ pub struct FunctionDeclaration {
     pub parameters: Vec<Parameter>,
     pub is_var_arg: bool,
     pub return_type: TypeRef,
+    pub function_attributes: Vec<FunctionAttribute>,
     pub return_attributes: Vec<ParameterAttribute>,
     pub linkage: Linkage,
     pub visibility: Visibility,
@@ -185,6 +767,11 @@ impl MemoryEffect {
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
 pub enum FunctionAttribute {
     AlignStack(u64),
+    /// Bitmask of the kinds of allocation function this is; see the LLVM docs
+    /// for `allockind` (`alloc` = 1, `realloc` = 2, `free` = 4,
+    /// `uninitialized` = 8, `zeroed` = 16, `aligned` = 32)
+    #[cfg(feature = "llvm-15-or-greater")]
+    AllocKind(u64),
     AllocSize {
         elt_size: u32,
         num_elts: Option<u32>,
@@ -193,13 +780,27 @@ pub enum FunctionAttribute {
     Builtin,
     Cold,
     Convergent,
+    #[cfg(feature = "llvm-18-or-greater")]
+    CoroOnlyDestroyWhenComplete,
+    #[cfg(feature = "llvm-14-or-greater")]
+    DisableSanitizerInstrumentation,
+    #[cfg(feature = "llvm-15-or-greater")]
+    FnRetThunkExtern,
+    #[cfg(feature = "llvm-12-or-greater")]
+    Hot,
+    #[cfg(feature = "llvm-19-or-greater")]
+    HybridPatchable,
     InaccessibleMemOnly,
     InaccessibleMemOrArgMemOnly,
     InlineHint,
     JumpTable,
     MinimizeSize,
+    #[cfg(feature = "llvm-12-or-greater")]
+    MustProgress,
     Naked,
     NoBuiltin,
+    #[cfg(feature = "llvm-15-or-greater")]
+    NoCallback,
     NoCFCheck,
     NoDuplicate,
     NoFree,
@@ -208,18 +809,28 @@ pub enum FunctionAttribute {
     #[cfg(feature = "llvm-11-or-greater")]
     NoMerge,
     NonLazyBind,
+    #[cfg(feature = "llvm-12-or-greater")]
+    NoProfile,
     NoRedZone,
     NoReturn,
     NoRecurse,
+    #[cfg(feature = "llvm-16-or-greater")]
+    NoSanitizeBounds,
+    #[cfg(feature = "llvm-14-or-greater")]
+    NoSanitizeCoverage,
     WillReturn,
     ReturnsTwice,
     NoSync,
     NoUnwind,
     #[cfg(feature = "llvm-11-or-greater")]
     NullPointerIsValid,
+    #[cfg(feature = "llvm-18-or-greater")]
+    OptDebug,
     OptForFuzzing,
     OptNone,
     OptSize,
+    #[cfg(feature = "llvm-15-or-greater")]
+    PreSplitCoroutine,
     ReadNone,
     ReadOnly,
     WriteOnly,
@@ -230,7 +841,11 @@ pub enum FunctionAttribute {
     SanitizeThread,
     SanitizeHWAddress,
     SanitizeMemTag,
+    #[cfg(feature = "llvm-19-or-greater")]
+    SanitizeNumericalStability,
     ShadowCallStack,
+    #[cfg(feature = "llvm-16-or-greater")]
+    SkipProfile,
     SpeculativeLoadHardening,
     Speculatable,
     StackProtect,
@@ -238,6 +853,12 @@ pub enum FunctionAttribute {
     StackProtectStrong,
     StrictFP,
     UWTable,
+    /// `vscale_range(min, max)`. `max` is `None` if unbounded.
+    #[cfg(feature = "llvm-12-or-greater")]
+    VScaleRange {
+        min: u32,
+        max: Option<u32>,
+    },
     #[cfg(feature = "llvm-16-or-greater")]
     Memory {
         default: MemoryEffect,
@@ -248,7 +869,12 @@ pub enum FunctionAttribute {
         kind: String,
         value: String, // for no value, use ""
     },
-    UnknownAttribute, // this is used if we get a value not in the above list
+    /// This is used if we get an enum attribute not in the above list.
+    /// `kind` is the attribute's name if we know it (e.g., a parameter
+    /// attribute appearing on a function), or else its numeric kind ID.
+    UnknownAttribute {
+        kind: String,
+    },
 }
 
 /// `ParameterAttribute`s can apply to function parameters as well as function return types.
@@ -274,6 +900,10 @@ pub enum ParameterAttribute {
     SRet,
     #[cfg(feature = "llvm-12-or-greater")]
     SRet(TypeRef),
+    #[cfg(feature = "llvm-12-or-greater")]
+    ByRef(TypeRef),
+    #[cfg(feature = "llvm-13-or-greater")]
+    ElementType(TypeRef),
     Alignment(u64),
     NoAlias,
     NoCapture,
@@ -284,17 +914,175 @@ pub enum ParameterAttribute {
     Dereferenceable(u64),
     DereferenceableOrNull(u64),
     SwiftSelf,
+    #[cfg(feature = "llvm-13-or-greater")]
+    SwiftAsync,
     SwiftError,
     ImmArg,
     #[cfg(feature = "llvm-11-or-greater")]
     NoUndef,
+    ReadNone,
+    ReadOnly,
+    WriteOnly,
+    #[cfg(feature = "llvm-15-or-greater")]
+    AllocAlign,
+    #[cfg(feature = "llvm-15-or-greater")]
+    AllocPtr,
+    /// Bitmask of the floating-point classes the value is known not to be;
+    /// see the LLVM docs for `nofpclass` (`snan` = 1, `qnan` = 2, `ninf` = 4,
+    /// `nnorm` = 8, `nsub` = 16, `nzero` = 32, `pzero` = 64, `psub` = 128,
+    /// `pnorm` = 256, `pinf` = 512)
+    #[cfg(feature = "llvm-16-or-greater")]
+    NoFPClass(u64),
+    #[cfg(feature = "llvm-18-or-greater")]
+    Writable,
+    #[cfg(feature = "llvm-18-or-greater")]
+    DeadOnUnwind,
+    /// `range(<ty> <lower>, <upper>)`: the value is in the half-open range
+    /// `[lower, upper)`, which may wrap around. `ty` is the integer type (for
+    /// vectors, the element type), and the bounds are as LLVM prints them, as
+    /// signed integers.
+    #[cfg(feature = "llvm-19-or-greater")]
+    Range { ty: TypeRef, lower: i128, upper: i128 },
+    /// `initializes((<start>, <end>), ...)`: the half-open ranges of byte
+    /// offsets, relative to the pointer, which the function initializes
+    #[cfg(feature = "llvm-19-or-greater")]
+    Initializes(Vec<(i64, i64)>),
     StringAttribute {
         kind: String,
         value: String, // for no value, use ""
     },
-    UnknownAttribute, // this is used if we get an EnumAttribute not in the above list; or, for LLVM 11 or lower, also for some TypeAttributes (due to C API limitations)
+    /// This is used if we get an enum attribute not in the above list.
+    /// `kind` is the attribute's name if we know it (e.g., a function
+    /// attribute appearing on a parameter), or else its numeric kind ID.
+    UnknownAttribute {
+        kind: String,
+    },
+    /// This is used if we get a type attribute not in the above list. `kind`
+    /// is as for `UnknownAttribute`.
     #[cfg(feature = "llvm-12-or-greater")]
-    UnknownTypeAttribute(TypeRef), // this is used if we get a TypeAttribute not in the above list
+    UnknownTypeAttribute {
+        kind: String,
+        ty: TypeRef,
+    },
+}
+
+/// Parsed contents of the `"target-features"` function attribute
+#[derive(PartialEq, Eq, Clone, Debug, Default, Hash)]
+pub struct TargetFeatures {
+    /// Features explicitly enabled, e.g. `avx2` for `+avx2`
+    pub enabled: BTreeSet<String>,
+    /// Features explicitly disabled, e.g. `sse4a` for `-sse4a`
+    pub disabled: BTreeSet<String>,
+}
+
+impl TargetFeatures {
+    /// Parse a comma-separated list of features, each prefixed with `+` or
+    /// `-`. If a feature is mentioned more than once, the last mention wins.
+    /// Entries without a `+` or `-` prefix are ignored.
+    pub fn parse(features: &str) -> Self {
+        let mut parsed = Self::default();
+        for feature in features.split(',') {
+            if let Some(feature) = feature.strip_prefix('+') {
+                parsed.disabled.remove(feature);
+                parsed.enabled.insert(feature.to_owned());
+            } else if let Some(feature) = feature.strip_prefix('-') {
+                parsed.enabled.remove(feature);
+                parsed.disabled.insert(feature.to_owned());
+            }
+        }
+        parsed
+    }
+
+    /// Is the given feature (without `+` or `-`) explicitly enabled
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        self.enabled.contains(feature)
+    }
+
+    /// Is the given feature (without `+` or `-`) explicitly disabled
+    pub fn is_disabled(&self, feature: &str) -> bool {
+        self.disabled.contains(feature)
+    }
+}
+
+/// Values of the `"frame-pointer"` function attribute
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum FramePointerKind {
+    /// `"none"`: the frame pointer may be eliminated
+    None,
+    /// `"non-leaf"`: keep the frame pointer in functions which make calls
+    NonLeaf,
+    /// `"all"`: keep the frame pointer in all functions
+    All,
+    /// `"reserved"`: the frame pointer register is reserved, but need not hold
+    /// the frame pointer
+    Reserved,
+}
+
+impl FramePointerKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "none" => Some(Self::None),
+            "non-leaf" => Some(Self::NonLeaf),
+            "all" => Some(Self::All),
+            "reserved" => Some(Self::Reserved),
+            _ => None,
+        }
+    }
+}
+
+/// Parsed contents of the `"denormal-fp-math"` and `"denormal-fp-math-f32"`
+/// function attributes
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub struct DenormalMode {
+    /// How denormal results of floating-point operations are treated
+    pub output: DenormalModeKind,
+    /// How denormal inputs to floating-point operations are treated
+    pub input: DenormalModeKind,
+}
+
+impl DenormalMode {
+    /// Parse a value like `preserve-sign,preserve-sign`. If only one mode is
+    /// given, it applies to both outputs and inputs.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.split_once(',') {
+            Some((output, input)) => Some(Self {
+                output: DenormalModeKind::parse(output)?,
+                input: DenormalModeKind::parse(input)?,
+            }),
+            None => {
+                let kind = DenormalModeKind::parse(value)?;
+                Some(Self {
+                    output: kind,
+                    input: kind,
+                })
+            },
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum DenormalModeKind {
+    /// `"ieee"`: denormals are handled as IEEE-754 specifies
+    IEEE,
+    /// `"preserve-sign"`: denormals are flushed to a zero of the same sign
+    PreserveSign,
+    /// `"positive-zero"`: denormals are flushed to positive zero
+    PositiveZero,
+    /// `"dynamic"`: the mode is determined dynamically by the floating-point
+    /// environment
+    Dynamic,
+}
+
+impl DenormalModeKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ieee" => Some(Self::IEEE),
+            "preserve-sign" => Some(Self::PreserveSign),
+            "positive-zero" => Some(Self::PositiveZero),
+            "dynamic" => Some(Self::Dynamic),
+            _ => None,
+        }
+    }
 }
 
 pub type GroupID = usize;
@@ -309,7 +1097,6 @@ use crate::llvm_sys::*;
 use crate::module::ModuleContext;
 #[cfg(feature = "llvm-12-or-greater")]
 use crate::types::TypesBuilder;
-use llvm_sys::comdat::*;
 use llvm_sys::{LLVMAttributeFunctionIndex, LLVMAttributeReturnIndex};
 use std::collections::HashMap;
 use std::ffi::CString;
@@ -392,6 +1179,27 @@ impl FunctionDeclaration {
             return_type: ctx
                 .types
                 .type_from_llvm_ref(unsafe { LLVMGetReturnType(functy) }),
+            function_attributes: {
+                let num_attrs =
+                    unsafe { LLVMGetAttributeCountAtIndex(func, LLVMAttributeFunctionIndex) };
+                if num_attrs > 0 {
+                    let mut attrs: Vec<LLVMAttributeRef> = Vec::with_capacity(num_attrs as usize);
+                    unsafe {
+                        LLVMGetAttributesAtIndex(
+                            func,
+                            LLVMAttributeFunctionIndex,
+                            attrs.as_mut_ptr(),
+                        );
+                        attrs.set_len(num_attrs as usize);
+                    };
+                    attrs
+                        .into_iter()
+                        .map(|attr| FunctionAttribute::from_llvm_ref(attr, &ctx.attrsdata))
+                        .collect()
+                } else {
+                    vec![]
+                }
+            },
             return_attributes: {
                 let num_attrs =
                     unsafe { LLVMGetAttributeCountAtIndex(func, LLVMAttributeReturnIndex) };
@@ -488,43 +1296,18 @@ impl Function {
                     .map(|bb| BasicBlock::from_llvm_ref(bb, ctx, &mut func_ctx))
                     .collect()
             },
-            function_attributes: {
-                let num_attrs =
-                    unsafe { LLVMGetAttributeCountAtIndex(func, LLVMAttributeFunctionIndex) };
-                if num_attrs > 0 {
-                    let mut attrs: Vec<LLVMAttributeRef> = Vec::with_capacity(num_attrs as usize);
-                    unsafe {
-                        LLVMGetAttributesAtIndex(
-                            func,
-                            LLVMAttributeFunctionIndex,
-                            attrs.as_mut_ptr(),
-                        );
-                        attrs.set_len(num_attrs as usize);
-                    };
-                    attrs
-                        .into_iter()
-                        .map(|attr| FunctionAttribute::from_llvm_ref(attr, &ctx.attrsdata))
-                        .collect()
-                } else {
-                    vec![]
-                }
-            },
+            function_attributes: decl.function_attributes,
             return_attributes: decl.return_attributes,
             linkage: decl.linkage,
             visibility: decl.visibility,
             dll_storage_class: decl.dll_storage_class,
             calling_convention: decl.calling_convention,
             section: unsafe { get_section(func) },
-            comdat: {
-                let comdat = unsafe { LLVMGetComdat(func) };
-                if comdat.is_null() {
-                    None
-                } else {
-                    Some(Comdat::from_llvm_ref(comdat))
-                }
-            },
+            comdat: ctx.comdat_name(func),
             alignment: decl.alignment,
             garbage_collector_name: decl.garbage_collector_name,
+            prefix_data: Self::hung_off_constant(func, 1, "prefix", ctx),
+            prologue_data: Self::hung_off_constant(func, 2, "prologue", ctx),
             personality_function: {
                 if unsafe { LLVMHasPersonalityFn(func) } != 0 {
                     Some(Constant::from_llvm_ref(
@@ -539,6 +1322,49 @@ impl Function {
             // metadata: unimplemented!("Function.metadata"),
         }
     }
+
+    /// Get the prefix data (`index` 1) or prologue data (`index` 2) of the
+    /// function, if it has any.
+    ///
+    /// The LLVM C API has no getters for these, but a function stores them as
+    /// operands: personality function, prefix data, and prologue data, in
+    /// that order. The operands are only allocated once any of the three is
+    /// set, and the unset ones then hold a null pointer as a placeholder. To
+    /// tell a placeholder apart from actual null-pointer data, we look for the
+    /// `keyword` in the function's printed header.
+    fn hung_off_constant(
+        func: LLVMValueRef,
+        index: u32,
+        keyword: &str,
+        ctx: &mut ModuleContext,
+    ) -> Option<ConstantRef> {
+        if unsafe { LLVMGetNumOperands(func) } <= index as i32 {
+            return None;
+        }
+        let operand = unsafe { LLVMGetOperand(func, index) };
+        if operand.is_null() {
+            return None;
+        }
+        if !unsafe { LLVMIsAConstantPointerNull(operand) }.is_null() {
+            let text = unsafe { print_to_string(func) };
+            let header = text
+                .lines()
+                .find(|line| line.starts_with("define "))
+                .unwrap_or(&text);
+            let keyword = format!(" {} ", keyword);
+            let mut in_quotes = false;
+            let found = header.char_indices().any(|(i, c)| {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                !in_quotes && header[i ..].starts_with(&keyword)
+            });
+            if !found {
+                return None;
+            }
+        }
+        Some(Constant::from_llvm_ref(operand, ctx))
+    }
 }
 
 impl CallingConvention {
@@ -598,10 +1424,14 @@ impl CallingConvention {
 pub(crate) struct AttributesData {
     function_attribute_names: HashMap<u32, String>,
     param_attribute_names: HashMap<u32, String>,
+    /// The context of the module being translated, in which attributes can be
+    /// printed; see `print_param_attr()`
+    #[cfg(feature = "llvm-19-or-greater")]
+    context: LLVMContextRef,
 }
 
 impl AttributesData {
-    pub fn create() -> Self {
+    pub fn create(#[cfg(feature = "llvm-19-or-greater")] context: LLVMContextRef) -> Self {
         let function_attribute_names = [
             "alignstack",
             "allocsize",
@@ -662,11 +1492,44 @@ impl AttributesData {
         ]
         .iter()
         .map(|&attrname| {
-            let cstr = CString::new(attrname).unwrap();
-            let kind = unsafe { LLVMGetEnumAttributeKindForName(cstr.as_ptr(), attrname.len()) };
+            let kind = Self::kind_for_name(attrname);
             assert_ne!(kind, 0, "Function attribute {:?} not found", attrname);
             (kind, attrname.into())
         })
+        .chain(Self::newer_attributes(&[
+            #[cfg(feature = "llvm-15-or-greater")]
+            "allockind",
+            #[cfg(feature = "llvm-18-or-greater")]
+            "coro_only_destroy_when_complete",
+            #[cfg(feature = "llvm-14-or-greater")]
+            "disable_sanitizer_instrumentation",
+            #[cfg(feature = "llvm-15-or-greater")]
+            "fn_ret_thunk_extern",
+            #[cfg(feature = "llvm-12-or-greater")]
+            "hot",
+            #[cfg(feature = "llvm-19-or-greater")]
+            "hybrid_patchable",
+            #[cfg(feature = "llvm-12-or-greater")]
+            "mustprogress",
+            #[cfg(feature = "llvm-15-or-greater")]
+            "nocallback",
+            #[cfg(feature = "llvm-12-or-greater")]
+            "noprofile",
+            #[cfg(feature = "llvm-16-or-greater")]
+            "nosanitize_bounds",
+            #[cfg(feature = "llvm-14-or-greater")]
+            "nosanitize_coverage",
+            #[cfg(feature = "llvm-18-or-greater")]
+            "optdebug",
+            #[cfg(feature = "llvm-15-or-greater")]
+            "presplitcoroutine",
+            #[cfg(feature = "llvm-19-or-greater")]
+            "sanitize_numerical_stability",
+            #[cfg(feature = "llvm-16-or-greater")]
+            "skipprofile",
+            #[cfg(feature = "llvm-12-or-greater")]
+            "vscale_range",
+        ]))
         .collect();
         let param_attribute_names = [
             "zeroext",
@@ -691,21 +1554,64 @@ impl AttributesData {
             "immarg",
             #[cfg(feature = "llvm-11-or-greater")]
             "noundef",
+            "readnone",
+            "readonly",
+            "writeonly",
         ]
         .iter()
         .map(|&attrname| {
-            let cstr = CString::new(attrname).unwrap();
-            let kind = unsafe { LLVMGetEnumAttributeKindForName(cstr.as_ptr(), attrname.len()) };
+            let kind = Self::kind_for_name(attrname);
             assert_ne!(kind, 0, "Parameter attribute {:?} not found", attrname);
             (kind, attrname.into())
         })
+        .chain(Self::newer_attributes(&[
+            #[cfg(feature = "llvm-12-or-greater")]
+            "byref",
+            #[cfg(feature = "llvm-13-or-greater")]
+            "elementtype",
+            #[cfg(feature = "llvm-13-or-greater")]
+            "swiftasync",
+            #[cfg(feature = "llvm-15-or-greater")]
+            "allocalign",
+            #[cfg(feature = "llvm-15-or-greater")]
+            "allocptr",
+            #[cfg(feature = "llvm-16-or-greater")]
+            "nofpclass",
+            #[cfg(feature = "llvm-18-or-greater")]
+            "writable",
+            #[cfg(feature = "llvm-18-or-greater")]
+            "dead_on_unwind",
+            #[cfg(feature = "llvm-19-or-greater")]
+            "range",
+            #[cfg(feature = "llvm-19-or-greater")]
+            "initializes",
+        ]))
         .collect();
         Self {
             function_attribute_names,
             param_attribute_names,
+            #[cfg(feature = "llvm-19-or-greater")]
+            context,
         }
     }
 
+    fn kind_for_name(attrname: &str) -> u32 {
+        let cstr = CString::new(attrname).unwrap();
+        unsafe { LLVMGetEnumAttributeKindForName(cstr.as_ptr(), attrname.len()) }
+    }
+
+    /// Kinds for attributes which were added in more recent LLVM versions.
+    /// Unlike for the attributes above, we don't insist that LLVM knows about
+    /// these: any that it doesn't are simply left out, and would be reported
+    /// as `UnknownAttribute`s if they were somehow encountered.
+    fn newer_attributes(attrnames: &[&str]) -> Vec<(u32, String)> {
+        attrnames
+            .iter()
+            .map(|&attrname| (Self::kind_for_name(attrname), attrname.into()))
+            .filter(|&(kind, _)| kind != 0)
+            .collect()
+    }
+
     /// Get the string name of an enum-style function attribute, or `None` if
     /// it's not one that we know about
     pub fn lookup_function_attr(&self, kind: u32) -> Option<&str> {
@@ -717,6 +1623,45 @@ impl AttributesData {
     pub fn lookup_param_attr(&self, kind: u32) -> Option<&str> {
         self.param_attribute_names.get(&kind).map(|s| s.as_str())
     }
+
+    /// Get a name for an attribute kind which we don't otherwise handle: the
+    /// attribute's name if it's one that we know about in any position, or
+    /// else the numeric kind
+    pub fn unknown_attr_name(&self, kind: u32) -> String {
+        self.lookup_function_attr(kind)
+            .or_else(|| self.lookup_param_attr(kind))
+            .map(String::from)
+            .unwrap_or_else(|| kind.to_string())
+    }
+
+    /// Print a parameter attribute as it appears in LLVM IR, e.g.
+    /// `range(i32 0, 10)`. The LLVM C API (as of LLVM 19) has no getters for
+    /// the payloads of constant-range attributes, so instead we attach the
+    /// attribute to the parameter of a scratch function declaration, and print
+    /// that.
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub fn print_param_attr(&self, a: LLVMAttributeRef) -> String {
+        let text = unsafe {
+            let module =
+                LLVMModuleCreateWithNameInContext(b"\0".as_ptr() as *const _, self.context);
+            let mut param_types = [LLVMInt8TypeInContext(self.context)];
+            let func_ty = LLVMFunctionType(
+                LLVMVoidTypeInContext(self.context),
+                param_types.as_mut_ptr(),
+                1,
+                0,
+            );
+            let func = LLVMAddFunction(module, b"f\0".as_ptr() as *const _, func_ty);
+            LLVMAddAttributeAtIndex(func, 1, a);
+            let text = print_to_string(func);
+            LLVMDisposeModule(module);
+            text
+        };
+        // `text` is `declare void @f(i8 <attribute>)`
+        let start = text.find("(i8 ").map_or(0, |i| i + "(i8 ".len());
+        let end = text.rfind(')').unwrap_or(text.len()).max(start);
+        text[start .. end].to_owned()
+    }
 }
 
 impl FunctionAttribute {
@@ -738,17 +1683,33 @@ impl FunctionAttribute {
                     };
                     Self::AllocSize { elt_size, num_elts }
                 },
+                #[cfg(feature = "llvm-15-or-greater")]
+                Some("allockind") => Self::AllocKind(unsafe { LLVMGetEnumAttributeValue(a) }),
                 Some("alwaysinline") => Self::AlwaysInline,
                 Some("builtin") => Self::Builtin,
                 Some("cold") => Self::Cold,
                 Some("convergent") => Self::Convergent,
+                #[cfg(feature = "llvm-18-or-greater")]
+                Some("coro_only_destroy_when_complete") => Self::CoroOnlyDestroyWhenComplete,
+                #[cfg(feature = "llvm-14-or-greater")]
+                Some("disable_sanitizer_instrumentation") => Self::DisableSanitizerInstrumentation,
+                #[cfg(feature = "llvm-15-or-greater")]
+                Some("fn_ret_thunk_extern") => Self::FnRetThunkExtern,
+                #[cfg(feature = "llvm-12-or-greater")]
+                Some("hot") => Self::Hot,
+                #[cfg(feature = "llvm-19-or-greater")]
+                Some("hybrid_patchable") => Self::HybridPatchable,
                 Some("inaccessiblememonly") => Self::InaccessibleMemOnly,
                 Some("inaccessiblemem_or_argmemonly") => Self::InaccessibleMemOrArgMemOnly,
                 Some("inlinehint") => Self::InlineHint,
                 Some("jumptable") => Self::JumpTable,
                 Some("minsize") => Self::MinimizeSize,
+                #[cfg(feature = "llvm-12-or-greater")]
+                Some("mustprogress") => Self::MustProgress,
                 Some("naked") => Self::Naked,
                 Some("nobuiltin") => Self::NoBuiltin,
+                #[cfg(feature = "llvm-15-or-greater")]
+                Some("nocallback") => Self::NoCallback,
                 Some("nocf_check") => Self::NoCFCheck,
                 Some("noduplicate") => Self::NoDuplicate,
                 Some("nofree") => Self::NoFree,
@@ -757,18 +1718,28 @@ impl FunctionAttribute {
                 #[cfg(feature = "llvm-11-or-greater")]
                 Some("nomerge") => Self::NoMerge,
                 Some("nonlazybind") => Self::NonLazyBind,
+                #[cfg(feature = "llvm-12-or-greater")]
+                Some("noprofile") => Self::NoProfile,
                 Some("noredzone") => Self::NoRedZone,
                 Some("noreturn") => Self::NoReturn,
                 Some("norecurse") => Self::NoRecurse,
+                #[cfg(feature = "llvm-16-or-greater")]
+                Some("nosanitize_bounds") => Self::NoSanitizeBounds,
+                #[cfg(feature = "llvm-14-or-greater")]
+                Some("nosanitize_coverage") => Self::NoSanitizeCoverage,
                 Some("willreturn") => Self::WillReturn,
                 Some("returns_twice") => Self::ReturnsTwice,
                 Some("nosync") => Self::NoSync,
                 Some("nounwind") => Self::NoUnwind,
                 #[cfg(feature = "llvm-11-or-greater")]
                 Some("null_pointer_is_valid") => Self::NullPointerIsValid,
+                #[cfg(feature = "llvm-18-or-greater")]
+                Some("optdebug") => Self::OptDebug,
                 Some("optforfuzzing") => Self::OptForFuzzing,
                 Some("optnone") => Self::OptNone,
                 Some("optsize") => Self::OptSize,
+                #[cfg(feature = "llvm-15-or-greater")]
+                Some("presplitcoroutine") => Self::PreSplitCoroutine,
                 Some("readnone") => Self::ReadNone,
                 Some("readonly") => Self::ReadOnly,
                 Some("writeonly") => Self::WriteOnly,
@@ -779,7 +1750,11 @@ impl FunctionAttribute {
                 Some("sanitize_thread") => Self::SanitizeThread,
                 Some("sanitize_hwaddress") => Self::SanitizeHWAddress,
                 Some("sanitize_memtag") => Self::SanitizeMemTag,
+                #[cfg(feature = "llvm-19-or-greater")]
+                Some("sanitize_numerical_stability") => Self::SanitizeNumericalStability,
                 Some("shadowcallstack") => Self::ShadowCallStack,
+                #[cfg(feature = "llvm-16-or-greater")]
+                Some("skipprofile") => Self::SkipProfile,
                 Some("speculative_load_hardening") => Self::SpeculativeLoadHardening,
                 Some("speculatable") => Self::Speculatable,
                 Some("ssp") => Self::StackProtect,
@@ -787,6 +1762,18 @@ impl FunctionAttribute {
                 Some("sspstrong") => Self::StackProtectStrong,
                 Some("strictfp") => Self::StrictFP,
                 Some("uwtable") => Self::UWTable,
+                #[cfg(feature = "llvm-12-or-greater")]
+                Some("vscale_range") => {
+                    // the min value is the upper 32 bits, and the max value is
+                    // the lower 32 bits, with 0 meaning unbounded
+                    let value = unsafe { LLVMGetEnumAttributeValue(a) };
+                    let min = (value >> 32) as u32;
+                    let max = match (value & 0xFFFF_FFFF) as u32 {
+                        0 => None,
+                        val => Some(val),
+                    };
+                    Self::VScaleRange { min, max }
+                },
                 #[cfg(feature = "llvm-16-or-greater")]
                 Some("memory") => {
                     let value = unsafe { LLVMGetEnumAttributeValue(a) };
@@ -809,7 +1796,9 @@ impl FunctionAttribute {
                 Some(s) => panic!("Unhandled value from lookup_function_attr: {:?}", s),
                 None => {
                     debug!("unknown enum function attr {}", kind);
-                    Self::UnknownAttribute
+                    Self::UnknownAttribute {
+                        kind: attrsdata.unknown_attr_name(kind),
+                    }
                 },
             }
         } else if unsafe { LLVMIsStringAttribute(a) } != 0 {
@@ -819,7 +1808,9 @@ impl FunctionAttribute {
             }
         } else {
             debug!("Encountered an unknown function attribute: neither enum nor string");
-            Self::UnknownAttribute
+            Self::UnknownAttribute {
+                kind: attrsdata.unknown_attr_name(unsafe { LLVMGetEnumAttributeKind(a) }),
+            }
         }
     }
 }
@@ -858,14 +1849,31 @@ impl ParameterAttribute {
                     Self::DereferenceableOrNull(unsafe { LLVMGetEnumAttributeValue(a) })
                 },
                 Some("swiftself") => Self::SwiftSelf,
+                #[cfg(feature = "llvm-13-or-greater")]
+                Some("swiftasync") => Self::SwiftAsync,
                 Some("swifterror") => Self::SwiftError,
                 Some("immarg") => Self::ImmArg,
                 #[cfg(feature = "llvm-11-or-greater")]
                 Some("noundef") => Self::NoUndef,
+                Some("readnone") => Self::ReadNone,
+                Some("readonly") => Self::ReadOnly,
+                Some("writeonly") => Self::WriteOnly,
+                #[cfg(feature = "llvm-15-or-greater")]
+                Some("allocalign") => Self::AllocAlign,
+                #[cfg(feature = "llvm-15-or-greater")]
+                Some("allocptr") => Self::AllocPtr,
+                #[cfg(feature = "llvm-16-or-greater")]
+                Some("nofpclass") => Self::NoFPClass(unsafe { LLVMGetEnumAttributeValue(a) }),
+                #[cfg(feature = "llvm-18-or-greater")]
+                Some("writable") => Self::Writable,
+                #[cfg(feature = "llvm-18-or-greater")]
+                Some("dead_on_unwind") => Self::DeadOnUnwind,
                 Some(s) => panic!("Unhandled value from lookup_param_attr: {:?}", s),
                 None => {
                     debug!("unknown enum param attr {}", kind);
-                    Self::UnknownAttribute
+                    Self::UnknownAttribute {
+                        kind: attrsdata.unknown_attr_name(kind),
+                    }
                 },
             }
         } else if unsafe { LLVMIsStringAttribute(a) } != 0 {
@@ -876,8 +1884,7 @@ impl ParameterAttribute {
         } else if Self::is_type_attr(a) {
             #[cfg(feature = "llvm-11-or-lower")]
             {
-                debug!("Encountered a type attr, which shouldn't happen on LLVM 11 or lower");
-                Self::UnknownAttribute
+                unreachable!("is_type_attr() is always false on LLVM 11 or lower")
             }
             #[cfg(feature = "llvm-12-or-greater")]
             {
@@ -889,19 +1896,83 @@ impl ParameterAttribute {
                     #[cfg(feature = "llvm-13-or-greater")]
                     Some("inalloca") => Self::InAlloca(ty),
                     Some("sret") => Self::SRet(ty),
+                    Some("byref") => Self::ByRef(ty),
+                    #[cfg(feature = "llvm-13-or-greater")]
+                    Some("elementtype") => Self::ElementType(ty),
                     Some(s) => panic!("Unhandled value from lookup_param_attr: {:?}", s),
                     None => {
                         debug!("unknown type param attr {}", kind);
-                        Self::UnknownTypeAttribute(ty)
+                        Self::UnknownTypeAttribute {
+                            kind: attrsdata.unknown_attr_name(kind),
+                            ty,
+                        }
                     },
                 }
             }
         } else {
-            debug!("Encountered an unknown parameter attribute: neither enum, string, nor type");
-            Self::UnknownAttribute
+            // For LLVM 11 or lower, the C API can't identify type attributes, so
+            // they end up here; for LLVM 19+, so do constant-range attributes
+            let kind = unsafe { LLVMGetEnumAttributeKind(a) };
+            match attrsdata.lookup_param_attr(kind) {
+                #[cfg(feature = "llvm-11-or-lower")]
+                Some("byval") => Self::ByVal,
+                #[cfg(feature = "llvm-11")]
+                Some("preallocated") => Self::Preallocated,
+                #[cfg(feature = "llvm-11-or-lower")]
+                Some("sret") => Self::SRet,
+                #[cfg(feature = "llvm-19-or-greater")]
+                Some(name @ "range") | Some(name @ "initializes") => {
+                    let text = attrsdata.print_param_attr(a);
+                    let parsed = if name == "range" {
+                        Self::parse_range(&text, types)
+                    } else {
+                        Self::parse_initializes(&text)
+                    };
+                    parsed.unwrap_or_else(|| {
+                        debug!("Failed to parse {:?} attribute {:?}", name, text);
+                        Self::UnknownAttribute { kind: name.into() }
+                    })
+                },
+                _ => {
+                    debug!("Encountered an unknown parameter attribute: neither enum, string, nor type");
+                    Self::UnknownAttribute {
+                        kind: attrsdata.unknown_attr_name(kind),
+                    }
+                },
+            }
         }
     }
 
+    /// Parse `range(<ty> <lower>, <upper>)`
+    #[cfg(feature = "llvm-19-or-greater")]
+    fn parse_range(text: &str, types: &mut TypesBuilder) -> Option<Self> {
+        let inner = text.strip_prefix("range(")?.strip_suffix(')')?;
+        let (ty, bounds) = inner.split_once(' ')?;
+        let bits = ty.strip_prefix('i')?.parse().ok()?;
+        let (lower, upper) = bounds.split_once(',')?;
+        Some(Self::Range {
+            ty: types.int(bits),
+            lower: lower.trim().parse().ok()?,
+            upper: upper.trim().parse().ok()?,
+        })
+    }
+
+    /// Parse `initializes((<start>, <end>), ...)`
+    #[cfg(feature = "llvm-19-or-greater")]
+    fn parse_initializes(text: &str) -> Option<Self> {
+        let inner = text.strip_prefix("initializes(")?.strip_suffix(')')?;
+        inner
+            .split(')')
+            .map(|range| range.trim_start_matches(|c| c == ',' || c == ' ' || c == '('))
+            .filter(|range| !range.is_empty())
+            .map(|range| {
+                let (start, end) = range.split_once(',')?;
+                Some((start.trim().parse().ok()?, end.trim().parse().ok()?))
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Self::Initializes)
+    }
+
     #[cfg(feature = "llvm-11-or-lower")]
     fn is_type_attr(_a: LLVMAttributeRef) -> bool {
         false
@@ -1,12 +1,13 @@
 use crate::constant::ConstantRef;
 use crate::debugloc::{DebugLoc, HasDebugLoc};
-use crate::function::{CallingConvention, FunctionAttribute, ParameterAttribute};
+#[cfg(feature = "llvm-16-or-greater")]
+use crate::function::MemoryEffect;
+use crate::function::{CallingConvention, FunctionAttribute, Parameter, ParameterAttribute};
+use crate::module::Module;
 use crate::name::Name;
 use crate::operand::Operand;
 use crate::predicates::*;
-#[cfg(feature = "llvm-14-or-lower")]
-use crate::types::NamedStructDef;
-use crate::types::{Type, TypeRef, Typed, Types};
+use crate::types::{NamedStructDef, Type, TypeRef, Typed, Types};
 use either::Either;
 use std::convert::TryFrom;
 use std::fmt::{self, Debug, Display};
@@ -274,6 +275,68 @@ impl Instruction {
         }
     }
 
+    /// Like [`try_get_result()`](#method.try_get_result), but with a mutable
+    /// reference, e.g. for renaming the result.
+    pub fn try_get_result_mut(&mut self) -> Option<&mut Name> {
+        match self {
+            Instruction::Add(i) => Some(&mut i.dest),
+            Instruction::Sub(i) => Some(&mut i.dest),
+            Instruction::Mul(i) => Some(&mut i.dest),
+            Instruction::UDiv(i) => Some(&mut i.dest),
+            Instruction::SDiv(i) => Some(&mut i.dest),
+            Instruction::URem(i) => Some(&mut i.dest),
+            Instruction::SRem(i) => Some(&mut i.dest),
+            Instruction::And(i) => Some(&mut i.dest),
+            Instruction::Or(i) => Some(&mut i.dest),
+            Instruction::Xor(i) => Some(&mut i.dest),
+            Instruction::Shl(i) => Some(&mut i.dest),
+            Instruction::LShr(i) => Some(&mut i.dest),
+            Instruction::AShr(i) => Some(&mut i.dest),
+            Instruction::FAdd(i) => Some(&mut i.dest),
+            Instruction::FSub(i) => Some(&mut i.dest),
+            Instruction::FMul(i) => Some(&mut i.dest),
+            Instruction::FDiv(i) => Some(&mut i.dest),
+            Instruction::FRem(i) => Some(&mut i.dest),
+            Instruction::FNeg(i) => Some(&mut i.dest),
+            Instruction::ExtractElement(i) => Some(&mut i.dest),
+            Instruction::InsertElement(i) => Some(&mut i.dest),
+            Instruction::ShuffleVector(i) => Some(&mut i.dest),
+            Instruction::ExtractValue(i) => Some(&mut i.dest),
+            Instruction::InsertValue(i) => Some(&mut i.dest),
+            Instruction::Alloca(i) => Some(&mut i.dest),
+            Instruction::Load(i) => Some(&mut i.dest),
+            Instruction::Store(_) => None,
+            Instruction::Fence(_) => None,
+            Instruction::CmpXchg(i) => Some(&mut i.dest),
+            Instruction::AtomicRMW(i) => Some(&mut i.dest),
+            Instruction::GetElementPtr(i) => Some(&mut i.dest),
+            Instruction::Trunc(i) => Some(&mut i.dest),
+            Instruction::ZExt(i) => Some(&mut i.dest),
+            Instruction::SExt(i) => Some(&mut i.dest),
+            Instruction::FPTrunc(i) => Some(&mut i.dest),
+            Instruction::FPExt(i) => Some(&mut i.dest),
+            Instruction::FPToUI(i) => Some(&mut i.dest),
+            Instruction::FPToSI(i) => Some(&mut i.dest),
+            Instruction::UIToFP(i) => Some(&mut i.dest),
+            Instruction::SIToFP(i) => Some(&mut i.dest),
+            Instruction::PtrToInt(i) => Some(&mut i.dest),
+            Instruction::IntToPtr(i) => Some(&mut i.dest),
+            Instruction::BitCast(i) => Some(&mut i.dest),
+            Instruction::AddrSpaceCast(i) => Some(&mut i.dest),
+            Instruction::ICmp(i) => Some(&mut i.dest),
+            Instruction::FCmp(i) => Some(&mut i.dest),
+            Instruction::Phi(i) => Some(&mut i.dest),
+            Instruction::Select(i) => Some(&mut i.dest),
+            #[cfg(feature = "llvm-10-or-greater")]
+            Instruction::Freeze(i) => Some(&mut i.dest),
+            Instruction::Call(i) => i.dest.as_mut(),
+            Instruction::VAArg(i) => Some(&mut i.dest),
+            Instruction::LandingPad(i) => Some(&mut i.dest),
+            Instruction::CatchPad(i) => Some(&mut i.dest),
+            Instruction::CleanupPad(i) => Some(&mut i.dest),
+        }
+    }
+
     /// Whether the `Instruction` is atomic
     pub fn is_atomic(&self) -> bool {
         match self {
@@ -418,6 +481,8 @@ pub trait BinaryOp: HasResult {
 }
 
 pub mod groups;
+pub mod intrinsics;
+pub use intrinsics::{Intrinsic, IntrinsicArgs, IntrinsicCall};
 
 impl Instruction {
     /// Determine if the `Instruction` is one of the ones in
@@ -677,6 +742,26 @@ macro_rules! binop_display_with_flags {
     };
 }
 
+// Use on floating-point binops, which carry `FastMathFlags` (in LLVM 18+)
+macro_rules! binop_display_with_fast_math_flags {
+    ($inst:ty, $dispname:expr) => {
+        impl Display for $inst {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{} = {}", &self.dest, $dispname)?;
+                #[cfg(feature = "llvm-18-or-greater")]
+                if !self.fast_math_flags.is_empty() {
+                    write!(f, " {}", &self.fast_math_flags)?;
+                }
+                write!(f, " {}, {}", &self.operand0, &self.operand1)?;
+                if self.debugloc.is_some() {
+                    write!(f, " (with debugloc)")?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
 // Use on unops where the result type is the same as the operand type
 macro_rules! unop_same_type {
     ($inst:ty, $dispname:expr) => {
@@ -1036,7 +1121,8 @@ pub struct FAdd {
     pub operand0: Operand,
     pub operand1: Operand,
     pub dest: Name,
-    // pub fast_math_flags: FastMathFlags,  // getters for these seem to not be exposed in the LLVM C API, only in the C++ one
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -1044,7 +1130,7 @@ pub struct FAdd {
 impl_inst!(FAdd, FAdd);
 impl_binop!(FAdd, FAdd);
 binop_same_type!(FAdd);
-binop_display!(FAdd, "fadd");
+binop_display_with_fast_math_flags!(FAdd, "fadd");
 
 /// Floating-point subtract.
 /// See [LLVM 14 docs on the 'fsub' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#fsub-instruction)
@@ -1053,7 +1139,8 @@ pub struct FSub {
     pub operand0: Operand,
     pub operand1: Operand,
     pub dest: Name,
-    // pub fast_math_flags: FastMathFlags,  // getters for these seem to not be exposed in the LLVM C API, only in the C++ one
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -1061,7 +1148,7 @@ pub struct FSub {
 impl_inst!(FSub, FSub);
 impl_binop!(FSub, FSub);
 binop_same_type!(FSub);
-binop_display!(FSub, "fsub");
+binop_display_with_fast_math_flags!(FSub, "fsub");
 
 /// Floating-point multiply.
 /// See [LLVM 14 docs on the 'fmul' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#fmul-instruction)
@@ -1070,7 +1157,8 @@ pub struct FMul {
     pub operand0: Operand,
     pub operand1: Operand,
     pub dest: Name,
-    // pub fast_math_flags: FastMathFlags,  // getters for these seem to not be exposed in the LLVM C API, only in the C++ one
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -1078,7 +1166,7 @@ pub struct FMul {
 impl_inst!(FMul, FMul);
 impl_binop!(FMul, FMul);
 binop_same_type!(FMul);
-binop_display!(FMul, "fmul");
+binop_display_with_fast_math_flags!(FMul, "fmul");
 
 /// Floating-point divide.
 /// See [LLVM 14 docs on the 'fdiv' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#fdiv-instruction)
@@ -1087,7 +1175,8 @@ pub struct FDiv {
     pub operand0: Operand,
     pub operand1: Operand,
     pub dest: Name,
-    // pub fast_math_flags: FastMathFlags,  // getters for these seem to not be exposed in the LLVM C API, only in the C++ one
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -1095,7 +1184,7 @@ pub struct FDiv {
 impl_inst!(FDiv, FDiv);
 impl_binop!(FDiv, FDiv);
 binop_same_type!(FDiv);
-binop_display!(FDiv, "fdiv");
+binop_display_with_fast_math_flags!(FDiv, "fdiv");
 
 /// Floating-point remainder.
 /// See [LLVM 14 docs on the 'frem' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#frem-instruction)
@@ -1104,7 +1193,8 @@ pub struct FRem {
     pub operand0: Operand,
     pub operand1: Operand,
     pub dest: Name,
-    // pub fast_math_flags: FastMathFlags,  // getters for these seem to not be exposed in the LLVM C API, only in the C++ one
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -1112,7 +1202,7 @@ pub struct FRem {
 impl_inst!(FRem, FRem);
 impl_binop!(FRem, FRem);
 binop_same_type!(FRem);
-binop_display!(FRem, "frem");
+binop_display_with_fast_math_flags!(FRem, "frem");
 
 /// Floating-point unary negation.
 /// See [LLVM 14 docs on the 'fneg' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#fneg-instruction)
@@ -1120,13 +1210,35 @@ binop_display!(FRem, "frem");
 pub struct FNeg {
     pub operand: Operand,
     pub dest: Name,
-    // pub fast_math_flags: FastMathFlags,  // getters for these seem to not be exposed in the LLVM C API, only in the C++ one
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
 
 impl_inst!(FNeg, FNeg);
-unop_same_type!(FNeg, "fneg");
+impl_unop!(FNeg);
+
+impl Typed for FNeg {
+    fn get_type(&self, types: &Types) -> TypeRef {
+        types.type_of(self.get_operand())
+    }
+}
+
+impl Display for FNeg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = fneg", &self.dest)?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        if !self.fast_math_flags.is_empty() {
+            write!(f, " {}", &self.fast_math_flags)?;
+        }
+        write!(f, " {}", &self.operand)?;
+        if self.debugloc.is_some() {
+            write!(f, " (with debugloc)")?;
+        }
+        Ok(())
+    }
+}
 
 /// Get an element from a vector at a specified index.
 /// See [LLVM 14 docs on the 'extractelement' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#extractelement-instruction)
@@ -1277,27 +1389,43 @@ impl_hasresult!(ExtractValue);
 
 impl Typed for ExtractValue {
     fn get_type(&self, types: &Types) -> TypeRef {
-        ev_type(types.type_of(&self.aggregate), self.indices.iter().copied())
+        ev_type(
+            types.type_of(&self.aggregate),
+            self.indices.iter().copied(),
+            types,
+        )
     }
 }
 
-fn ev_type(cur_type: TypeRef, mut indices: impl Iterator<Item = u32>) -> TypeRef {
-    match indices.next() {
-        None => cur_type,
-        Some(index) => match cur_type.as_ref() {
-            Type::ArrayType { element_type, .. } => ev_type(element_type.clone(), indices),
-            Type::StructType { element_types, .. } => ev_type(
-                element_types
-                    .get(index as usize)
-                    .expect("ExtractValue index out of range")
-                    .clone(),
-                indices,
-            ),
-            _ => panic!(
-                "ExtractValue from something that's not ArrayType or StructType; its type is {:?}",
-                cur_type
-            ),
+fn ev_type(cur_type: TypeRef, mut indices: impl Iterator<Item = u32>, types: &Types) -> TypeRef {
+    let index = match indices.next() {
+        None => return cur_type,
+        Some(index) => index,
+    };
+    let cur_type = match cur_type.as_ref() {
+        Type::NamedStructType { name } => match types.named_struct_def(name) {
+            None => panic!("Named struct without a definition (name {:?})", name),
+            Some(NamedStructDef::Opaque) => {
+                panic!("ExtractValue from an opaque struct type (name {:?})", name)
+            },
+            Some(NamedStructDef::Defined(ty)) => ty.clone(),
         },
+        _ => cur_type,
+    };
+    match cur_type.as_ref() {
+        Type::ArrayType { element_type, .. } => ev_type(element_type.clone(), indices, types),
+        Type::StructType { element_types, .. } => ev_type(
+            element_types
+                .get(index as usize)
+                .expect("ExtractValue index out of range")
+                .clone(),
+            indices,
+            types,
+        ),
+        _ => panic!(
+            "ExtractValue from something that's not ArrayType, StructType, or NamedStructType; its type is {:?}",
+            cur_type
+        ),
     }
 }
 
@@ -1648,6 +1776,12 @@ pub struct GetElementPtr {
     pub indices: Vec<Operand>,
     pub dest: Name,
     pub in_bounds: bool,
+    /// "No unsigned signed wrap". Implied by `in_bounds`.
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub nusw: bool,
+    /// "No unsigned wrap"
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub nuw: bool,
     pub debugloc: Option<DebugLoc>,
     #[cfg(feature = "llvm-14-or-greater")]
     pub source_element_type: TypeRef, // --TODO not yet implemented-- pub metadata: InstructionMetadata,
@@ -1719,6 +1853,14 @@ impl Display for GetElementPtr {
         if self.in_bounds {
             write!(f, "inbounds ")?;
         }
+        #[cfg(feature = "llvm-19-or-greater")]
+        if self.nusw && !self.in_bounds {
+            write!(f, "nusw ")?;
+        }
+        #[cfg(feature = "llvm-19-or-greater")]
+        if self.nuw {
+            write!(f, "nuw ")?;
+        }
         write!(f, "{}", &self.address)?;
         for idx in &self.indices {
             write!(f, ", {}", idx)?;
@@ -1737,12 +1879,18 @@ pub struct Trunc {
     pub operand: Operand,
     pub to_type: TypeRef,
     pub dest: Name,
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub nuw: bool,
+    #[cfg(feature = "llvm-19-or-greater")]
+    pub nsw: bool,
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
 
 impl_inst!(Trunc, Trunc);
-unop_explicitly_typed!(Trunc, "trunc");
+impl_unop!(Trunc);
+explicitly_typed!(Trunc);
+unop_typed_display_with_flags!(Trunc, "trunc", ("nuw" ; nuw ; "llvm-19-or-greater", "nsw" ; nsw ; "llvm-19-or-greater"));
 
 /// Zero-extend.
 /// See [LLVM 14 docs on the 'zext' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#zext-to-instruction)
@@ -1971,6 +2119,8 @@ pub struct FCmp {
     pub operand0: Operand,
     pub operand1: Operand,
     pub dest: Name,
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -1998,10 +2148,15 @@ impl Typed for FCmp {
 
 impl Display for FCmp {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = fcmp", &self.dest)?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        if !self.fast_math_flags.is_empty() {
+            write!(f, " {}", &self.fast_math_flags)?;
+        }
         write!(
             f,
-            "{} = fcmp {} {}, {}",
-            &self.dest, &self.predicate, &self.operand0, &self.operand1,
+            " {} {}, {}",
+            &self.predicate, &self.operand0, &self.operand1,
         )?;
         if self.debugloc.is_some() {
             write!(f, " (with debugloc)")?;
@@ -2016,6 +2171,8 @@ pub struct Phi {
     pub incoming_values: Vec<(Operand, Name)>,
     pub dest: Name,
     pub to_type: TypeRef,
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -2030,10 +2187,15 @@ impl Display for Phi {
             .incoming_values
             .get(0)
             .expect("Phi with no incoming values");
+        write!(f, "{} = phi", &self.dest)?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        if !self.fast_math_flags.is_empty() {
+            write!(f, " {}", &self.fast_math_flags)?;
+        }
         write!(
             f,
-            "{} = phi {} [ {}, {} ]",
-            &self.dest, &self.to_type, first_val, first_label,
+            " {} [ {}, {} ]",
+            &self.to_type, first_val, first_label,
         )?;
         for (val, label) in &self.incoming_values[1 ..] {
             write!(f, ", [ {}, {} ]", val, label)?;
@@ -2053,6 +2215,8 @@ pub struct Select {
     pub true_value: Operand,
     pub false_value: Operand,
     pub dest: Name,
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -2070,10 +2234,15 @@ impl Typed for Select {
 
 impl Display for Select {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = select", &self.dest)?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        if !self.fast_math_flags.is_empty() {
+            write!(f, " {}", &self.fast_math_flags)?;
+        }
         write!(
             f,
-            "{} = select {}, {}, {}",
-            &self.dest, &self.condition, &self.true_value, &self.false_value,
+            " {}, {}, {}",
+            &self.condition, &self.true_value, &self.false_value,
         )?;
         if self.debugloc.is_some() {
             write!(f, " (with debugloc)")?;
@@ -2102,15 +2271,21 @@ unop_same_type!(Freeze, "freeze");
 /// See [LLVM 14 docs on the 'call' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#call-instruction)
 #[derive(PartialEq, Clone, Debug, Hash)]
 pub struct Call {
-    pub function: Either<InlineAssembly, Operand>,
+    pub function: Either<Box<InlineAssembly>, Operand>, // boxed, as inline assembly is rare, but (in LLVM 18+) large enough to dominate the size of `Instruction`
     #[cfg(feature = "llvm-15-or-greater")]
     pub function_ty: TypeRef,
     pub arguments: Vec<(Operand, Vec<ParameterAttribute>)>,
     pub return_attributes: Vec<ParameterAttribute>,
     pub dest: Option<Name>, // will be None if the `function` returns void
     pub function_attributes: Vec<FunctionAttribute>, // llvm-hs has the equivalent of Vec<Either<GroupID, FunctionAttribute>>, but I'm not sure how the GroupID option comes up
-    pub is_tail_call: bool, // llvm-hs has the more sophisticated structure Option<TailCallKind>, but the LLVM C API just gives us true/false
+    pub is_tail_call: bool, // prior to LLVM 18, the LLVM C API just gives us true/false; see `tail_call_kind`
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub tail_call_kind: Option<TailCallKind>, // `None` for an ordinary call
     pub calling_convention: CallingConvention,
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub fast_math_flags: FastMathFlags, // prior to LLVM 18, no getter for this was exposed in the LLVM C API, only in the C++ one
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub operand_bundles: Vec<OperandBundle>, // getters for these were added to the LLVM C API in LLVM 18
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -2146,12 +2321,22 @@ impl Display for Call {
         if let Some(dest) = &self.dest {
             write!(f, "{} = ", dest)?;
         }
+        #[cfg(feature = "llvm-17-or-lower")]
         if self.is_tail_call {
             write!(f, "tail ")?;
         }
+        #[cfg(feature = "llvm-18-or-greater")]
+        if let Some(kind) = &self.tail_call_kind {
+            write!(f, "{} ", kind)?;
+        }
+        write!(f, "call ")?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        if !self.fast_math_flags.is_empty() {
+            write!(f, "{} ", &self.fast_math_flags)?;
+        }
         write!(
             f,
-            "call {}(",
+            "{}(",
             match &self.function {
                 Either::Left(_) => "<inline assembly>".into(),
                 Either::Right(op) => format!("{}", op),
@@ -2165,6 +2350,8 @@ impl Display for Call {
             }
         }
         write!(f, ")")?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        fmt_operand_bundles(f, &self.operand_bundles)?;
         if self.debugloc.is_some() {
             write!(f, " (with debugloc)")?;
         }
@@ -2172,6 +2359,230 @@ impl Display for Call {
     }
 }
 
+impl Call {
+    /// The function attributes which apply to this call: those on the call
+    /// site itself, plus those on the callee's `Function` or
+    /// `FunctionDeclaration` (looking through aliases).
+    ///
+    /// Where the call site and the callee both specify an attribute of the
+    /// same kind, they're combined as LLVM does: `memory(...)` effects are
+    /// intersected, since both restrictions hold. For other kinds (e.g., two
+    /// different `alignstack` values, or two string attributes with the same
+    /// key) the call site's takes precedence.
+    ///
+    /// For indirect calls, or calls to inline assembly, this is just the call
+    /// site's attributes.
+    pub fn effective_function_attributes(&self, module: &Module) -> Vec<FunctionAttribute> {
+        match self.callee(module) {
+            Some(callee) => merge_attributes(
+                &self.function_attributes,
+                callee.function_attributes,
+                combine_function_attrs,
+            ),
+            None => self.function_attributes.clone(),
+        }
+    }
+
+    /// The attributes which apply to the argument at index `arg_index`: those
+    /// on the call site, plus those on the corresponding parameter of the
+    /// callee (looking through aliases). As for
+    /// [`effective_function_attributes()`](#method.effective_function_attributes),
+    /// attributes of the same kind are combined: `align`, `dereferenceable` and
+    /// `dereferenceable_or_null` take the stronger (larger) of the two values,
+    /// and for other kinds the call site's attribute takes precedence.
+    ///
+    /// Panics if `arg_index` is out of range for this call's arguments.
+    pub fn effective_argument_attributes(
+        &self,
+        module: &Module,
+        arg_index: usize,
+    ) -> Vec<ParameterAttribute> {
+        let call_site = &self.arguments[arg_index].1;
+        match self
+            .callee(module)
+            .and_then(|callee| callee.parameters.get(arg_index)) // variadic arguments have no corresponding parameter
+        {
+            Some(param) => merge_attributes(call_site, &param.attributes, combine_param_attrs),
+            None => call_site.clone(),
+        }
+    }
+
+    /// The attributes which apply to the return value: those on the call
+    /// site, plus those on the callee's return value (looking through
+    /// aliases), combined as for
+    /// [`effective_argument_attributes()`](#method.effective_argument_attributes).
+    pub fn effective_return_attributes(&self, module: &Module) -> Vec<ParameterAttribute> {
+        match self.callee(module) {
+            Some(callee) => merge_attributes(
+                &self.return_attributes,
+                callee.return_attributes,
+                combine_param_attrs,
+            ),
+            None => self.return_attributes.clone(),
+        }
+    }
+
+    /// Find the `Function` or `FunctionDeclaration` this call directly calls,
+    /// looking through aliases (and, for typed pointers, bitcasts)
+    fn callee<'m>(&self, module: &'m Module) -> Option<Callee<'m>> {
+        let mut target = match &self.function {
+            Either::Right(Operand::ConstantOperand(c)) => c,
+            _ => return None,
+        };
+        // bound the number of aliases we'll look through, in case of cycles
+        for _ in 0 .. 16 {
+            let name = match target.as_ref() {
+                Constant::GlobalReference { name, .. } => name,
+                Constant::BitCast(bc) => {
+                    target = &bc.operand;
+                    continue;
+                },
+                Constant::AddrSpaceCast(asc) => {
+                    target = &asc.operand;
+                    continue;
+                },
+                _ => return None,
+            };
+            if let Name::Name(fname) = name {
+                if let Some(func) = module.get_func_by_name(fname) {
+                    return Some(Callee {
+                        function_attributes: &func.function_attributes,
+                        return_attributes: &func.return_attributes,
+                        parameters: &func.parameters,
+                    });
+                }
+                if let Some(decl) = module.get_func_decl_by_name(fname) {
+                    return Some(Callee {
+                        function_attributes: &decl.function_attributes,
+                        return_attributes: &decl.return_attributes,
+                        parameters: &decl.parameters,
+                    });
+                }
+            }
+            target = &module.get_global_alias_by_name(name)?.aliasee;
+        }
+        None
+    }
+}
+
+/// The attribute-bearing parts of a `Function` or `FunctionDeclaration`
+struct Callee<'m> {
+    function_attributes: &'m [FunctionAttribute],
+    return_attributes: &'m [ParameterAttribute],
+    parameters: &'m [Parameter],
+}
+
+/// Combine call-site attributes with callee attributes. `combine` returns the
+/// effective attribute for a call-site and a callee attribute of the same
+/// kind, or `None` if they're of different kinds.
+fn merge_attributes<A: Clone>(
+    call_site: &[A],
+    callee: &[A],
+    combine: impl Fn(&A, &A) -> Option<A>,
+) -> Vec<A> {
+    let mut merged = call_site.to_vec();
+    for attr in callee {
+        let combined = merged[.. call_site.len()]
+            .iter_mut()
+            .find_map(|cs| combine(cs, attr).map(|combined| (cs, combined)));
+        match combined {
+            Some((cs, combined)) => *cs = combined,
+            None => merged.push(attr.clone()),
+        }
+    }
+    merged
+}
+
+fn combine_function_attrs(
+    call_site: &FunctionAttribute,
+    callee: &FunctionAttribute,
+) -> Option<FunctionAttribute> {
+    match (call_site, callee) {
+        (
+            FunctionAttribute::StringAttribute { kind: a, .. },
+            FunctionAttribute::StringAttribute { kind: b, .. },
+        )
+        | (
+            FunctionAttribute::UnknownAttribute { kind: a },
+            FunctionAttribute::UnknownAttribute { kind: b },
+        ) => {
+            if a == b {
+                Some(call_site.clone())
+            } else {
+                None
+            }
+        },
+        #[cfg(feature = "llvm-16-or-greater")]
+        (
+            FunctionAttribute::Memory {
+                default: default_a,
+                argmem: argmem_a,
+                inaccessible_mem: inaccessible_mem_a,
+            },
+            FunctionAttribute::Memory {
+                default: default_b,
+                argmem: argmem_b,
+                inaccessible_mem: inaccessible_mem_b,
+            },
+        ) => Some(FunctionAttribute::Memory {
+            default: intersect_memory_effects(default_a, default_b),
+            argmem: intersect_memory_effects(argmem_a, argmem_b),
+            inaccessible_mem: intersect_memory_effects(inaccessible_mem_a, inaccessible_mem_b),
+        }),
+        _ if std::mem::discriminant(call_site) == std::mem::discriminant(callee) => {
+            Some(call_site.clone())
+        },
+        _ => None,
+    }
+}
+
+/// The accesses allowed by both `a` and `b`
+#[cfg(feature = "llvm-16-or-greater")]
+fn intersect_memory_effects(a: &MemoryEffect, b: &MemoryEffect) -> MemoryEffect {
+    match (a, b) {
+        (MemoryEffect::ReadWrite, other) | (other, MemoryEffect::ReadWrite) => other.clone(),
+        (MemoryEffect::Read, MemoryEffect::Read) => MemoryEffect::Read,
+        (MemoryEffect::Write, MemoryEffect::Write) => MemoryEffect::Write,
+        _ => MemoryEffect::None,
+    }
+}
+
+fn combine_param_attrs(
+    call_site: &ParameterAttribute,
+    callee: &ParameterAttribute,
+) -> Option<ParameterAttribute> {
+    match (call_site, callee) {
+        (
+            ParameterAttribute::StringAttribute { kind: a, .. },
+            ParameterAttribute::StringAttribute { kind: b, .. },
+        )
+        | (
+            ParameterAttribute::UnknownAttribute { kind: a },
+            ParameterAttribute::UnknownAttribute { kind: b },
+        ) => {
+            if a == b {
+                Some(call_site.clone())
+            } else {
+                None
+            }
+        },
+        (ParameterAttribute::Alignment(a), ParameterAttribute::Alignment(b)) => {
+            Some(ParameterAttribute::Alignment(*a.max(b)))
+        },
+        (ParameterAttribute::Dereferenceable(a), ParameterAttribute::Dereferenceable(b)) => {
+            Some(ParameterAttribute::Dereferenceable(*a.max(b)))
+        },
+        (
+            ParameterAttribute::DereferenceableOrNull(a),
+            ParameterAttribute::DereferenceableOrNull(b),
+        ) => Some(ParameterAttribute::DereferenceableOrNull(*a.max(b))),
+        _ if std::mem::discriminant(call_site) == std::mem::discriminant(callee) => {
+            Some(call_site.clone())
+        },
+        _ => None,
+    }
+}
+
 /// Used to access variadic arguments passed to a function.
 /// See [LLVM 14 docs on the 'va_arg' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#va-arg-instruction)
 #[derive(PartialEq, Clone, Debug, Hash)]
@@ -2233,6 +2644,9 @@ impl Display for LandingPad {
         if self.cleanup {
             write!(f, " cleanup")?;
         }
+        for clause in &self.clauses {
+            write!(f, " {}", clause)?;
+        }
         if self.debugloc.is_some() {
             write!(f, " (with debugloc)")?;
         }
@@ -2324,17 +2738,26 @@ impl Display for CleanupPad {
     }
 }
 
-/*
+/// See [LLVM 14 docs on the 'call' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#call-instruction)
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum TailCallKind {
     Tail,
     MustTail,
     NoTail,
 }
-*/
+
+impl Display for TailCallKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TailCallKind::Tail => write!(f, "tail"),
+            TailCallKind::MustTail => write!(f, "musttail"),
+            TailCallKind::NoTail => write!(f, "notail"),
+        }
+    }
+}
 
 /// See [LLVM 14 docs on Fast-Math Flags](https://releases.llvm.org/14.0.0/docs/LangRef.html#fastmath)
-#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Hash)]
 #[allow(non_snake_case)]
 pub struct FastMathFlags {
     pub allow_reassoc: bool,
@@ -2346,6 +2769,47 @@ pub struct FastMathFlags {
     pub approx_func: bool,
 }
 
+impl FastMathFlags {
+    /// Are no flags set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Are all flags set (i.e., `fast`)
+    pub fn is_fast(&self) -> bool {
+        self.allow_reassoc
+            && self.no_NaNs
+            && self.no_Infs
+            && self.no_signed_zeros
+            && self.allow_reciprocal
+            && self.allow_contract
+            && self.approx_func
+    }
+}
+
+impl Display for FastMathFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_fast() {
+            return write!(f, "fast");
+        }
+        let flags = [
+            (self.allow_reassoc, "reassoc"),
+            (self.no_NaNs, "nnan"),
+            (self.no_Infs, "ninf"),
+            (self.no_signed_zeros, "nsz"),
+            (self.allow_reciprocal, "arcp"),
+            (self.allow_contract, "contract"),
+            (self.approx_func, "afn"),
+        ];
+        let names: Vec<&str> = flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, name)| *name)
+            .collect();
+        write!(f, "{}", names.join(" "))
+    }
+}
+
 /// See [LLVM 14 docs on Atomic Memory Ordering Constraints](https://releases.llvm.org/14.0.0/docs/LangRef.html#ordering)
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
 pub struct Atomicity {
@@ -2397,6 +2861,52 @@ impl Display for MemoryOrdering {
     }
 }
 
+/// An operand bundle attached to a `Call`, `Invoke`, or `CallBr`.
+/// See [LLVM 14 docs on Operand Bundles](https://releases.llvm.org/14.0.0/docs/LangRef.html#operand-bundles)
+#[cfg(feature = "llvm-18-or-greater")]
+#[derive(PartialEq, Clone, Debug, Hash)]
+pub struct OperandBundle {
+    /// e.g., `"deopt"`, `"funclet"`, or `"clang.arc.attachedcall"`
+    pub tag: String,
+    pub inputs: Vec<Operand>,
+}
+
+#[cfg(feature = "llvm-18-or-greater")]
+impl Display for OperandBundle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"(", &self.tag)?;
+        for (i, input) in self.inputs.iter().enumerate() {
+            if i == self.inputs.len() - 1 {
+                write!(f, "{}", input)?;
+            } else {
+                write!(f, "{}, ", input)?;
+            }
+        }
+        write!(f, ")")
+    }
+}
+
+/// Format the operand bundles of a `Call`, `Invoke`, or `CallBr`, if it has
+/// any, e.g. ` [ "deopt"(i32 0) ]`
+#[cfg(feature = "llvm-18-or-greater")]
+pub(crate) fn fmt_operand_bundles(
+    f: &mut fmt::Formatter,
+    operand_bundles: &[OperandBundle],
+) -> fmt::Result {
+    if operand_bundles.is_empty() {
+        return Ok(());
+    }
+    write!(f, " [ ")?;
+    for (i, bundle) in operand_bundles.iter().enumerate() {
+        if i == operand_bundles.len() - 1 {
+            write!(f, "{}", bundle)?;
+        } else {
+            write!(f, "{}, ", bundle)?;
+        }
+    }
+    write!(f, " ]")
+}
+
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
 pub struct InlineAssembly {
     #[cfg(feature = "llvm-18-or-greater")]
@@ -2482,22 +2992,25 @@ impl Display for RMWBinOp {
     }
 }
 
-// --TODO this seems to be the data structure we want. But see notes on
-// LandingPadClause::from_llvm_ref()
-/*
+/// See [LLVM 14 docs on the 'landingpad' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#landingpad-instruction)
 #[derive(PartialEq, Clone, Debug, Hash)]
 pub enum LandingPadClause {
-    Catch(Constant),
-    Filter(Constant),
+    /// The landing pad catches exceptions of the type described by this
+    /// type-info constant (typically a reference to a typeinfo global)
+    Catch(ConstantRef),
+    /// The landing pad filters exceptions against the type-info constants in
+    /// this array constant (an empty array matches every exception)
+    Filter(ConstantRef),
+}
+
+impl Display for LandingPadClause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LandingPadClause::Catch(c) => write!(f, "catch {}", c),
+            LandingPadClause::Filter(c) => write!(f, "filter {}", c),
+        }
+    }
 }
-*/
-// Instead we have this for now
-/// See [LLVM 14 docs on the 'landingpad' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#landingpad-instruction)
-///
-/// `LandingPadClause` needs more fields, but the necessary getter functions are
-/// apparently not exposed in the LLVM C API (only the C++ API)
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
-pub struct LandingPadClause {}
 
 // ********* //
 // from_llvm //
@@ -2513,7 +3026,9 @@ use llvm_sys::LLVMAtomicOrdering;
 #[cfg(feature = "llvm-10-or-greater")]
 use llvm_sys::LLVMAtomicRMWBinOp;
 use llvm_sys::LLVMOpcode;
-use llvm_sys::LLVMTypeKind::LLVMVoidTypeKind;
+#[cfg(feature = "llvm-18-or-greater")]
+use llvm_sys::LLVMTailCallKind;
+use llvm_sys::LLVMTypeKind::{LLVMArrayTypeKind, LLVMVoidTypeKind};
 #[cfg(feature = "llvm-11-or-greater")]
 use std::convert::TryInto;
 
@@ -2724,6 +3239,37 @@ macro_rules! binop_from_llvm_with_flags {
     };
 }
 
+macro_rules! binop_from_llvm_with_fast_math_flags {
+    ($inst:ident) => {
+        impl $inst {
+            pub(crate) fn from_llvm_ref(
+                inst: LLVMValueRef,
+                ctx: &mut ModuleContext,
+                func_ctx: &mut FunctionContext,
+            ) -> Self {
+                assert_eq!(unsafe { LLVMGetNumOperands(inst) }, 2);
+                Self {
+                    operand0: Operand::from_llvm_ref(
+                        unsafe { LLVMGetOperand(inst, 0) },
+                        ctx,
+                        func_ctx,
+                    ),
+                    operand1: Operand::from_llvm_ref(
+                        unsafe { LLVMGetOperand(inst, 1) },
+                        ctx,
+                        func_ctx,
+                    ),
+                    dest: Name::name_or_num(unsafe { get_value_name(inst) }, &mut func_ctx.ctr),
+                    #[cfg(feature = "llvm-18-or-greater")]
+                    fast_math_flags: FastMathFlags::from_llvm_ref(inst),
+                    debugloc: DebugLoc::from_llvm_with_col(inst, &mut ctx.string_interner),
+                    // metadata: InstructionMetadata::from_llvm_inst(inst),
+                }
+            }
+        }
+    };
+}
+
 binop_from_llvm_with_flags!(Add, (nuw = LLVMGetNUW ; "llvm-17-or-greater", nsw = LLVMGetNSW ; "llvm-17-or-greater"));
 binop_from_llvm_with_flags!(Sub, (nuw = LLVMGetNUW ; "llvm-17-or-greater", nsw = LLVMGetNSW ; "llvm-17-or-greater"));
 binop_from_llvm_with_flags!(Mul, (nuw = LLVMGetNUW ; "llvm-17-or-greater", nsw = LLVMGetNSW ; "llvm-17-or-greater"));
@@ -2738,12 +3284,29 @@ binop_from_llvm!(URem);
 binop_from_llvm!(SRem);
 binop_from_llvm!(And);
 binop_from_llvm!(Xor);
-binop_from_llvm!(FAdd);
-binop_from_llvm!(FSub);
-binop_from_llvm!(FMul);
-binop_from_llvm!(FDiv);
-binop_from_llvm!(FRem);
-unop_from_llvm!(FNeg);
+binop_from_llvm_with_fast_math_flags!(FAdd);
+binop_from_llvm_with_fast_math_flags!(FSub);
+binop_from_llvm_with_fast_math_flags!(FMul);
+binop_from_llvm_with_fast_math_flags!(FDiv);
+binop_from_llvm_with_fast_math_flags!(FRem);
+
+impl FNeg {
+    pub(crate) fn from_llvm_ref(
+        inst: LLVMValueRef,
+        ctx: &mut ModuleContext,
+        func_ctx: &mut FunctionContext,
+    ) -> Self {
+        assert_eq!(unsafe { LLVMGetNumOperands(inst) }, 1);
+        Self {
+            operand: Operand::from_llvm_ref(unsafe { LLVMGetOperand(inst, 0) }, ctx, func_ctx),
+            dest: Name::name_or_num(unsafe { get_value_name(inst) }, &mut func_ctx.ctr),
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::from_llvm_ref(inst),
+            debugloc: DebugLoc::from_llvm_with_col(inst, &mut ctx.string_interner),
+            // metadata: InstructionMetadata::from_llvm_inst(inst),
+        }
+    }
+}
 #[cfg(feature = "llvm-10-or-greater")]
 unop_from_llvm!(Freeze);
 
@@ -3034,6 +3597,9 @@ impl GetElementPtr {
         ctx: &mut ModuleContext,
         func_ctx: &mut FunctionContext,
     ) -> Self {
+        // bits as defined by the `LLVMGEPNoWrapFlags` enum in the LLVM C API
+        #[cfg(feature = "llvm-19-or-greater")]
+        let nowrap_flags = unsafe { LLVMGEPGetNoWrapFlags(inst) };
         Self {
             address: Operand::from_llvm_ref(unsafe { LLVMGetOperand(inst, 0) }, ctx, func_ctx),
             indices: {
@@ -3046,6 +3612,10 @@ impl GetElementPtr {
             },
             dest: Name::name_or_num(unsafe { get_value_name(inst) }, &mut func_ctx.ctr),
             in_bounds: unsafe { LLVMIsInBounds(inst) } != 0,
+            #[cfg(feature = "llvm-19-or-greater")]
+            nusw: nowrap_flags & (1 << 1) != 0,
+            #[cfg(feature = "llvm-19-or-greater")]
+            nuw: nowrap_flags & (1 << 2) != 0,
             debugloc: DebugLoc::from_llvm_with_col(inst, &mut ctx.string_interner),
             #[cfg(feature = "llvm-14-or-greater")]
             source_element_type: ctx
@@ -3115,7 +3685,7 @@ macro_rules! typed_unop_from_llvm_with_flags {
 
 typed_unop_from_llvm_with_flags!(ZExt, (nneg = LLVMGetNNeg ; "llvm-18-or-greater"));
 
-typed_unop_from_llvm!(Trunc);
+typed_unop_from_llvm_with_flags!(Trunc, (nuw = LLVMGetNUW ; "llvm-19-or-greater", nsw = LLVMGetNSW ; "llvm-19-or-greater"));
 typed_unop_from_llvm!(SExt);
 typed_unop_from_llvm!(FPTrunc);
 typed_unop_from_llvm!(FPExt);
@@ -3158,6 +3728,8 @@ impl FCmp {
             operand0: Operand::from_llvm_ref(unsafe { LLVMGetOperand(inst, 0) }, ctx, func_ctx),
             operand1: Operand::from_llvm_ref(unsafe { LLVMGetOperand(inst, 1) }, ctx, func_ctx),
             dest: Name::name_or_num(unsafe { get_value_name(inst) }, &mut func_ctx.ctr),
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::from_llvm_ref(inst),
             debugloc: DebugLoc::from_llvm_with_col(inst, &mut ctx.string_interner),
             // metadata: InstructionMetadata::from_llvm_inst(inst),
         }
@@ -3191,6 +3763,8 @@ impl Phi {
             },
             dest: Name::name_or_num(unsafe { get_value_name(inst) }, &mut func_ctx.ctr),
             to_type: ctx.types.type_from_llvm_ref(unsafe { LLVMTypeOf(inst) }),
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::from_llvm_ref(inst),
             debugloc: DebugLoc::from_llvm_with_col(inst, &mut ctx.string_interner),
             // metadata: InstructionMetadata::from_llvm_inst(inst),
         }
@@ -3209,6 +3783,8 @@ impl Select {
             true_value: Operand::from_llvm_ref(unsafe { LLVMGetOperand(inst, 1) }, ctx, func_ctx),
             false_value: Operand::from_llvm_ref(unsafe { LLVMGetOperand(inst, 2) }, ctx, func_ctx),
             dest: Name::name_or_num(unsafe { get_value_name(inst) }, &mut func_ctx.ctr),
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::from_llvm_ref(inst),
             debugloc: DebugLoc::from_llvm_with_col(inst, &mut ctx.string_interner),
             // metadata: InstructionMetadata::from_llvm_inst(inst),
         }
@@ -3217,13 +3793,15 @@ impl Select {
 
 // just the logic shared by Call and Invoke. Not a public struct, just an implementation convenience.
 pub(crate) struct CallInfo {
-    pub function: Either<InlineAssembly, Operand>,
+    pub function: Either<Box<InlineAssembly>, Operand>,
     #[cfg(feature = "llvm-15-or-greater")]
     pub function_ty: TypeRef,
     pub arguments: Vec<(Operand, Vec<ParameterAttribute>)>,
     pub return_attributes: Vec<ParameterAttribute>,
     pub function_attributes: Vec<FunctionAttribute>,
     pub calling_convention: CallingConvention,
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub operand_bundles: Vec<OperandBundle>,
 }
 
 impl CallInfo {
@@ -3239,7 +3817,7 @@ impl CallInfo {
             function: {
                 let asm = unsafe { LLVMIsAInlineAsm(called_val) };
                 if !asm.is_null() {
-                    Either::Left(InlineAssembly::from_llvm_ref(asm, &mut ctx.types))
+                    Either::Left(Box::new(InlineAssembly::from_llvm_ref(asm, &mut ctx.types)))
                 } else {
                     Either::Right(Operand::from_llvm_ref(called_val, ctx, func_ctx))
                 }
@@ -3318,6 +3896,19 @@ impl CallInfo {
             calling_convention: CallingConvention::from_u32(unsafe {
                 LLVMGetInstructionCallConv(inst)
             }),
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles: {
+                let num_bundles = unsafe { LLVMGetNumOperandBundles(inst) };
+                (0 .. num_bundles)
+                    .map(|i| {
+                        OperandBundle::from_llvm_ref(
+                            unsafe { LLVMGetOperandBundleAtIndex(inst, i) },
+                            ctx,
+                            func_ctx,
+                        )
+                    })
+                    .collect()
+            },
         }
     }
 }
@@ -3348,7 +3939,13 @@ impl Call {
             },
             function_attributes: callinfo.function_attributes,
             is_tail_call: unsafe { LLVMIsTailCall(inst) } != 0,
+            #[cfg(feature = "llvm-18-or-greater")]
+            tail_call_kind: TailCallKind::from_llvm(unsafe { LLVMGetTailCallKind(inst) }),
             calling_convention: callinfo.calling_convention,
+            #[cfg(feature = "llvm-18-or-greater")]
+            fast_math_flags: FastMathFlags::from_llvm_ref(inst),
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles: callinfo.operand_bundles,
             debugloc: DebugLoc::from_llvm_with_col(inst, &mut ctx.string_interner),
             // metadata: InstructionMetadata::from_llvm_inst(inst),
         }
@@ -3383,7 +3980,7 @@ impl LandingPad {
             clauses: {
                 let num_clauses = unsafe { LLVMGetNumClauses(inst) };
                 (0 .. num_clauses)
-                    .map(|i| LandingPadClause::from_llvm_ref(unsafe { LLVMGetClause(inst, i) }))
+                    .map(|i| LandingPadClause::from_llvm_ref(unsafe { LLVMGetClause(inst, i) }, ctx))
                     .collect()
             },
             dest: Name::name_or_num(unsafe { get_value_name(inst) }, &mut func_ctx.ctr),
@@ -3500,6 +4097,66 @@ impl RMWBinOp {
     }
 }
 
+#[cfg(feature = "llvm-18-or-greater")]
+impl TailCallKind {
+    pub(crate) fn from_llvm(kind: LLVMTailCallKind) -> Option<Self> {
+        match kind {
+            LLVMTailCallKind::LLVMTailCallKindNone => None,
+            LLVMTailCallKind::LLVMTailCallKindTail => Some(TailCallKind::Tail),
+            LLVMTailCallKind::LLVMTailCallKindMustTail => Some(TailCallKind::MustTail),
+            LLVMTailCallKind::LLVMTailCallKindNoTail => Some(TailCallKind::NoTail),
+        }
+    }
+}
+
+#[cfg(feature = "llvm-18-or-greater")]
+impl FastMathFlags {
+    pub(crate) fn from_llvm_ref(inst: LLVMValueRef) -> Self {
+        // Only FP operations (including calls, phis, and selects of FP type)
+        // can carry fast-math flags, and the getter asserts that it is given one
+        if unsafe { LLVMCanValueUseFastMathFlags(inst) } == 0 {
+            return Self::default();
+        }
+        // bits as defined by the `LLVMFastMathFlags` enum in the LLVM C API
+        let flags = unsafe { LLVMGetFastMathFlags(inst) };
+        Self {
+            allow_reassoc: flags & (1 << 0) != 0,
+            no_NaNs: flags & (1 << 1) != 0,
+            no_Infs: flags & (1 << 2) != 0,
+            no_signed_zeros: flags & (1 << 3) != 0,
+            allow_reciprocal: flags & (1 << 4) != 0,
+            allow_contract: flags & (1 << 5) != 0,
+            approx_func: flags & (1 << 6) != 0,
+        }
+    }
+}
+
+#[cfg(feature = "llvm-18-or-greater")]
+impl OperandBundle {
+    pub(crate) fn from_llvm_ref(
+        bundle: LLVMOperandBundleRef,
+        ctx: &mut ModuleContext,
+        func_ctx: &mut FunctionContext,
+    ) -> Self {
+        let num_inputs = unsafe { LLVMGetNumOperandBundleArgs(bundle) };
+        let operand_bundle = Self {
+            tag: unsafe { get_operand_bundle_tag(bundle) },
+            inputs: (0 .. num_inputs)
+                .map(|i| {
+                    Operand::from_llvm_ref(
+                        unsafe { LLVMGetOperandBundleArgAtIndex(bundle, i) },
+                        ctx,
+                        func_ctx,
+                    )
+                })
+                .collect(),
+        };
+        // `LLVMGetOperandBundleAtIndex()` gives us a new bundle object which we own
+        unsafe { LLVMDisposeOperandBundle(bundle) };
+        operand_bundle
+    }
+}
+
 impl InlineAssembly {
     pub(crate) fn from_llvm_ref(asm: LLVMValueRef, types: &mut TypesBuilder) -> Self {
         Self {
@@ -3529,10 +4186,17 @@ impl AssemblyDialect {
 }
 
 impl LandingPadClause {
-    pub(crate) fn from_llvm_ref(_lpc: LLVMValueRef) -> Self {
+    pub(crate) fn from_llvm_ref(lpc: LLVMValueRef, ctx: &mut ModuleContext) -> Self {
         // The LLVM C API has an enum `LLVMLandingPadClauseTy`, but appears not
-        // to reference it. In particular, it's unclear how to tell whether a
-        // given clause is a `Catch` or a `Filter`.
-        Self {}
+        // to reference it. However, like the C++ `LandingPadInst::isFilter()`,
+        // we can distinguish the two by type: a filter clause is always an
+        // array of type-info constants, and a catch clause never is.
+        let is_filter = unsafe { LLVMGetTypeKind(LLVMTypeOf(lpc)) } == LLVMArrayTypeKind;
+        let clause = Constant::from_llvm_ref(lpc, ctx);
+        if is_filter {
+            LandingPadClause::Filter(clause)
+        } else {
+            LandingPadClause::Catch(clause)
+        }
     }
 }
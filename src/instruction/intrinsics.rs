@@ -0,0 +1,499 @@
+//! Typed recognition of calls to LLVM intrinsics.
+//!
+//! See [LLVM 14 docs on intrinsic functions](https://releases.llvm.org/14.0.0/docs/LangRef.html#intrinsic-functions)
+
+use super::{Call, Operand};
+use crate::constant::Constant;
+use crate::module::Module;
+use crate::name::Name;
+use crate::types::{FPType, TypeRef, Types};
+use either::Either;
+
+/// The LLVM intrinsics that [`Call::as_intrinsic`] recognizes.
+///
+/// Each variant corresponds to a family of overloaded intrinsics; for
+/// instance, `Memcpy` covers `llvm.memcpy.p0.p0.i32`, `llvm.memcpy.p0.p0.i64`,
+/// etc. The types the intrinsic is overloaded on are available in
+/// [`IntrinsicCall::overloaded_types`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum Intrinsic {
+    // Memory intrinsics
+    Memcpy,
+    MemcpyInline,
+    Memmove,
+    Memset,
+    MemsetInline,
+    // Arithmetic with overflow
+    SAddWithOverflow,
+    UAddWithOverflow,
+    SSubWithOverflow,
+    USubWithOverflow,
+    SMulWithOverflow,
+    UMulWithOverflow,
+    // Saturating arithmetic
+    SAddSat,
+    UAddSat,
+    SSubSat,
+    USubSat,
+    // Bit manipulation
+    Ctpop,
+    Ctlz,
+    Cttz,
+    Bswap,
+    Bitreverse,
+    Fshl,
+    Fshr,
+    // Vector reductions
+    VectorReduceAdd,
+    VectorReduceMul,
+    VectorReduceAnd,
+    VectorReduceOr,
+    VectorReduceXor,
+    VectorReduceSMax,
+    VectorReduceSMin,
+    VectorReduceUMax,
+    VectorReduceUMin,
+    VectorReduceFAdd,
+    VectorReduceFMul,
+    VectorReduceFMax,
+    VectorReduceFMin,
+    // Lifetime markers
+    LifetimeStart,
+    LifetimeEnd,
+    // Optimizer hints
+    Assume,
+    // Debug info
+    DbgDeclare,
+    DbgValue,
+    DbgAssign,
+    DbgLabel,
+}
+
+/// Base names of the intrinsics we recognize. Names which were used by older
+/// LLVM versions map to the same `Intrinsic` as their modern equivalents.
+const INTRINSIC_NAMES: &[(&str, Intrinsic)] = &[
+    ("llvm.memcpy", Intrinsic::Memcpy),
+    ("llvm.memcpy.inline", Intrinsic::MemcpyInline),
+    ("llvm.memmove", Intrinsic::Memmove),
+    ("llvm.memset", Intrinsic::Memset),
+    ("llvm.memset.inline", Intrinsic::MemsetInline),
+    ("llvm.sadd.with.overflow", Intrinsic::SAddWithOverflow),
+    ("llvm.uadd.with.overflow", Intrinsic::UAddWithOverflow),
+    ("llvm.ssub.with.overflow", Intrinsic::SSubWithOverflow),
+    ("llvm.usub.with.overflow", Intrinsic::USubWithOverflow),
+    ("llvm.smul.with.overflow", Intrinsic::SMulWithOverflow),
+    ("llvm.umul.with.overflow", Intrinsic::UMulWithOverflow),
+    ("llvm.sadd.sat", Intrinsic::SAddSat),
+    ("llvm.uadd.sat", Intrinsic::UAddSat),
+    ("llvm.ssub.sat", Intrinsic::SSubSat),
+    ("llvm.usub.sat", Intrinsic::USubSat),
+    ("llvm.ctpop", Intrinsic::Ctpop),
+    ("llvm.ctlz", Intrinsic::Ctlz),
+    ("llvm.cttz", Intrinsic::Cttz),
+    ("llvm.bswap", Intrinsic::Bswap),
+    ("llvm.bitreverse", Intrinsic::Bitreverse),
+    ("llvm.fshl", Intrinsic::Fshl),
+    ("llvm.fshr", Intrinsic::Fshr),
+    ("llvm.vector.reduce.add", Intrinsic::VectorReduceAdd),
+    ("llvm.vector.reduce.mul", Intrinsic::VectorReduceMul),
+    ("llvm.vector.reduce.and", Intrinsic::VectorReduceAnd),
+    ("llvm.vector.reduce.or", Intrinsic::VectorReduceOr),
+    ("llvm.vector.reduce.xor", Intrinsic::VectorReduceXor),
+    ("llvm.vector.reduce.smax", Intrinsic::VectorReduceSMax),
+    ("llvm.vector.reduce.smin", Intrinsic::VectorReduceSMin),
+    ("llvm.vector.reduce.umax", Intrinsic::VectorReduceUMax),
+    ("llvm.vector.reduce.umin", Intrinsic::VectorReduceUMin),
+    ("llvm.vector.reduce.fadd", Intrinsic::VectorReduceFAdd),
+    ("llvm.vector.reduce.fmul", Intrinsic::VectorReduceFMul),
+    ("llvm.vector.reduce.fmax", Intrinsic::VectorReduceFMax),
+    ("llvm.vector.reduce.fmin", Intrinsic::VectorReduceFMin),
+    // LLVM 11 and earlier
+    ("llvm.experimental.vector.reduce.add", Intrinsic::VectorReduceAdd),
+    ("llvm.experimental.vector.reduce.mul", Intrinsic::VectorReduceMul),
+    ("llvm.experimental.vector.reduce.and", Intrinsic::VectorReduceAnd),
+    ("llvm.experimental.vector.reduce.or", Intrinsic::VectorReduceOr),
+    ("llvm.experimental.vector.reduce.xor", Intrinsic::VectorReduceXor),
+    ("llvm.experimental.vector.reduce.smax", Intrinsic::VectorReduceSMax),
+    ("llvm.experimental.vector.reduce.smin", Intrinsic::VectorReduceSMin),
+    ("llvm.experimental.vector.reduce.umax", Intrinsic::VectorReduceUMax),
+    ("llvm.experimental.vector.reduce.umin", Intrinsic::VectorReduceUMin),
+    ("llvm.experimental.vector.reduce.v2.fadd", Intrinsic::VectorReduceFAdd),
+    ("llvm.experimental.vector.reduce.v2.fmul", Intrinsic::VectorReduceFMul),
+    ("llvm.experimental.vector.reduce.fmax", Intrinsic::VectorReduceFMax),
+    ("llvm.experimental.vector.reduce.fmin", Intrinsic::VectorReduceFMin),
+    ("llvm.lifetime.start", Intrinsic::LifetimeStart),
+    ("llvm.lifetime.end", Intrinsic::LifetimeEnd),
+    ("llvm.assume", Intrinsic::Assume),
+    ("llvm.dbg.declare", Intrinsic::DbgDeclare),
+    ("llvm.dbg.value", Intrinsic::DbgValue),
+    ("llvm.dbg.assign", Intrinsic::DbgAssign),
+    ("llvm.dbg.label", Intrinsic::DbgLabel),
+];
+
+impl Intrinsic {
+    /// Split the name of a function into the `Intrinsic` it refers to and the
+    /// (still-mangled) overloaded type suffixes, if any. E.g.,
+    /// `llvm.memcpy.p0.p0.i64` becomes `(Intrinsic::Memcpy, "p0.p0.i64")`.
+    ///
+    /// Returns `None` if the name isn't that of an intrinsic we recognize.
+    pub fn from_name(name: &str) -> Option<(Self, &str)> {
+        INTRINSIC_NAMES
+            .iter()
+            .filter_map(|(base, intrinsic)| {
+                let rest = name.strip_prefix(base)?;
+                if rest.is_empty() {
+                    Some((base.len(), *intrinsic, rest))
+                } else {
+                    rest.strip_prefix('.')
+                        .map(|suffixes| (base.len(), *intrinsic, suffixes))
+                }
+            })
+            .max_by_key(|(len, _, _)| *len) // e.g., prefer `llvm.memcpy.inline` to `llvm.memcpy`
+            .map(|(_, intrinsic, suffixes)| (intrinsic, suffixes))
+    }
+
+    /// The base name of the intrinsic, without any overloaded type suffixes;
+    /// e.g., `llvm.memcpy`. For intrinsics which have been renamed, this is
+    /// the modern name.
+    pub fn base_name(&self) -> &'static str {
+        INTRINSIC_NAMES
+            .iter()
+            .find(|(_, intrinsic)| intrinsic == self)
+            .map(|(base, _)| *base)
+            .expect("every Intrinsic has an entry in INTRINSIC_NAMES")
+    }
+}
+
+/// A [`Call`] to a recognized LLVM intrinsic. See [`Call::as_intrinsic`].
+#[derive(PartialEq, Clone, Debug, Hash)]
+pub struct IntrinsicCall<'a> {
+    pub intrinsic: Intrinsic,
+    /// The types the intrinsic is overloaded on, decoded from the suffixes
+    /// of its name. E.g., for `llvm.memcpy.p0.p0.i64` these are `ptr`, `ptr`,
+    /// and `i64`.
+    pub overloaded_types: Vec<TypeRef>,
+    /// The arguments of the call, by name
+    pub args: IntrinsicArgs<'a>,
+}
+
+/// The arguments of an [`IntrinsicCall`], by name.
+/// Argument names follow the LLVM LangRef.
+#[derive(PartialEq, Clone, Debug, Hash)]
+pub enum IntrinsicArgs<'a> {
+    /// `llvm.memcpy`, `llvm.memcpy.inline`, and `llvm.memmove`
+    MemTransfer {
+        dest: &'a Operand,
+        src: &'a Operand,
+        len: &'a Operand,
+        is_volatile: &'a Operand,
+    },
+    /// `llvm.memset` and `llvm.memset.inline`
+    MemSet {
+        dest: &'a Operand,
+        val: &'a Operand,
+        len: &'a Operand,
+        is_volatile: &'a Operand,
+    },
+    /// Arithmetic with overflow (e.g., `llvm.sadd.with.overflow`) and
+    /// saturating arithmetic (e.g., `llvm.sadd.sat`)
+    Arithmetic { lhs: &'a Operand, rhs: &'a Operand },
+    /// `llvm.ctpop`, `llvm.ctlz`, and `llvm.cttz`.
+    /// `is_zero_poison` is present for `llvm.ctlz` and `llvm.cttz` only.
+    BitCount {
+        value: &'a Operand,
+        is_zero_poison: Option<&'a Operand>,
+    },
+    /// `llvm.bswap` and `llvm.bitreverse`
+    BitReorder { value: &'a Operand },
+    /// `llvm.fshl` and `llvm.fshr`
+    FunnelShift {
+        a: &'a Operand,
+        b: &'a Operand,
+        shift: &'a Operand,
+    },
+    /// The `llvm.vector.reduce.*` intrinsics.
+    /// `start` is present for `fadd` and `fmul` reductions only.
+    VectorReduce {
+        start: Option<&'a Operand>,
+        vector: &'a Operand,
+    },
+    /// `llvm.lifetime.start` and `llvm.lifetime.end`
+    Lifetime { size: &'a Operand, ptr: &'a Operand },
+    /// `llvm.assume`
+    Assume { cond: &'a Operand },
+    /// `llvm.dbg.declare` and `llvm.dbg.value`. All three arguments are
+    /// metadata operands.
+    DbgVariable {
+        location: &'a Operand,
+        variable: &'a Operand,
+        expression: &'a Operand,
+    },
+    /// `llvm.dbg.assign`. All six arguments are metadata operands.
+    DbgAssign {
+        value: &'a Operand,
+        variable: &'a Operand,
+        expression: &'a Operand,
+        id: &'a Operand,
+        address: &'a Operand,
+        address_expression: &'a Operand,
+    },
+    /// `llvm.dbg.label`. The argument is a metadata operand.
+    DbgLabel { label: &'a Operand },
+}
+
+impl Call {
+    /// If this is a direct call to one of the LLVM intrinsics in [`Intrinsic`],
+    /// get a typed view of it, with the overloaded types decoded and the
+    /// arguments named.
+    ///
+    /// Returns `None` if this is not a direct call to a recognized intrinsic,
+    /// or if the call's name or arguments don't have the expected shape.
+    pub fn as_intrinsic<'a>(&'a self, module: &Module) -> Option<IntrinsicCall<'a>> {
+        let name = match &self.function {
+            Either::Right(Operand::ConstantOperand(c)) => match c.as_ref() {
+                Constant::GlobalReference {
+                    name: Name::Name(name),
+                    ..
+                } => name,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let (intrinsic, suffixes) = Intrinsic::from_name(name)?;
+        let overloaded_types = decode_overloaded_types(suffixes, &module.types)?;
+        let args: Vec<&Operand> = self.arguments.iter().map(|(op, _)| op).collect();
+        let args = match (intrinsic, args.as_slice()) {
+            (
+                Intrinsic::Memcpy | Intrinsic::MemcpyInline | Intrinsic::Memmove,
+                &[dest, src, len, is_volatile],
+            ) => IntrinsicArgs::MemTransfer {
+                dest,
+                src,
+                len,
+                is_volatile,
+            },
+            (Intrinsic::Memset | Intrinsic::MemsetInline, &[dest, val, len, is_volatile]) => {
+                IntrinsicArgs::MemSet {
+                    dest,
+                    val,
+                    len,
+                    is_volatile,
+                }
+            },
+            (
+                Intrinsic::SAddWithOverflow
+                | Intrinsic::UAddWithOverflow
+                | Intrinsic::SSubWithOverflow
+                | Intrinsic::USubWithOverflow
+                | Intrinsic::SMulWithOverflow
+                | Intrinsic::UMulWithOverflow
+                | Intrinsic::SAddSat
+                | Intrinsic::UAddSat
+                | Intrinsic::SSubSat
+                | Intrinsic::USubSat,
+                &[lhs, rhs],
+            ) => IntrinsicArgs::Arithmetic { lhs, rhs },
+            (Intrinsic::Ctpop, &[value]) => IntrinsicArgs::BitCount {
+                value,
+                is_zero_poison: None,
+            },
+            (Intrinsic::Ctlz | Intrinsic::Cttz, &[value, is_zero_poison]) => {
+                IntrinsicArgs::BitCount {
+                    value,
+                    is_zero_poison: Some(is_zero_poison),
+                }
+            },
+            (Intrinsic::Bswap | Intrinsic::Bitreverse, &[value]) => {
+                IntrinsicArgs::BitReorder { value }
+            },
+            (Intrinsic::Fshl | Intrinsic::Fshr, &[a, b, shift]) => {
+                IntrinsicArgs::FunnelShift { a, b, shift }
+            },
+            (Intrinsic::VectorReduceFAdd | Intrinsic::VectorReduceFMul, &[start, vector]) => {
+                IntrinsicArgs::VectorReduce {
+                    start: Some(start),
+                    vector,
+                }
+            },
+            (
+                Intrinsic::VectorReduceAdd
+                | Intrinsic::VectorReduceMul
+                | Intrinsic::VectorReduceAnd
+                | Intrinsic::VectorReduceOr
+                | Intrinsic::VectorReduceXor
+                | Intrinsic::VectorReduceSMax
+                | Intrinsic::VectorReduceSMin
+                | Intrinsic::VectorReduceUMax
+                | Intrinsic::VectorReduceUMin
+                | Intrinsic::VectorReduceFMax
+                | Intrinsic::VectorReduceFMin,
+                &[vector],
+            ) => IntrinsicArgs::VectorReduce {
+                start: None,
+                vector,
+            },
+            (Intrinsic::LifetimeStart | Intrinsic::LifetimeEnd, &[size, ptr]) => {
+                IntrinsicArgs::Lifetime { size, ptr }
+            },
+            (Intrinsic::Assume, &[cond]) => IntrinsicArgs::Assume { cond },
+            (Intrinsic::DbgDeclare | Intrinsic::DbgValue, &[location, variable, expression]) => {
+                IntrinsicArgs::DbgVariable {
+                    location,
+                    variable,
+                    expression,
+                }
+            },
+            (
+                Intrinsic::DbgAssign,
+                &[value, variable, expression, id, address, address_expression],
+            ) => IntrinsicArgs::DbgAssign {
+                value,
+                variable,
+                expression,
+                id,
+                address,
+                address_expression,
+            },
+            (Intrinsic::DbgLabel, &[label]) => IntrinsicArgs::DbgLabel { label },
+            _ => return None,
+        };
+        Some(IntrinsicCall {
+            intrinsic,
+            overloaded_types,
+            args,
+        })
+    }
+}
+
+/// Decode the `.`-separated overloaded type suffixes of an intrinsic name,
+/// e.g. `p0.p0.i64`. Returns `None` if any of them can't be decoded.
+fn decode_overloaded_types(mut suffixes: &str, types: &Types) -> Option<Vec<TypeRef>> {
+    let mut decoded = vec![];
+    while !suffixes.is_empty() {
+        let (ty, rest) = decode_type(suffixes, types)?;
+        // Typed pointers are mangled with their pointee type (e.g., `p0i8`);
+        // when we only have opaque pointers, skip over the pointee
+        #[cfg(feature = "llvm-15-or-greater")]
+        let rest = match (ty.as_ref(), rest.starts_with('.') || rest.is_empty()) {
+            (crate::types::Type::PointerType { .. }, false) => decode_type(rest, types)?.1,
+            _ => rest,
+        };
+        decoded.push(ty);
+        suffixes = if rest.is_empty() {
+            rest
+        } else {
+            rest.strip_prefix('.')?
+        };
+    }
+    Some(decoded)
+}
+
+/// Decode one type mangled in the style of LLVM's `getMangledTypeStr()` from
+/// the front of `s`, returning the type and the remainder of `s`
+fn decode_type<'s>(s: &'s str, types: &Types) -> Option<(TypeRef, &'s str)> {
+    if let Some(rest) = s.strip_prefix("nxv") {
+        #[cfg(feature = "llvm-11-or-greater")]
+        {
+            let (num_elements, rest) = decode_number(rest)?;
+            let (element_type, rest) = decode_type(rest, types)?;
+            return Some((types.vector_of(element_type, num_elements, true), rest));
+        }
+        #[cfg(feature = "llvm-10-or-lower")]
+        {
+            let _ = rest;
+            return None; // scalable vectors aren't supported for LLVM 10 and lower
+        }
+    }
+    if let Some(rest) = s.strip_prefix('v') {
+        if let Some((num_elements, rest)) = decode_number(rest) {
+            let (element_type, rest) = decode_type(rest, types)?;
+            #[cfg(feature = "llvm-11-or-greater")]
+            return Some((types.vector_of(element_type, num_elements, false), rest));
+            #[cfg(feature = "llvm-10-or-lower")]
+            return Some((types.vector_of(element_type, num_elements), rest));
+        }
+    }
+    if let Some(rest) = s.strip_prefix('a') {
+        if let Some((num_elements, rest)) = decode_number(rest) {
+            let (element_type, rest) = decode_type(rest, types)?;
+            return Some((types.array_of(element_type, num_elements), rest));
+        }
+    }
+    if let Some(rest) = s.strip_prefix("ppcf128") {
+        return Some((types.fp(FPType::PPC_FP128), rest));
+    }
+    if let Some(rest) = s.strip_prefix('p') {
+        if let Some((addr_space, rest)) = decode_number(rest) {
+            #[cfg(feature = "llvm-14-or-lower")]
+            {
+                let (pointee_type, rest) = decode_type(rest, types)?;
+                return Some((
+                    types.pointer_in_addr_space(pointee_type, addr_space as u32),
+                    rest,
+                ));
+            }
+            #[cfg(feature = "llvm-15-or-greater")]
+            return Some((types.pointer_in_addr_space(addr_space as u32), rest));
+        }
+    }
+    if let Some(rest) = s.strip_prefix("sl_") {
+        let mut element_types = vec![];
+        let mut rest = rest;
+        loop {
+            if let Some(rest) = rest.strip_prefix('s') {
+                return Some((types.struct_of(element_types, false), rest));
+            }
+            let (element_type, r) = decode_type(rest, types)?;
+            element_types.push(element_type);
+            rest = r;
+        }
+    }
+    if let Some(rest) = s.strip_prefix("s_") {
+        // The struct name isn't delimited, so take the longest struct name we
+        // know about
+        let name = types
+            .all_struct_names()
+            .filter(|name| rest.starts_with(name.as_str()))
+            .max_by_key(|name| name.len())?;
+        return Some((types.named_struct(name), &rest[name.len() ..]));
+    }
+    #[cfg(feature = "llvm-11-or-greater")]
+    if let Some(rest) = s.strip_prefix("bf16") {
+        return Some((types.fp(FPType::BFloat), rest));
+    }
+    for (prefix, fpt) in [
+        ("f16", FPType::Half),
+        ("f32", FPType::Single),
+        ("f64", FPType::Double),
+        ("f80", FPType::X86_FP80),
+        ("f128", FPType::FP128),
+    ] {
+        if let Some(rest) = s.strip_prefix(prefix) {
+            if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+                return Some((types.fp(fpt), rest));
+            }
+        }
+    }
+    if let Some(rest) = s.strip_prefix("isVoid") {
+        return Some((types.void(), rest));
+    }
+    if let Some(rest) = s.strip_prefix('i') {
+        let (bits, rest) = decode_number(rest)?;
+        return Some((types.int(bits as u32), rest));
+    }
+    if let Some(rest) = s.strip_prefix("x86mmx") {
+        return Some((types.x86_mmx(), rest));
+    }
+    if let Some(rest) = s.strip_prefix("Metadata") {
+        return Some((types.metadata_type(), rest));
+    }
+    None
+}
+
+/// Decode a decimal number from the front of `s`, returning it and the
+/// remainder of `s`
+fn decode_number(s: &str) -> Option<(usize, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let num = s[.. end].parse().ok()?;
+    Some((num, &s[end ..]))
+}
@@ -13,28 +13,44 @@ mod llvm_sys;
 
 pub mod basicblock;
 pub use basicblock::BasicBlock;
+pub mod builder;
+pub use builder::IRBuilder;
+pub mod cfg;
 pub mod constant;
 pub use constant::{Constant, ConstantRef};
 pub mod debugloc;
 pub use debugloc::{DebugLoc, HasDebugLoc};
+pub mod extract;
+pub use extract::ExtractOptions;
 pub mod function;
 pub use function::Function;
 pub mod instruction;
 pub use instruction::Instruction;
 // pub mod metadata;
 // pub use metadata::Metadata;
+pub mod link;
+pub use link::LinkError;
+pub mod mem2reg;
 pub mod module;
 pub use module::Module;
 pub mod name;
 pub use name::Name;
 pub mod operand;
 pub use operand::Operand;
+pub mod pass;
 pub mod predicates;
+pub mod reduce;
 pub use predicates::{FPPredicate, IntPredicate};
 pub mod terminator;
 pub use terminator::Terminator;
+pub mod triple;
+pub use triple::TargetTriple;
 pub mod types;
 pub use types::{Type, TypeRef};
+pub mod verify;
+pub use verify::VerifierError;
+pub mod visit;
+pub use visit::{Visitor, VisitorMut};
 
 macro_rules! case {
     ($feature:expr) => {
@@ -0,0 +1,909 @@
+//! Linking two `Module`s into one; see
+//! [`Module::link()`](../module/struct.Module.html#method.link).
+
+use crate::constant::{Constant, ConstantRef};
+use crate::function::{Function, ParameterAttribute};
+use crate::instruction::{InlineAssembly, Instruction};
+use crate::module::{Alignments, GlobalAlias, GlobalIFunc, GlobalVariable, Linkage, Module};
+use crate::name::Name;
+use crate::operand::Operand;
+use crate::terminator::Terminator;
+use crate::types::{NamedStructDef, Type, TypeRef, Types};
+use crate::visit::{walk_mut, VisitorMut};
+use either::Either;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// A reason two `Module`s couldn't be linked by
+/// [`Module::link()`](../module/struct.Module.html#method.link)
+#[derive(PartialEq, Clone, Debug)]
+pub enum LinkError {
+    /// Both modules contain a strong (e.g., `external`) definition of the
+    /// symbol
+    ConflictingDefinitions { name: Name },
+    /// The symbol is a function in one module and a global variable in the
+    /// other
+    KindMismatch { name: Name },
+    /// The symbol has a different type in each module. For functions this is
+    /// the function type; for global variables, the value type; for
+    /// `appending` globals, the array element type.
+    TypeMismatch {
+        name: Name,
+        first: TypeRef,
+        second: TypeRef,
+    },
+    /// The symbol has `appending` linkage in one module but not the other
+    IncompatibleLinkage {
+        name: Name,
+        first: Linkage,
+        second: Linkage,
+    },
+    /// The initializer of an `appending` global is not a constant array, so
+    /// it can't be concatenated with the other module's
+    AppendingInitializer { name: Name },
+}
+
+impl Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkError::ConflictingDefinitions { name } => {
+                write!(f, "symbol @{} is defined in both modules", name_str(name))
+            },
+            LinkError::KindMismatch { name } => write!(
+                f,
+                "symbol @{} is a function in one module and a variable in the other",
+                name_str(name)
+            ),
+            LinkError::TypeMismatch {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "symbol @{} has type {} in the first module but {} in the second",
+                name_str(name),
+                first,
+                second
+            ),
+            LinkError::IncompatibleLinkage {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "symbol @{} has incompatible linkages {:?} and {:?}",
+                name_str(name),
+                first,
+                second
+            ),
+            LinkError::AppendingInitializer { name } => write!(
+                f,
+                "appending global @{} doesn't have a constant array initializer",
+                name_str(name)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum SymbolKind {
+    Function,
+    Variable,
+    Alias,
+    IFunc,
+}
+
+struct Symbol {
+    kind: SymbolKind,
+    linkage: Linkage,
+    is_definition: bool,
+    /// Function type of a function; value type of a global variable; `None`
+    /// for aliases and ifuncs
+    ty: Option<TypeRef>,
+}
+
+impl Symbol {
+    fn is_local(&self) -> bool {
+        matches!(
+            self.linkage,
+            Linkage::Private
+                | Linkage::Internal
+                | Linkage::LinkerPrivate
+                | Linkage::LinkerPrivateWeak
+        )
+    }
+
+    /// Which of two definitions of the same symbol wins: the one with the
+    /// higher rank, or the first one if the ranks are equal. Two strong
+    /// definitions (rank 3) conflict.
+    fn rank(&self) -> u8 {
+        if !self.is_definition {
+            return 0;
+        }
+        match self.linkage {
+            Linkage::ExternalWeak => 0,
+            Linkage::AvailableExternally => 1,
+            Linkage::LinkOnceAny
+            | Linkage::LinkOnceODR
+            | Linkage::LinkOnceODRAutoHide
+            | Linkage::WeakAny
+            | Linkage::WeakODR
+            | Linkage::Common => 2,
+            _ => 3,
+        }
+    }
+}
+
+/// All global symbols of the module, in the order they appear in it
+fn symbols(module: &Module) -> Vec<(Name, Symbol)> {
+    let functions = module.functions.iter().map(|func| {
+        let sym = Symbol {
+            kind: SymbolKind::Function,
+            linkage: func.linkage,
+            is_definition: true,
+            ty: Some(module.type_of(func)),
+        };
+        (Name::from(func.name.as_str()), sym)
+    });
+    let decls = module.func_declarations.iter().map(|decl| {
+        let ty = module.types.func_type(
+            decl.return_type.clone(),
+            decl.parameters.iter().map(|p| p.ty.clone()).collect(),
+            decl.is_var_arg,
+        );
+        let sym = Symbol {
+            kind: SymbolKind::Function,
+            linkage: decl.linkage,
+            is_definition: false,
+            ty: Some(ty),
+        };
+        (Name::from(decl.name.as_str()), sym)
+    });
+    let vars = module.global_vars.iter().map(|var| {
+        let sym = Symbol {
+            kind: SymbolKind::Variable,
+            linkage: var.linkage,
+            is_definition: var.initializer.is_some(),
+            ty: Some(var.value_type.clone()),
+        };
+        (var.name.clone(), sym)
+    });
+    let aliases = module.global_aliases.iter().map(|alias| {
+        let sym = Symbol {
+            kind: SymbolKind::Alias,
+            linkage: alias.linkage,
+            is_definition: true,
+            ty: None,
+        };
+        (alias.name.clone(), sym)
+    });
+    let ifuncs = module.global_ifuncs.iter().map(|ifunc| {
+        let sym = Symbol {
+            kind: SymbolKind::IFunc,
+            linkage: ifunc.linkage,
+            is_definition: true,
+            ty: None,
+        };
+        (ifunc.name.clone(), sym)
+    });
+    functions
+        .chain(decls)
+        .chain(vars)
+        .chain(aliases)
+        .chain(ifuncs)
+        .collect()
+}
+
+/// Functions are named with `String`s rather than `Name`s
+fn name_str(name: &Name) -> String {
+    match name {
+        Name::Name(s) => String::clone(s),
+        Name::Number(n) => n.to_string(),
+    }
+}
+
+pub(crate) fn link_modules(mut a: Module, mut b: Module) -> Result<Module, LinkError> {
+    let struct_renames = merge_struct_defs(&mut a.types, &b.types);
+    rename_structs(&mut b, &struct_renames, &a.types);
+
+    // Give local symbols which collide with a symbol of the other module a
+    // fresh name. If both are local, the one from `b` is renamed.
+    let syms_a = symbols(&a);
+    let syms_b = symbols(&b);
+    let index_a: HashMap<&Name, &Symbol> = syms_a.iter().map(|(n, s)| (n, s)).collect();
+    let index_b: HashMap<&Name, &Symbol> = syms_b.iter().map(|(n, s)| (n, s)).collect();
+    let mut taken: HashSet<Name> = index_a
+        .keys()
+        .chain(index_b.keys())
+        .map(|&n| n.clone())
+        .collect();
+    let mut renames_a = HashMap::new();
+    for (name, sym) in &syms_a {
+        if sym.is_local() && matches!(index_b.get(name), Some(other) if !other.is_local()) {
+            renames_a.insert(name.clone(), fresh_name(name, &mut taken));
+        }
+    }
+    let mut renames_b = HashMap::new();
+    for (name, sym) in &syms_b {
+        if sym.is_local() && index_a.contains_key(name) {
+            renames_b.insert(name.clone(), fresh_name(name, &mut taken));
+        }
+    }
+    rename_globals(&mut a, &renames_a);
+    rename_globals(&mut b, &renames_b);
+
+    // Resolve the symbols defined or declared in both modules, keeping one of
+    // them
+    let syms_a = symbols(&a);
+    let index_a: HashMap<&Name, &Symbol> = syms_a.iter().map(|(n, s)| (n, s)).collect();
+    for (name, sym_b) in symbols(&b) {
+        let sym_a = match index_a.get(&name) {
+            Some(sym_a) => sym_a,
+            None => continue,
+        };
+        if let Some(keep_a) = resolve_common(&a, sym_a, &sym_b) {
+            let (kept, removed, removed_sym) = if keep_a {
+                (&mut a, &mut b, &sym_b)
+            } else {
+                (&mut b, &mut a, *sym_a)
+            };
+            let alignment = removed
+                .get_global_var_by_name(&name)
+                .expect("common symbols are global variables")
+                .alignment;
+            let var = kept
+                .global_vars
+                .iter_mut()
+                .find(|g| g.name == name)
+                .expect("common symbols are global variables");
+            var.alignment = var.alignment.max(alignment);
+            remove_symbol(removed, &name, removed_sym.kind);
+            continue;
+        }
+        check_compatible(&name, sym_a, &sym_b)?;
+        if sym_a.linkage == Linkage::Appending || sym_b.linkage == Linkage::Appending {
+            if sym_a.linkage != sym_b.linkage {
+                return Err(LinkError::IncompatibleLinkage {
+                    name,
+                    first: sym_a.linkage,
+                    second: sym_b.linkage,
+                });
+            }
+            append_globals(&mut a, &mut b, &name)?;
+        } else if sym_a.rank() == 3 && sym_b.rank() == 3 {
+            return Err(LinkError::ConflictingDefinitions { name });
+        } else if sym_b.rank() > sym_a.rank() {
+            remove_symbol(&mut a, &name, sym_a.kind);
+        } else {
+            remove_symbol(&mut b, &name, sym_b.kind);
+        }
+    }
+
+    a.functions.extend(b.functions);
+    a.func_declarations.extend(b.func_declarations);
+    a.global_vars.extend(b.global_vars);
+    a.global_aliases.extend(b.global_aliases);
+    a.global_ifuncs.extend(b.global_ifuncs);
+    for comdat in b.comdats {
+        if a.get_comdat_by_name(&comdat.name).is_none() {
+            a.comdats.push(comdat);
+        }
+    }
+    if a.inline_assembly.is_empty() {
+        a.inline_assembly = b.inline_assembly;
+    } else if !b.inline_assembly.is_empty() {
+        a.inline_assembly.push('\n');
+        a.inline_assembly.push_str(&b.inline_assembly);
+    }
+    if a.data_layout.layout_str.is_empty() {
+        a.data_layout = b.data_layout;
+    }
+    if a.target_triple.is_none() {
+        a.target_triple = b.target_triple;
+    }
+    Ok(a)
+}
+
+/// Add the named struct definitions of `b` to `a`. A struct which is opaque in
+/// one table takes its definition from the other. A struct which is defined
+/// differently in the two tables (including because it contains a struct
+/// which is) is added to `a` under a fresh name, `name.0`, `name.1`, etc., as
+/// `llvm-link` does. Returns the structs of `b` which were renamed this way.
+fn merge_struct_defs(a: &mut Types, b: &Types) -> HashMap<String, String> {
+    let mut names: Vec<&String> = b.all_struct_names().collect();
+    names.sort();
+    let mut taken: HashSet<String> = a
+        .all_struct_names()
+        .chain(b.all_struct_names())
+        .cloned()
+        .collect();
+
+    // Renaming a struct may make another one which contains it conflict too,
+    // so repeat until no more renames are needed
+    let mut renames = HashMap::new();
+    loop {
+        let mut changed = false;
+        for &name in &names {
+            if renames.contains_key(name) {
+                continue;
+            }
+            if let (Some(NamedStructDef::Defined(ty_a)), Some(NamedStructDef::Defined(ty_b))) =
+                (a.named_struct_def(name), b.named_struct_def(name))
+            {
+                if ty_a != &StructRenamer::new(&renames, a).ty(ty_b) {
+                    let fresh = (0 ..)
+                        .map(|i| format!("{}.{}", name, i))
+                        .find(|n| !taken.contains(n))
+                        .expect("some suffix is free");
+                    taken.insert(fresh.clone());
+                    renames.insert(name.clone(), fresh);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    for &name in &names {
+        let def_b = match b.named_struct_def(name) {
+            Some(NamedStructDef::Defined(ty)) => {
+                NamedStructDef::Defined(StructRenamer::new(&renames, a).ty(ty))
+            },
+            _ => NamedStructDef::Opaque,
+        };
+        let name = renames.get(name).unwrap_or(name);
+        match (a.named_struct_def(name), &def_b) {
+            (None, _) => a.add_named_struct_def(name.clone(), def_b),
+            (Some(NamedStructDef::Opaque), NamedStructDef::Defined(_)) => {
+                a.remove_named_struct_def(name);
+                a.add_named_struct_def(name.clone(), def_b);
+            },
+            (Some(_), _) => {},
+        }
+    }
+    renames
+}
+
+/// Rewrite all the types in the module which mention a renamed struct. `types`
+/// is used to create the new types.
+fn rename_structs(module: &mut Module, renames: &HashMap<String, String>, types: &Types) {
+    if renames.is_empty() {
+        return;
+    }
+    let mut renamer = StructRenamer::new(renames, types);
+    renamer.visit_module(module);
+    for decl in &mut module.func_declarations {
+        renamer.retype(&mut decl.return_type);
+        renamer.attributes(&mut decl.return_attributes);
+        for param in &mut decl.parameters {
+            renamer.retype(&mut param.ty);
+            renamer.attributes(&mut param.attributes);
+        }
+    }
+}
+
+/// Renames named structs in types (which the `VisitorMut` doesn't visit) and
+/// in everything holding them
+struct StructRenamer<'r> {
+    renames: &'r HashMap<String, String>,
+    types: &'r Types,
+}
+
+impl<'r> StructRenamer<'r> {
+    fn new(renames: &'r HashMap<String, String>, types: &'r Types) -> Self {
+        Self { renames, types }
+    }
+
+    fn ty(&self, ty: &TypeRef) -> TypeRef {
+        let mut new = ty.as_ref().clone();
+        match &mut new {
+            Type::NamedStructType { name } => match self.renames.get(name) {
+                Some(renamed) => *name = renamed.clone(),
+                None => return ty.clone(),
+            },
+            #[cfg(feature = "llvm-14-or-lower")]
+            Type::PointerType { pointee_type, .. } => self.retype(pointee_type),
+            Type::FuncType {
+                result_type,
+                param_types,
+                ..
+            } => {
+                self.retype(result_type);
+                param_types.iter_mut().for_each(|ty| self.retype(ty));
+            },
+            Type::VectorType { element_type, .. } | Type::ArrayType { element_type, .. } => {
+                self.retype(element_type)
+            },
+            Type::StructType { element_types, .. } => {
+                element_types.iter_mut().for_each(|ty| self.retype(ty))
+            },
+            #[cfg(feature = "llvm-16-or-greater")]
+            Type::TargetExtType {
+                contained_types, ..
+            } => contained_types.iter_mut().for_each(|ty| self.retype(ty)),
+            _ => return ty.clone(),
+        }
+        if &new == ty.as_ref() {
+            ty.clone()
+        } else {
+            self.types.get_for_type(&new)
+        }
+    }
+
+    fn retype(&self, ty: &mut TypeRef) {
+        *ty = self.ty(ty);
+    }
+
+    fn attributes(&self, attributes: &mut [ParameterAttribute]) {
+        for attribute in attributes {
+            match attribute {
+                #[cfg(feature = "llvm-12-or-greater")]
+                ParameterAttribute::ByVal(ty)
+                | ParameterAttribute::Preallocated(ty)
+                | ParameterAttribute::SRet(ty)
+                | ParameterAttribute::ByRef(ty)
+                | ParameterAttribute::UnknownTypeAttribute { ty, .. } => self.retype(ty),
+                #[cfg(feature = "llvm-13-or-greater")]
+                ParameterAttribute::InAlloca(ty) | ParameterAttribute::ElementType(ty) => {
+                    self.retype(ty)
+                },
+                #[cfg(feature = "llvm-19-or-greater")]
+                ParameterAttribute::Range { ty, .. } => self.retype(ty),
+                _ => {},
+            }
+        }
+    }
+
+    fn callee(&self, function: &mut Either<Box<InlineAssembly>, Operand>) {
+        if let Either::Left(asm) = function {
+            self.retype(&mut asm.ty);
+        }
+    }
+}
+
+impl VisitorMut for StructRenamer<'_> {
+    fn visit_function(&mut self, function: &mut Function) {
+        self.retype(&mut function.return_type);
+        self.attributes(&mut function.return_attributes);
+        for param in &mut function.parameters {
+            self.retype(&mut param.ty);
+            self.attributes(&mut param.attributes);
+        }
+        walk_mut::function(self, function);
+    }
+
+    fn visit_global_variable(&mut self, global: &mut GlobalVariable) {
+        self.retype(&mut global.ty);
+        self.retype(&mut global.value_type);
+        walk_mut::global_variable(self, global);
+    }
+
+    fn visit_global_alias(&mut self, alias: &mut GlobalAlias) {
+        self.retype(&mut alias.ty);
+        walk_mut::global_alias(self, alias);
+    }
+
+    fn visit_global_ifunc(&mut self, ifunc: &mut GlobalIFunc) {
+        self.retype(&mut ifunc.ty);
+        walk_mut::global_ifunc(self, ifunc);
+    }
+
+    fn visit_instruction(&mut self, instruction: &mut Instruction) {
+        walk_mut::instruction(self, instruction);
+        match instruction {
+            Instruction::Alloca(i) => self.retype(&mut i.allocated_type),
+            #[cfg(feature = "llvm-15-or-greater")]
+            Instruction::Load(i) => self.retype(&mut i.loaded_ty),
+            #[cfg(feature = "llvm-14-or-greater")]
+            Instruction::GetElementPtr(i) => self.retype(&mut i.source_element_type),
+            Instruction::Trunc(i) => self.retype(&mut i.to_type),
+            Instruction::ZExt(i) => self.retype(&mut i.to_type),
+            Instruction::SExt(i) => self.retype(&mut i.to_type),
+            Instruction::FPTrunc(i) => self.retype(&mut i.to_type),
+            Instruction::FPExt(i) => self.retype(&mut i.to_type),
+            Instruction::FPToUI(i) => self.retype(&mut i.to_type),
+            Instruction::FPToSI(i) => self.retype(&mut i.to_type),
+            Instruction::UIToFP(i) => self.retype(&mut i.to_type),
+            Instruction::SIToFP(i) => self.retype(&mut i.to_type),
+            Instruction::PtrToInt(i) => self.retype(&mut i.to_type),
+            Instruction::IntToPtr(i) => self.retype(&mut i.to_type),
+            Instruction::BitCast(i) => self.retype(&mut i.to_type),
+            Instruction::AddrSpaceCast(i) => self.retype(&mut i.to_type),
+            Instruction::Phi(i) => self.retype(&mut i.to_type),
+            Instruction::VAArg(i) => self.retype(&mut i.cur_type),
+            Instruction::LandingPad(i) => self.retype(&mut i.result_type),
+            Instruction::Call(i) => {
+                #[cfg(feature = "llvm-15-or-greater")]
+                self.retype(&mut i.function_ty);
+                self.callee(&mut i.function);
+                self.attributes(&mut i.return_attributes);
+                for (_, attributes) in &mut i.arguments {
+                    self.attributes(attributes);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn visit_terminator(&mut self, terminator: &mut Terminator) {
+        walk_mut::terminator(self, terminator);
+        match terminator {
+            Terminator::Invoke(t) => {
+                #[cfg(feature = "llvm-15-or-greater")]
+                self.retype(&mut t.function_ty);
+                self.callee(&mut t.function);
+                self.attributes(&mut t.return_attributes);
+                for (_, attributes) in &mut t.arguments {
+                    self.attributes(attributes);
+                }
+            },
+            Terminator::CallBr(t) => {
+                #[cfg(feature = "llvm-15-or-greater")]
+                self.retype(&mut t.function_ty);
+                self.callee(&mut t.function);
+                self.attributes(&mut t.return_attributes);
+                for (_, attributes) in &mut t.arguments {
+                    self.attributes(attributes);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand) {
+        walk_mut::operand(self, operand);
+        if let Operand::LocalOperand { ty, .. } = operand {
+            self.retype(ty);
+        }
+    }
+
+    fn visit_constant(&mut self, constant: &mut ConstantRef) {
+        walk_mut::constant(self, constant);
+        let mut new = constant.as_ref().clone();
+        match &mut new {
+            Constant::Null(ty) | Constant::AggregateZero(ty) | Constant::Undef(ty) => {
+                self.retype(ty)
+            },
+            #[cfg(feature = "llvm-12-or-greater")]
+            Constant::Poison(ty) => self.retype(ty),
+            Constant::Struct {
+                name: Some(name), ..
+            } => {
+                if let Some(renamed) = self.renames.get(name) {
+                    *name = renamed.clone();
+                }
+            },
+            Constant::Array { element_type, .. } => self.retype(element_type),
+            Constant::GlobalReference { ty, .. } => self.retype(ty),
+            Constant::Trunc(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::ZExt(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::SExt(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::FPTrunc(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::FPExt(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::FPToUI(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::FPToSI(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::UIToFP(c) => self.retype(&mut c.to_type),
+            #[cfg(feature = "llvm-17-or-lower")]
+            Constant::SIToFP(c) => self.retype(&mut c.to_type),
+            Constant::PtrToInt(c) => self.retype(&mut c.to_type),
+            Constant::IntToPtr(c) => self.retype(&mut c.to_type),
+            Constant::BitCast(c) => self.retype(&mut c.to_type),
+            Constant::AddrSpaceCast(c) => self.retype(&mut c.to_type),
+            _ => return,
+        }
+        if &new != constant.as_ref() {
+            *constant = ConstantRef::new(new);
+        }
+    }
+}
+
+/// `name.1`, `name.2`, etc. for a named symbol; the next unused number for a
+/// numbered one
+fn fresh_name(name: &Name, taken: &mut HashSet<Name>) -> Name {
+    let fresh = match name {
+        Name::Name(s) => (1 ..)
+            .map(|i| Name::from(format!("{}.{}", s, i)))
+            .find(|n| !taken.contains(n))
+            .expect("some suffix is free"),
+        Name::Number(_) => {
+            let max = taken
+                .iter()
+                .filter_map(|n| match n {
+                    Name::Number(n) => Some(*n),
+                    Name::Name(_) => None,
+                })
+                .max()
+                .unwrap_or(0);
+            Name::Number(max + 1)
+        },
+    };
+    taken.insert(fresh.clone());
+    fresh
+}
+
+/// Rename global symbols of the module, and all references to them
+fn rename_globals(module: &mut Module, renames: &HashMap<Name, Name>) {
+    if renames.is_empty() {
+        return;
+    }
+    struct Renamer<'r> {
+        renames: &'r HashMap<Name, Name>,
+    }
+    impl VisitorMut for Renamer<'_> {
+        fn visit_constant(&mut self, constant: &mut ConstantRef) {
+            walk_mut::constant(self, constant);
+            let renamed = match constant.as_ref() {
+                Constant::GlobalReference { name, ty } => {
+                    self.renames.get(name).map(|new| Constant::GlobalReference {
+                        name: new.clone(),
+                        ty: ty.clone(),
+                    })
+                },
+                Constant::BlockAddress { function, block } => {
+                    self.renames
+                        .get(function)
+                        .map(|new| Constant::BlockAddress {
+                            function: new.clone(),
+                            block: block.clone(),
+                        })
+                },
+                _ => None,
+            };
+            if let Some(renamed) = renamed {
+                *constant = ConstantRef::new(renamed);
+            }
+        }
+    }
+    Renamer { renames }.visit_module(module);
+
+    let rename_str = |name: &mut String| {
+        if let Some(new) = renames.get(&Name::from(name.as_str())) {
+            *name = name_str(new);
+        }
+    };
+    module
+        .functions
+        .iter_mut()
+        .for_each(|f| rename_str(&mut f.name));
+    module
+        .func_declarations
+        .iter_mut()
+        .for_each(|d| rename_str(&mut d.name));
+    let rename = |name: &mut Name| {
+        if let Some(new) = renames.get(name) {
+            *name = new.clone();
+        }
+    };
+    module
+        .global_vars
+        .iter_mut()
+        .for_each(|g| rename(&mut g.name));
+    module
+        .global_aliases
+        .iter_mut()
+        .for_each(|g| rename(&mut g.name));
+    module
+        .global_ifuncs
+        .iter_mut()
+        .for_each(|g| rename(&mut g.name));
+}
+
+/// Resolve two global variables at least one of which is `common`, returning
+/// whether to keep the one from `a`. Unlike other definitions, `common` ones
+/// may have different types: a strong definition beats a `common` one, and of
+/// two `common` ones the larger is kept. Returns `None` if the symbols should
+/// be resolved (and type-checked) as usual.
+fn resolve_common(a: &Module, sym_a: &Symbol, sym_b: &Symbol) -> Option<bool> {
+    if sym_a.kind != SymbolKind::Variable || sym_b.kind != SymbolKind::Variable {
+        return None;
+    }
+    match (sym_a.linkage, sym_b.linkage) {
+        (Linkage::Common, Linkage::Common) => {
+            // struct definitions have been merged into `a` already
+            let size_a = alloc_size(sym_a.ty.as_ref()?, a)?;
+            let size_b = alloc_size(sym_b.ty.as_ref()?, a)?;
+            Some(size_a >= size_b)
+        },
+        (Linkage::Common, _) if sym_b.rank() == 3 => Some(false),
+        (_, Linkage::Common) if sym_a.rank() == 3 => Some(true),
+        _ => None,
+    }
+}
+
+/// Number of bytes allocated for a value of type `ty`, including padding, or
+/// `None` if it isn't sized
+fn alloc_size(ty: &TypeRef, module: &Module) -> Option<u64> {
+    let size = match ty.as_ref() {
+        Type::IntegerType { .. } | Type::FPType(_) | Type::PointerType { .. } => {
+            element_bits(ty, module)?.div_ceil(8)
+        },
+        Type::ArrayType {
+            element_type,
+            num_elements,
+        } => return Some(alloc_size(element_type, module)? * *num_elements as u64),
+        Type::VectorType {
+            element_type,
+            num_elements,
+            ..
+        } => (element_bits(element_type, module)? * *num_elements as u64).div_ceil(8),
+        Type::StructType {
+            element_types,
+            is_packed,
+        } => {
+            let mut size: u64 = 0;
+            for element_type in element_types {
+                if !is_packed {
+                    size = size.next_multiple_of(abi_alignment(element_type, module)?);
+                }
+                size += alloc_size(element_type, module)?;
+            }
+            size
+        },
+        Type::NamedStructType { name } => match module.types.named_struct_def(name)? {
+            NamedStructDef::Defined(def) => return alloc_size(def, module),
+            NamedStructDef::Opaque => return None,
+        },
+        _ => return None,
+    };
+    Some(size.next_multiple_of(abi_alignment(ty, module)?))
+}
+
+/// ABI alignment of type `ty`, in bytes
+fn abi_alignment(ty: &TypeRef, module: &Module) -> Option<u64> {
+    let alignments = &module.data_layout.alignments;
+    let alignment = match ty.as_ref() {
+        Type::ArrayType { element_type, .. } => return abi_alignment(element_type, module),
+        Type::StructType {
+            element_types,
+            is_packed,
+        } => {
+            if *is_packed {
+                return Some(1);
+            }
+            let mut alignment = 1;
+            for element_type in element_types {
+                alignment = alignment.max(abi_alignment(element_type, module)?);
+            }
+            return Some(alignment);
+        },
+        Type::NamedStructType { name } => match module.types.named_struct_def(name)? {
+            NamedStructDef::Defined(def) => return abi_alignment(def, module),
+            NamedStructDef::Opaque => return None,
+        },
+        Type::FPType(fpt) => {
+            let bits = Alignments::fpt_size(*fpt);
+            alignments.explicit_fp_alignments().get(&bits)?.abi
+        },
+        Type::VectorType {
+            element_type,
+            num_elements,
+            ..
+        } => {
+            let bits = element_bits(element_type, module)? * *num_elements as u64;
+            alignments.vec_alignment(u32::try_from(bits).ok()?).abi
+        },
+        Type::IntegerType { .. } | Type::PointerType { .. } => alignments.type_alignment(ty).abi,
+        _ => return None,
+    };
+    Some((u64::from(alignment) / 8).max(1))
+}
+
+/// Size in bits of an integer, floating-point or pointer type
+fn element_bits(ty: &TypeRef, module: &Module) -> Option<u64> {
+    match ty.as_ref() {
+        Type::IntegerType { bits } => Some(u64::from(*bits)),
+        Type::FPType(fpt) => Some(u64::from(Alignments::fpt_size(*fpt))),
+        Type::PointerType { addr_space, .. } => Some(u64::from(
+            module
+                .data_layout
+                .alignments
+                .ptr_alignment(*addr_space)
+                .size,
+        )),
+        _ => None,
+    }
+}
+
+fn check_compatible(name: &Name, a: &Symbol, b: &Symbol) -> Result<(), LinkError> {
+    use SymbolKind::*;
+    match (a.kind, b.kind) {
+        (Function, Variable) | (Variable, Function) => {
+            return Err(LinkError::KindMismatch { name: name.clone() })
+        },
+        _ => {},
+    }
+    if a.kind != b.kind || a.linkage == Linkage::Appending || b.linkage == Linkage::Appending {
+        // aliases and ifuncs are untyped here; appending arrays are checked
+        // when they're concatenated
+        return Ok(());
+    }
+    match (&a.ty, &b.ty) {
+        (Some(ty_a), Some(ty_b)) if ty_a != ty_b => Err(LinkError::TypeMismatch {
+            name: name.clone(),
+            first: ty_a.clone(),
+            second: ty_b.clone(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Remove the function, declaration, global, alias or ifunc with the given
+/// name from the module
+fn remove_symbol(module: &mut Module, name: &Name, kind: SymbolKind) {
+    match kind {
+        SymbolKind::Function => {
+            let name = name_str(name);
+            module.functions.retain(|f| f.name != name);
+            module.func_declarations.retain(|d| d.name != name);
+        },
+        SymbolKind::Variable => module.global_vars.retain(|g| &g.name != name),
+        SymbolKind::Alias => module.global_aliases.retain(|g| &g.name != name),
+        SymbolKind::IFunc => module.global_ifuncs.retain(|g| &g.name != name),
+    }
+}
+
+/// Concatenate the `appending` global `name` of `b` onto the one of `a`, and
+/// remove it from `b`
+fn append_globals(a: &mut Module, b: &mut Module, name: &Name) -> Result<(), LinkError> {
+    let idx_b = b
+        .global_vars
+        .iter()
+        .position(|g| &g.name == name)
+        .expect("appending symbols are global variables");
+    let var_b = b.global_vars.remove(idx_b);
+    let var_a = a
+        .global_vars
+        .iter_mut()
+        .find(|g| &g.name == name)
+        .expect("appending symbols are global variables");
+    let element_type = |ty: &TypeRef| match ty.as_ref() {
+        Type::ArrayType { element_type, .. } => Ok(element_type.clone()),
+        _ => Err(LinkError::AppendingInitializer { name: name.clone() }),
+    };
+    let element_type_a = element_type(&var_a.value_type)?;
+    let element_type_b = element_type(&var_b.value_type)?;
+    if element_type_a != element_type_b {
+        return Err(LinkError::TypeMismatch {
+            name: name.clone(),
+            first: var_a.value_type.clone(),
+            second: var_b.value_type.clone(),
+        });
+    }
+    let elements = |init: &Option<ConstantRef>| match init.as_ref().map(ConstantRef::as_ref) {
+        None => Ok(vec![]),
+        Some(Constant::Array { elements, .. }) => Ok(elements.clone()),
+        Some(Constant::AggregateZero(ty)) => match ty.as_ref() {
+            Type::ArrayType {
+                num_elements: 0, ..
+            } => Ok(vec![]),
+            _ => Err(LinkError::AppendingInitializer { name: name.clone() }),
+        },
+        Some(_) => Err(LinkError::AppendingInitializer { name: name.clone() }),
+    };
+    let mut elements_a = elements(&var_a.initializer)?;
+    elements_a.extend(elements(&var_b.initializer)?);
+    var_a.value_type = a.types.array_of(element_type_a.clone(), elements_a.len());
+    #[cfg(feature = "llvm-14-or-lower")]
+    {
+        var_a.ty = a
+            .types
+            .pointer_in_addr_space(var_a.value_type.clone(), var_a.addr_space);
+    }
+    var_a.initializer = Some(ConstantRef::new(Constant::Array {
+        element_type: element_type_a,
+        elements: elements_a,
+    }));
+    Ok(())
+}
@@ -0,0 +1,429 @@
+//! Promotion of stack variables to SSA values; see
+//! [`Function::mem2reg()`](../function/struct.Function.html#method.mem2reg).
+
+use crate::cfg::{ControlFlowGraph, DominatorTree};
+use crate::constant::{Constant, ConstantRef};
+use crate::debugloc::DebugLoc;
+use crate::function::Function;
+#[cfg(feature = "llvm-18-or-greater")]
+use crate::instruction::FastMathFlags;
+use crate::instruction::{Call, Instruction, Phi};
+use crate::name::Name;
+use crate::operand::Operand;
+use crate::pass::{AnalysisManager, FunctionPass};
+use crate::types::{Type, TypeRef, Types};
+use crate::visit::{Visitor, VisitorMut};
+use std::collections::{HashMap, HashSet};
+
+/// [`Function::mem2reg()`](../function/struct.Function.html#method.mem2reg)
+/// as a [`FunctionPass`](../pass/trait.FunctionPass.html)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Mem2Reg;
+
+impl FunctionPass for Mem2Reg {
+    fn name(&self) -> &str {
+        "mem2reg"
+    }
+
+    fn run_on_function(
+        &mut self,
+        function: &mut Function,
+        types: &Types,
+        analyses: &mut AnalysisManager,
+    ) -> bool {
+        mem2reg(function, types, analyses)
+    }
+}
+
+/// An `Alloca` which can be promoted
+struct Promotable {
+    name: Name,
+    ty: TypeRef,
+    debugloc: Option<DebugLoc>,
+}
+
+/// Promote the allocas of `func`, getting its dominator tree from `analyses`.
+/// The caller is responsible for invalidating the cached analyses of `func`
+/// if this returns `true`.
+pub(crate) fn mem2reg(func: &mut Function, types: &Types, analyses: &mut AnalysisManager) -> bool {
+    let allocas = promotable_allocas(func, types);
+    if allocas.is_empty() {
+        return false;
+    }
+    let alloca_index: HashMap<Name, usize> = allocas
+        .iter()
+        .enumerate()
+        .map(|(i, alloca)| (alloca.name.clone(), i))
+        .collect();
+    let promoted =
+        |address: &Operand| local_name(address).and_then(|n| alloca_index.get(n).copied());
+    let block_index: HashMap<Name, usize> = func
+        .basic_blocks
+        .iter()
+        .enumerate()
+        .map(|(i, bb)| (bb.name.clone(), i))
+        .collect();
+    let num_blocks = func.basic_blocks.len();
+    let cfg = analyses.get::<ControlFlowGraph>(func, types);
+    let domtree = analyses.get::<DominatorTree>(func, types);
+    let frontiers = domtree.dominance_frontiers(&cfg);
+    let children = domtree.children();
+
+    // Place phis at the iterated dominance frontier of the blocks storing to
+    // each alloca. `phis[b]` holds the allocas needing a phi in block `b`.
+    let mut phis: Vec<Vec<usize>> = vec![vec![]; num_blocks];
+    for a in 0 .. allocas.len() {
+        let mut worklist: Vec<usize> = (0 .. num_blocks)
+            .filter(|&b| domtree.is_reachable(b))
+            .filter(|&b| {
+                func.basic_blocks[b].instrs.iter().any(|inst| {
+                    matches!(inst, Instruction::Store(store) if promoted(&store.address) == Some(a))
+                })
+            })
+            .collect();
+        let mut has_phi = vec![false; num_blocks];
+        while let Some(block) = worklist.pop() {
+            for &frontier in &frontiers[block] {
+                if !has_phi[frontier] {
+                    has_phi[frontier] = true;
+                    phis[frontier].push(a);
+                    worklist.push(frontier);
+                }
+            }
+        }
+    }
+    let mut taken = defined_names(func);
+    let phi_names: Vec<Vec<Name>> = phis
+        .iter()
+        .map(|allocas_here| {
+            allocas_here
+                .iter()
+                .map(|&a| fresh_name(&allocas[a].name, &mut taken))
+                .collect()
+        })
+        .collect();
+
+    // Rename: walk the dominator tree from the entry, tracking the current
+    // value of each alloca, and record the value each load reads and the
+    // incoming values of each phi. Unreachable blocks are processed
+    // afterwards, starting from `undef`.
+    let undefs: Vec<Operand> = allocas.iter().map(|a| undef(a.ty.clone())).collect();
+    let mut replacements: HashMap<Name, Operand> = HashMap::new();
+    let mut incoming: Vec<Vec<Vec<(Operand, Name)>>> = phis
+        .iter()
+        .map(|allocas_here| vec![vec![]; allocas_here.len()])
+        .collect();
+    let mut process_block = |block: usize, mut values: Vec<Operand>| {
+        for (&a, name) in phis[block].iter().zip(&phi_names[block]) {
+            values[a] = Operand::LocalOperand {
+                name: name.clone(),
+                ty: allocas[a].ty.clone(),
+            };
+        }
+        let bb = &func.basic_blocks[block];
+        for inst in &bb.instrs {
+            match inst {
+                Instruction::Load(load) => {
+                    if let Some(a) = promoted(&load.address) {
+                        replacements.insert(load.dest.clone(), values[a].clone());
+                    }
+                },
+                Instruction::Store(store) => {
+                    if let Some(a) = promoted(&store.address) {
+                        values[a] = resolve(&store.value, &replacements);
+                    }
+                },
+                _ => {},
+            }
+        }
+        for succ in bb.term.successors() {
+            if let Some(&succ) = block_index.get(succ) {
+                for (k, &a) in phis[succ].iter().enumerate() {
+                    incoming[succ][k].push((values[a].clone(), bb.name.clone()));
+                }
+            }
+        }
+        values
+    };
+    let mut stack = vec![(0, undefs.clone())];
+    while let Some((block, values)) = stack.pop() {
+        let values = process_block(block, values);
+        // pushed in reverse, so that they're processed in order
+        for &child in children[block].iter().rev() {
+            stack.push((child, values.clone()));
+        }
+    }
+    for block in 0 .. num_blocks {
+        if !domtree.is_reachable(block) {
+            process_block(block, undefs.clone());
+        }
+    }
+
+    // Rewrite the function: delete the allocas and the loads, stores and
+    // lifetime markers using them; insert the phis; and replace the results
+    // of the loads with the values they read
+    for (b, bb) in func.basic_blocks.iter_mut().enumerate() {
+        bb.instrs.retain(|inst| match inst {
+            Instruction::Alloca(alloca) => !alloca_index.contains_key(&alloca.dest),
+            Instruction::Load(load) => promoted(&load.address).is_none(),
+            Instruction::Store(store) => promoted(&store.address).is_none(),
+            Instruction::Call(call) if is_lifetime_marker(call) => !call
+                .arguments
+                .iter()
+                .any(|(arg, _)| promoted(arg).is_some()),
+            _ => true,
+        });
+        let new_phis = phis[b]
+            .iter()
+            .zip(&phi_names[b])
+            .zip(std::mem::take(&mut incoming[b]))
+            .map(|((&a, name), incoming_values)| {
+                Instruction::Phi(Phi {
+                    incoming_values,
+                    dest: name.clone(),
+                    to_type: allocas[a].ty.clone(),
+                    #[cfg(feature = "llvm-18-or-greater")]
+                    fast_math_flags: FastMathFlags::default(),
+                    debugloc: allocas[a].debugloc.clone(),
+                })
+            });
+        bb.instrs.splice(0 .. 0, new_phis);
+    }
+    struct Replacer<'r> {
+        replacements: &'r HashMap<Name, Operand>,
+    }
+    impl VisitorMut for Replacer<'_> {
+        fn visit_operand(&mut self, operand: &mut Operand) {
+            if let Operand::LocalOperand { name, .. } = operand {
+                if self.replacements.contains_key(name) {
+                    *operand = resolve(operand, self.replacements);
+                }
+            }
+        }
+    }
+    let mut replacer = Replacer {
+        replacements: &replacements,
+    };
+    func.basic_blocks
+        .iter_mut()
+        .for_each(|bb| replacer.visit_basic_block(bb));
+
+    let inserted: HashSet<Name> = phi_names.into_iter().flatten().collect();
+    remove_dead_phis(func, &inserted);
+    true
+}
+
+/// Allocas in the entry block, of a single integer, floating-point or
+/// pointer value, which are only loaded from and stored to (with the
+/// allocated type, and neither volatile nor atomic), or passed to lifetime
+/// markers
+fn promotable_allocas(func: &Function, types: &Types) -> Vec<Promotable> {
+    let entry = match func.basic_blocks.first() {
+        Some(entry) => entry,
+        None => return vec![],
+    };
+    let candidates: HashMap<&Name, &TypeRef> = entry
+        .instrs
+        .iter()
+        .filter_map(|inst| match inst {
+            Instruction::Alloca(alloca) => Some(alloca),
+            _ => None,
+        })
+        .filter(|alloca| {
+            matches!(
+                alloca.num_elements,
+                Operand::ConstantOperand(ref c) if matches!(c.as_ref(), Constant::Int { value: 1, .. })
+            )
+        })
+        .filter(|alloca| {
+            matches!(
+                alloca.allocated_type.as_ref(),
+                Type::IntegerType { .. } | Type::FPType(_) | Type::PointerType { .. }
+            )
+        })
+        .map(|alloca| (&alloca.dest, &alloca.allocated_type))
+        .collect();
+
+    let mut escaped: HashSet<&Name> = HashSet::new();
+    for bb in &func.basic_blocks {
+        for inst in &bb.instrs {
+            // uses of candidates which make them escape
+            let mut uses = LocalUses(vec![]);
+            match inst {
+                Instruction::Load(load) => {
+                    if let Some((&name, &ty)) =
+                        local_name(&load.address).and_then(|name| candidates.get_key_value(name))
+                    {
+                        if load.volatile || load.atomicity.is_some() || &types.type_of(inst) != ty {
+                            escaped.insert(name);
+                        }
+                    }
+                },
+                Instruction::Store(store) => {
+                    uses.visit_operand(&store.value);
+                    if let Some((&name, &ty)) =
+                        local_name(&store.address).and_then(|name| candidates.get_key_value(name))
+                    {
+                        if store.volatile
+                            || store.atomicity.is_some()
+                            || &types.type_of(&store.value) != ty
+                        {
+                            escaped.insert(name);
+                        }
+                    }
+                },
+                Instruction::Call(call) if is_lifetime_marker(call) => {},
+                _ => uses.visit_instruction(inst),
+            }
+            uses.escape(&candidates, &mut escaped);
+        }
+        let mut uses = LocalUses(vec![]);
+        uses.visit_terminator(&bb.term);
+        uses.escape(&candidates, &mut escaped);
+    }
+
+    entry
+        .instrs
+        .iter()
+        .filter_map(|inst| match inst {
+            Instruction::Alloca(alloca)
+                if candidates.contains_key(&alloca.dest) && !escaped.contains(&alloca.dest) =>
+            {
+                Some(Promotable {
+                    name: alloca.dest.clone(),
+                    ty: alloca.allocated_type.clone(),
+                    debugloc: alloca.debugloc.clone(),
+                })
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Collects the names of the local values used by the IR it visits
+struct LocalUses(Vec<Name>);
+
+impl LocalUses {
+    /// Mark the candidates among the uses as escaping
+    fn escape<'n>(self, candidates: &HashMap<&'n Name, &TypeRef>, escaped: &mut HashSet<&'n Name>) {
+        for name in self.0 {
+            if let Some((&name, _)) = candidates.get_key_value(&name) {
+                escaped.insert(name);
+            }
+        }
+    }
+}
+
+impl Visitor for LocalUses {
+    fn visit_operand(&mut self, operand: &Operand) {
+        if let Operand::LocalOperand { name, .. } = operand {
+            self.0.push(name.clone());
+        }
+    }
+}
+
+fn local_name(operand: &Operand) -> Option<&Name> {
+    match operand {
+        Operand::LocalOperand { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+fn is_lifetime_marker(call: &Call) -> bool {
+    match call.function.as_ref().right() {
+        Some(Operand::ConstantOperand(constant)) => matches!(
+            constant.as_ref(),
+            Constant::GlobalReference { name: Name::Name(name), .. } if name.starts_with("llvm.lifetime.")
+        ),
+        _ => false,
+    }
+}
+
+fn undef(ty: TypeRef) -> Operand {
+    Operand::ConstantOperand(ConstantRef::new(Constant::Undef(ty)))
+}
+
+/// The value `operand` stands for, following the replacements of loads. A
+/// chain of replacements can only loop back on itself in unreachable code, in
+/// which case the value is `undef`.
+fn resolve(operand: &Operand, replacements: &HashMap<Name, Operand>) -> Operand {
+    let mut operand = operand;
+    let mut seen = HashSet::new();
+    while let Operand::LocalOperand { name, ty } = operand {
+        match replacements.get(name) {
+            Some(replacement) if seen.insert(name) => operand = replacement,
+            Some(_) => return undef(ty.clone()),
+            None => break,
+        }
+    }
+    operand.clone()
+}
+
+/// Names of the function's parameters, blocks and local values
+fn defined_names(func: &Function) -> HashSet<Name> {
+    let params = func.parameters.iter().map(|p| p.name.clone());
+    let blocks = func.basic_blocks.iter().flat_map(|bb| {
+        std::iter::once(bb.name.clone())
+            .chain(
+                bb.instrs
+                    .iter()
+                    .filter_map(|inst| inst.try_get_result().cloned()),
+            )
+            .chain(bb.term.try_get_result().cloned())
+    });
+    params.chain(blocks).collect()
+}
+
+/// `name.0`, `name.1`, etc., as LLVM names the phis it inserts for an alloca
+fn fresh_name(alloca: &Name, taken: &mut HashSet<Name>) -> Name {
+    let base = match alloca {
+        Name::Name(name) => String::clone(name),
+        Name::Number(n) => n.to_string(),
+    };
+    let name = (0..)
+        .map(|i| Name::from(format!("{}.{}", base, i)))
+        .find(|name| !taken.contains(name))
+        .expect("some suffix is free");
+    taken.insert(name.clone());
+    name
+}
+
+/// Remove the phis named in `inserted` which have no uses, other than by
+/// themselves or by other such phis which are themselves dead
+fn remove_dead_phis(func: &mut Function, inserted: &HashSet<Name>) {
+    let mut live = HashSet::new();
+    let mut phi_uses: HashMap<&Name, Vec<Name>> = HashMap::new();
+    for bb in &func.basic_blocks {
+        for inst in &bb.instrs {
+            let mut uses = LocalUses(vec![]);
+            uses.visit_instruction(inst);
+            match inst {
+                Instruction::Phi(phi) if inserted.contains(&phi.dest) => {
+                    phi_uses.insert(&phi.dest, uses.0);
+                },
+                _ => live.extend(uses.0),
+            }
+        }
+        let mut uses = LocalUses(vec![]);
+        uses.visit_terminator(&bb.term);
+        live.extend(uses.0);
+    }
+    let mut worklist: Vec<Name> = live
+        .iter()
+        .filter(|name| inserted.contains(*name))
+        .cloned()
+        .collect();
+    while let Some(phi) = worklist.pop() {
+        for name in phi_uses.get(&phi).into_iter().flatten() {
+            if inserted.contains(name) && live.insert(name.clone()) {
+                worklist.push(name.clone());
+            }
+        }
+    }
+    for bb in &mut func.basic_blocks {
+        bb.instrs.retain(|inst| match inst {
+            Instruction::Phi(phi) => !inserted.contains(&phi.dest) || live.contains(&phi.dest),
+            _ => true,
+        });
+    }
+}
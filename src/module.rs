@@ -1,10 +1,17 @@
 use crate::constant::ConstantRef;
 use crate::debugloc::*;
+use crate::extract::ExtractOptions;
 use crate::from_llvm::StringInterner;
-use crate::function::{Function, FunctionAttribute, FunctionDeclaration, GroupID};
+use crate::function::{
+    BlockAddressRenamer, Function, FunctionAttribute, FunctionDeclaration, GroupID,
+};
+use crate::link::LinkError;
 use crate::llvm_sys::*;
 use crate::name::Name;
+use crate::triple::TargetTriple;
 use crate::types::{FPType, Type, TypeRef, Typed, Types, TypesBuilder};
+use crate::verify::VerifierError;
+use crate::visit::VisitorMut;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 
@@ -36,7 +43,9 @@ pub struct Module {
     pub inline_assembly: String,
     // --TODO not yet implemented-- pub metadata_nodes: Vec<(MetadataNodeID, MetadataNode)>,
     // --TODO not yet implemented-- pub named_metadatas: Vec<NamedMetadata>,
-    // --TODO not yet implemented-- pub comdats: Vec<Comdat>,
+    /// Comdats used by the functions and global variables in this `Module`.
+    /// See [LLVM 14 docs on Comdats](https://releases.llvm.org/14.0.0/docs/LangRef.html#langref-comdats)
+    pub comdats: Vec<Comdat>,
     /// Holds a reference to all of the `Type`s used in the `Module`, and
     /// facilitates lookups so you can get a `TypeRef` to the `Type` you want.
     pub types: Types,
@@ -85,6 +94,110 @@ impl Module {
             .find(|global| global.name == *name)
     }
 
+    /// Get the `Comdat` having the given `name` (if any).
+    pub fn get_comdat_by_name(&self, name: &str) -> Option<&Comdat> {
+        self.comdats.iter().find(|comdat| comdat.name == name)
+    }
+
+    /// Get the module's target triple (if any), parsed into its components.
+    pub fn parsed_target_triple(&self) -> Option<TargetTriple> {
+        self.target_triple.as_deref().map(TargetTriple::parse)
+    }
+
+    /// Check that the module is well-formed: that every local value is
+    /// defined exactly once and dominates its uses, that `Phi`s list exactly
+    /// the predecessors of their block, that branch targets exist, and that
+    /// instructions, terminators and calls have operands of the types they
+    /// require.
+    ///
+    /// This is not a reimplementation of LLVM's verifier, and doesn't catch
+    /// everything it would; it's meant for checking a `Module` after
+    /// modifying it in Rust.
+    pub fn verify(&self) -> Result<(), Vec<VerifierError>> {
+        crate::verify::verify_module(self)
+    }
+
+    /// Link two modules into one, as `llvm-link` would.
+    ///
+    /// Declarations are resolved against definitions, and when both modules
+    /// define a symbol, `Linkage` decides which definition is kept: a strong
+    /// definition beats a weak, `linkonce`/`linkonce_odr` or `common` one,
+    /// which beats an `available_externally` one; between two weak
+    /// definitions, the one from `a` is kept. `appending` arrays are
+    /// concatenated. Private and internal symbols which collide with a
+    /// symbol of the other module are renamed, along with all references to
+    /// them. Named struct types are unified by name, except that a struct
+    /// which `b` defines differently than `a` is renamed in `b` (e.g.,
+    /// `%struct.S` to `%struct.S.0`), as `llvm-link` does.
+    ///
+    /// The name, source file name, data layout and target triple of the result
+    /// are those of `a`, except that an empty data layout or missing target
+    /// triple is taken from `b`.
+    pub fn link(a: Module, b: Module) -> Result<Module, LinkError> {
+        crate::link::link_modules(a, b)
+    }
+
+    /// Create a standalone `Module` containing the functions with the given
+    /// names, and everything they need: the global variables, aliases and
+    /// ifuncs they reference (transitively, through initializers), and the
+    /// named struct types they use. Other functions they reference become
+    /// `FunctionDeclaration`s, unless `options.copy_callees` is set or the
+    /// reference is a `blockaddress`, which needs the function's body.
+    ///
+    /// The result has the same data layout, target triple and module-level
+    /// inline assembly as `self`.
+    ///
+    /// Panics if one of the `names` isn't a function defined in this
+    /// `Module`.
+    pub fn extract(&self, names: &[&str], options: ExtractOptions) -> Module {
+        crate::extract::extract(self, names, &options)
+    }
+
+    /// Reduce the module to a smaller one for which `predicate` still holds,
+    /// in the style of `llvm-reduce`. This is meant for shrinking a module
+    /// which triggers a bug (e.g., a crash in a downstream tool) into a small
+    /// test case.
+    ///
+    /// Using delta debugging, this repeatedly tries deleting global symbols,
+    /// turning function definitions into declarations, and deleting basic
+    /// blocks, instructions and function parameters; and replacing
+    /// instruction operands with zero or `undef`. A change is kept if the
+    /// predicate still holds afterwards, and, if `self` passes
+    /// [`verify()`](#method.verify), the changed module does too. This
+    /// continues until no change is kept.
+    ///
+    /// Panics if `predicate` doesn't hold for `self`.
+    pub fn reduce(&self, predicate: impl FnMut(&Module) -> bool) -> Module {
+        crate::reduce::reduce(self, predicate)
+    }
+
+    /// Call [`Function::renumber()`](../function/struct.Function.html#method.renumber)
+    /// on every function in the module, also updating the `blockaddress`
+    /// constants which refer to their blocks from elsewhere in the module,
+    /// e.g. from global variable initializers.
+    pub fn renumber(&mut self) {
+        self.rename_locals(false)
+    }
+
+    /// Call [`Function::canonicalize_names()`](../function/struct.Function.html#method.canonicalize_names)
+    /// on every function in the module, also updating the `blockaddress`
+    /// constants which refer to their blocks from elsewhere in the module.
+    pub fn canonicalize_names(&mut self) {
+        self.rename_locals(true)
+    }
+
+    fn rename_locals(&mut self, rename_named: bool) {
+        let renames: HashMap<Name, HashMap<Name, Name>> = self
+            .functions
+            .iter_mut()
+            .map(|func| {
+                let renames = func.rename_locals(rename_named);
+                (Name::from(func.name.as_str()), renames)
+            })
+            .collect();
+        BlockAddressRenamer { renames: &renames }.visit_module(self);
+    }
+
     /// Parse the LLVM bitcode (.bc) file at the given path to create a `Module`
     pub fn from_bc_path(path: impl AsRef<Path>) -> Result<Self, String> {
         Self::from_path(path, Self::parse_bc)
@@ -212,7 +325,7 @@ impl Module {
             module.assume_init()
         };
         debug!("Parsed bitcode to llvm_sys module");
-        Ok(Self::from_llvm_ref(module))
+        Self::from_llvm_ref(module)
     }
 }
 
@@ -230,7 +343,9 @@ pub struct GlobalVariable {
     pub unnamed_addr: Option<UnnamedAddr>,
     pub initializer: Option<ConstantRef>,
     pub section: Option<String>,
-    pub comdat: Option<Comdat>, // llvm-hs-pure has Option<String> for some reason
+    /// Name of the comdat this global belongs to, if any. The comdat itself
+    /// can be found in `Module.comdats`.
+    pub comdat: Option<String>,
     pub alignment: u32,
     pub debugloc: Option<DebugLoc>,
     pub value_type: TypeRef
@@ -406,6 +521,272 @@ impl PartialEq for DataLayout {
 
 impl Eq for DataLayout {}
 
+impl DataLayout {
+    /// The default data layout for x86-64 Linux (`x86_64-unknown-linux-gnu`)
+    pub fn x86_64_linux() -> Self {
+        #[cfg(feature = "llvm-9-or-lower")]
+        let layout_str = "e-m:e-i64:64-f80:128-n8:16:32:64-S128";
+        #[cfg(all(feature = "llvm-10-or-greater", feature = "llvm-17-or-lower"))]
+        let layout_str = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128";
+        #[cfg(feature = "llvm-18-or-greater")]
+        let layout_str =
+            "e-m:e-p270:32:32-p271:32:32-p272:64:64-i64:64-i128:128-f80:128-n8:16:32:64-S128";
+        Self::parse(layout_str)
+    }
+
+    /// The default data layout for 64-bit Arm Linux (`aarch64-unknown-linux-gnu`)
+    pub fn aarch64_linux() -> Self {
+        #[cfg(feature = "llvm-18-or-lower")]
+        let layout_str = "e-m:e-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128";
+        #[cfg(feature = "llvm-19-or-greater")]
+        let layout_str = "e-m:e-p270:32:32-p271:32:32-p272:64:64-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128-Fn32";
+        Self::parse(layout_str)
+    }
+
+    /// The default data layout for 32-bit WebAssembly (`wasm32-unknown-unknown`)
+    pub fn wasm32() -> Self {
+        #[cfg(feature = "llvm-13-or-lower")]
+        let layout_str = "e-m:e-p:32:32-i64:64-n32:64-S128";
+        #[cfg(all(feature = "llvm-14-or-greater", feature = "llvm-18-or-lower"))]
+        let layout_str = "e-m:e-p:32:32-p10:8:8-p20:8:8-i64:64-n32:64-S128-ni:1:10:20";
+        #[cfg(feature = "llvm-19-or-greater")]
+        let layout_str = "e-m:e-p:32:32-p10:8:8-p20:8:8-i64:64-i128:128-n32:64-S128-ni:1:10:20";
+        Self::parse(layout_str)
+    }
+
+    /// The default data layout for 64-bit RISC-V Linux (`riscv64-unknown-linux-gnu`)
+    pub fn riscv64_linux() -> Self {
+        Self::parse("e-m:e-p:64:64-i64:64-i128:128-n64-S128")
+    }
+
+    /// Render this `DataLayout` as a data layout string, based on its
+    /// individual fields rather than on `layout_str`.
+    ///
+    /// The result is canonical: specs which match LLVM's defaults are omitted,
+    /// and the remaining specs appear in a fixed order. Two `DataLayout`s
+    /// describing the same layout therefore produce the same string, even if
+    /// their `layout_str`s differ.
+    pub fn canonical_string(&self) -> String {
+        fn alignment_spec(alignment: &Alignment) -> String {
+            if alignment.pref == alignment.abi {
+                format!("{}", alignment.abi)
+            } else {
+                format!("{}:{}", alignment.abi, alignment.pref)
+            }
+        }
+
+        let defaults = Alignments::default();
+        let alignments = &self.alignments;
+        let mut specs = vec![match self.endianness {
+            Endianness::LittleEndian => "e".to_owned(),
+            Endianness::BigEndian => "E".to_owned(),
+        }];
+        if let Some(mangling) = self.mangling {
+            let mangling = match mangling {
+                Mangling::ELF => "e",
+                Mangling::MIPS => "m",
+                Mangling::MachO => "o",
+                Mangling::WindowsX86COFF => "x",
+                Mangling::WindowsCOFF => "w",
+                #[cfg(feature = "llvm-11-or-greater")]
+                Mangling::XCOFF => "a",
+            };
+            specs.push(format!("m:{}", mangling));
+        }
+        let mut addr_spaces: Vec<AddrSpace> = alignments.pointer_layouts.keys().copied().collect();
+        addr_spaces.sort_unstable();
+        for addr_space in addr_spaces {
+            let layout = &alignments.pointer_layouts[&addr_space];
+            if defaults.pointer_layouts.get(&addr_space) == Some(layout) {
+                continue;
+            }
+            let mut spec = if addr_space == 0 {
+                format!("p:{}:{}", layout.size, layout.alignment.abi)
+            } else {
+                format!("p{}:{}:{}", addr_space, layout.size, layout.alignment.abi)
+            };
+            if layout.alignment.pref != layout.alignment.abi || layout.index_size != layout.size {
+                spec.push_str(&format!(":{}", layout.alignment.pref));
+            }
+            if layout.index_size != layout.size {
+                spec.push_str(&format!(":{}", layout.index_size));
+            }
+            specs.push(spec);
+        }
+        if alignments.fptr_alignment != defaults.fptr_alignment {
+            let kind = if alignments.fptr_alignment.independent {
+                "i"
+            } else {
+                "n"
+            };
+            specs.push(format!("F{}{}", kind, alignments.fptr_alignment.abi));
+        }
+        for (size, alignment) in &alignments.int_alignments {
+            if defaults.int_alignments.get(size) != Some(alignment) {
+                specs.push(format!("i{}:{}", size, alignment_spec(alignment)));
+            }
+        }
+        for (size, alignment) in &alignments.vec_alignments {
+            if defaults.vec_alignments.get(size) != Some(alignment) {
+                specs.push(format!("v{}:{}", size, alignment_spec(alignment)));
+            }
+        }
+        let mut fp_sizes: Vec<u32> = alignments.fp_alignments.keys().copied().collect();
+        fp_sizes.sort_unstable();
+        for size in fp_sizes {
+            let alignment = &alignments.fp_alignments[&size];
+            if defaults.fp_alignments.get(&size) != Some(alignment) {
+                specs.push(format!("f{}:{}", size, alignment_spec(alignment)));
+            }
+        }
+        if alignments.agg_alignment != defaults.agg_alignment {
+            specs.push(format!("a:{}", alignment_spec(&alignments.agg_alignment)));
+        }
+        if let Some(native_int_widths) = &self.native_int_widths {
+            let mut widths: Vec<u32> = native_int_widths.iter().copied().collect();
+            widths.sort_unstable();
+            if !widths.is_empty() {
+                let widths: Vec<String> = widths.iter().map(u32::to_string).collect();
+                specs.push(format!("n{}", widths.join(":")));
+            }
+        }
+        if let Some(stack_alignment) = self.stack_alignment {
+            specs.push(format!("S{}", stack_alignment));
+        }
+        if self.program_address_space != 0 {
+            specs.push(format!("P{}", self.program_address_space));
+        }
+        if self.alloca_address_space != 0 {
+            specs.push(format!("A{}", self.alloca_address_space));
+        }
+        if !self.non_integral_ptr_types.is_empty() {
+            let mut addr_spaces: Vec<AddrSpace> =
+                self.non_integral_ptr_types.iter().copied().collect();
+            addr_spaces.sort_unstable();
+            let addr_spaces: Vec<String> = addr_spaces.iter().map(u32::to_string).collect();
+            specs.push(format!("ni:{}", addr_spaces.join(":")));
+        }
+        specs.join("-")
+    }
+}
+
+/// Builds a `DataLayout` programmatically, starting from LLVM's defaults.
+///
+/// The resulting `DataLayout`'s `layout_str` is its
+/// [`canonical_string()`](struct.DataLayout.html#method.canonical_string).
+#[derive(Clone, Debug)]
+pub struct DataLayoutBuilder {
+    data_layout: DataLayout,
+}
+
+impl Default for DataLayoutBuilder {
+    fn default() -> Self {
+        Self {
+            data_layout: DataLayout {
+                // LLVM's default when no endianness is specified
+                endianness: Endianness::LittleEndian,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl DataLayoutBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.data_layout.endianness = endianness;
+        self
+    }
+
+    /// Natural alignment of the stack, in bits
+    pub fn stack_alignment(mut self, bits: u32) -> Self {
+        self.data_layout.stack_alignment = Some(bits);
+        self
+    }
+
+    pub fn program_address_space(mut self, addr_space: AddrSpace) -> Self {
+        self.data_layout.program_address_space = addr_space;
+        self
+    }
+
+    pub fn alloca_address_space(mut self, addr_space: AddrSpace) -> Self {
+        self.data_layout.alloca_address_space = addr_space;
+        self
+    }
+
+    pub fn mangling(mut self, mangling: Mangling) -> Self {
+        self.data_layout.mangling = Some(mangling);
+        self
+    }
+
+    /// Layout of (non-function-pointer) pointers in the given address space
+    pub fn pointer_layout(mut self, addr_space: AddrSpace, layout: PointerLayout) -> Self {
+        self.data_layout
+            .alignments
+            .set_pointer_layout(addr_space, layout);
+        self
+    }
+
+    /// Alignment of integers of the given size (in bits)
+    pub fn int_alignment(mut self, size: u32, alignment: Alignment) -> Self {
+        self.data_layout
+            .alignments
+            .set_int_alignment(size, alignment);
+        self
+    }
+
+    /// Alignment of vectors of the given total size (in bits)
+    pub fn vec_alignment(mut self, size: u32, alignment: Alignment) -> Self {
+        self.data_layout
+            .alignments
+            .set_vec_alignment(size, alignment);
+        self
+    }
+
+    /// Alignment of floating-point types of the given size (in bits)
+    pub fn fp_alignment(mut self, size: u32, alignment: Alignment) -> Self {
+        self.data_layout
+            .alignments
+            .set_fp_alignment(size, alignment);
+        self
+    }
+
+    /// Alignment of aggregate types (structs, arrays)
+    pub fn agg_alignment(mut self, alignment: Alignment) -> Self {
+        self.data_layout.alignments.set_agg_alignment(alignment);
+        self
+    }
+
+    pub fn fptr_alignment(mut self, alignment: FunctionPtrAlignment) -> Self {
+        self.data_layout.alignments.set_fptr_alignment(alignment);
+        self
+    }
+
+    /// Native integer width(s) for the target CPU, in bits
+    pub fn native_int_widths(mut self, widths: impl IntoIterator<Item = u32>) -> Self {
+        self.data_layout.native_int_widths = Some(widths.into_iter().collect());
+        self
+    }
+
+    /// Mark the given (nonzero) address space as having non-integral pointers
+    pub fn non_integral_address_space(mut self, addr_space: AddrSpace) -> Self {
+        assert_ne!(
+            addr_space, 0,
+            "LLVM spec does not allow address space 0 to have non-integral pointer types"
+        );
+        self.data_layout.non_integral_ptr_types.insert(addr_space);
+        self
+    }
+
+    pub fn build(mut self) -> DataLayout {
+        self.data_layout.layout_str = self.data_layout.canonical_string();
+        self.data_layout
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
 pub enum Endianness {
     /// Least-significant bits are stored in the lowest address location
@@ -582,8 +963,60 @@ impl Alignments {
         }
     }
 
+    /// Explicit alignments for various sizes of integers (in bits). Sizes
+    /// not present here are handled as described in `int_alignment()`.
+    pub fn explicit_int_alignments(&self) -> &BTreeMap<u32, Alignment> {
+        &self.int_alignments
+    }
+
+    /// Explicit alignments for various sizes of vectors (in bits). Sizes not
+    /// present here are handled as described in `vec_alignment()`.
+    pub fn explicit_vec_alignments(&self) -> &BTreeMap<u32, Alignment> {
+        &self.vec_alignments
+    }
+
+    /// Alignments for floating-point types, by size (in bits)
+    pub fn explicit_fp_alignments(&self) -> &HashMap<u32, Alignment> {
+        &self.fp_alignments
+    }
+
+    /// Layout details for (non-function-pointer) pointers, for each address
+    /// space which has them specified. Other address spaces use the layout of
+    /// address space 0.
+    pub fn pointer_layouts(&self) -> &HashMap<AddrSpace, PointerLayout> {
+        &self.pointer_layouts
+    }
+
+    pub fn set_int_alignment(&mut self, size: u32, alignment: Alignment) {
+        self.int_alignments.insert(size, alignment);
+    }
+
+    pub fn set_vec_alignment(&mut self, size: u32, alignment: Alignment) {
+        self.vec_alignments.insert(size, alignment);
+    }
+
+    pub fn set_fp_alignment(&mut self, size: u32, alignment: Alignment) {
+        self.fp_alignments.insert(size, alignment);
+    }
+
+    pub fn set_agg_alignment(&mut self, alignment: Alignment) {
+        self.agg_alignment = alignment;
+    }
+
+    pub fn set_fptr_alignment(&mut self, alignment: FunctionPtrAlignment) {
+        self.fptr_alignment_as_alignment = Alignment {
+            abi: alignment.abi,
+            pref: alignment.abi,
+        };
+        self.fptr_alignment = alignment;
+    }
+
+    pub fn set_pointer_layout(&mut self, addr_space: AddrSpace, layout: PointerLayout) {
+        self.pointer_layouts.insert(addr_space, layout);
+    }
+
     /// for internal use: size of an `FPType`, in bits
-    fn fpt_size(fpt: FPType) -> u32 {
+    pub(crate) fn fpt_size(fpt: FPType) -> u32 {
         match fpt {
             FPType::Half => 16,
             #[cfg(feature = "llvm-11-or-greater")]
@@ -613,6 +1046,7 @@ pub enum Mangling {
 // from_llvm //
 // ********* //
 
+use crate::basicblock::BasicBlock;
 use crate::constant::Constant;
 use crate::from_llvm::*;
 use crate::function::AttributesData;
@@ -640,24 +1074,93 @@ pub(crate) struct ModuleContext<'a> {
     pub global_names: &'a HashMap<LLVMValueRef, Name>,
     /// String interner for debug location filenames and directories
     pub string_interner: StringInterner,
+    /// Map from an llvm-sys function to the `Name`s of its basic blocks.
+    /// Only populated on demand; see `bb_name()`
+    // We use LLVMValueRef and LLVMBasicBlockRef as *const, even though they're technically *mut
+    #[allow(clippy::mutable_key_type)]
+    pub bb_names: HashMap<LLVMValueRef, HashMap<LLVMBasicBlockRef, Name>>,
+    /// Comdats seen so far, in order of first use
+    pub comdats: Vec<Comdat>,
+    /// Map from an llvm-sys comdat to its name, for all comdats in the module;
+    /// see `Comdat::names_from_module()`
+    // We use LLVMComdatRef as a *const, even though it's technically a *mut
+    #[allow(clippy::mutable_key_type)]
+    comdat_names: HashMap<LLVMComdatRef, String>,
+    /// The llvm-sys comdats which have been added to `comdats`
+    #[allow(clippy::mutable_key_type)]
+    used_comdats: HashSet<LLVMComdatRef>,
 }
 
 impl<'a> ModuleContext<'a> {
     // We use LLVMValueRef as a *const, even though it's technically a *mut
     #[allow(clippy::mutable_key_type)]
-    fn new(global_names: &'a HashMap<LLVMValueRef, Name>) -> Self {
+    fn new(
+        global_names: &'a HashMap<LLVMValueRef, Name>,
+        comdat_names: HashMap<LLVMComdatRef, String>,
+        #[cfg(feature = "llvm-19-or-greater")] context: LLVMContextRef,
+    ) -> Self {
         Self {
             types: TypesBuilder::new(),
-            attrsdata: AttributesData::create(),
+            attrsdata: AttributesData::create(
+                #[cfg(feature = "llvm-19-or-greater")]
+                context,
+            ),
             constants: HashMap::new(),
             global_names,
             string_interner: StringInterner::new(),
+            bb_names: HashMap::new(),
+            comdats: Vec::new(),
+            comdat_names,
+            used_comdats: HashSet::new(),
         }
     }
+
+    /// Get the name of the comdat which the given function or global variable
+    /// belongs to (if any), adding the comdat to `comdats` if this is its first
+    /// use.
+    pub fn comdat_name(&mut self, global: LLVMValueRef) -> Option<String> {
+        let comdat = unsafe { LLVMGetComdat(global) };
+        if comdat.is_null() {
+            return None;
+        }
+        let name = self
+            .comdat_names
+            .get(&comdat)
+            .expect("Comdat should have been named in Comdat::names_from_module()")
+            .clone();
+        if self.used_comdats.insert(comdat) {
+            self.comdats
+                .push(Comdat::from_llvm_ref(comdat, name.clone()));
+        }
+        Some(name)
+    }
+
+    /// Get the `Name` of the given basic block in the given function, for
+    /// constants (e.g., `blockaddress`) which refer to a block from outside
+    /// of any `FunctionContext`.
+    ///
+    /// Unnamed parameters, values, and blocks are numbered exactly as
+    /// `Function::from_llvm_ref` numbers them.
+    pub fn bb_name(&mut self, func: LLVMValueRef, bb: LLVMBasicBlockRef) -> Name {
+        self.bb_names
+            .entry(func)
+            .or_insert_with(|| {
+                let mut ctr = 0;
+                for param in get_parameters(func) {
+                    Name::name_or_num(unsafe { get_value_name(param) }, &mut ctr);
+                }
+                get_basic_blocks(func)
+                    .map(|bb| (bb, BasicBlock::first_pass_names(bb, &mut ctr).0))
+                    .collect()
+            })
+            .get(&bb)
+            .expect("Expected to find bb in its function")
+            .clone()
+    }
 }
 
 impl Module {
-    pub(crate) fn from_llvm_ref(module: LLVMModuleRef) -> Self {
+    pub(crate) fn from_llvm_ref(module: LLVMModuleRef) -> Result<Self, String> {
         debug!("Creating a Module from an LLVMModuleRef");
         let mut global_ctr = 0; // this ctr is used to number global objects that aren't named
 
@@ -684,9 +1187,16 @@ impl Module {
             .collect();
         global_ctr = 0; // reset the global_ctr; the second pass should number everything exactly the same though
 
-        let mut ctx = ModuleContext::new(&global_names);
+        let mut ctx = ModuleContext::new(
+            &global_names,
+            Comdat::names_from_module(module)?,
+            #[cfg(feature = "llvm-19-or-greater")]
+            unsafe {
+                LLVMGetModuleContext(module)
+            },
+        );
 
-        Self {
+        Ok(Self {
             name: unsafe { get_module_identifier(module) },
             source_file_name: unsafe { get_source_file_name(module) },
             data_layout: DataLayout::from_module_ref(module),
@@ -710,9 +1220,9 @@ impl Module {
             inline_assembly: unsafe { get_module_inline_asm(module) },
             // metadata_nodes: unimplemented!("metadata_nodes"),
             // named_metadatas: unimplemented!("named_metadatas"),
-            // comdats: unimplemented!("comdats"),  // I think llvm-hs also collects these along the way
+            comdats: ctx.comdats,
             types: ctx.types.build(),
-        }
+        })
     }
 }
 
@@ -752,14 +1262,7 @@ impl GlobalVariable {
                 }
             },
             section: unsafe { get_section(global) },
-            comdat: {
-                let comdat = unsafe { LLVMGetComdat(global) };
-                if comdat.is_null() {
-                    None
-                } else {
-                    Some(Comdat::from_llvm_ref(unsafe { LLVMGetComdat(global) }))
-                }
-            },
+            comdat: ctx.comdat_name(global),
             alignment: unsafe { LLVMGetAlignment(global) },
             debugloc: DebugLoc::from_llvm_no_col(global, &mut ctx.string_interner),
             value_type,
@@ -889,12 +1392,94 @@ impl ThreadLocalMode {
 }
 
 impl Comdat {
-    pub(crate) fn from_llvm_ref(comdat: LLVMComdatRef) -> Self {
+    pub(crate) fn from_llvm_ref(comdat: LLVMComdatRef, name: String) -> Self {
         Self {
-            name: "error: not yet implemented: Comdat.name".to_owned(), // there appears to not be a getter for this in the LLVM C API?  I could be misunderstanding something
+            name,
             selection_kind: SelectionKind::from_llvm(unsafe { LLVMGetComdatSelectionKind(comdat) }),
         }
     }
+
+    /// Get the names of all the comdats used in the given module.
+    ///
+    /// Each comdat is named only once, from the first global object in it.
+    /// Printing a function prints its whole body, so global variables are
+    /// tried first: many comdats contain both a function and a variable.
+    // We use LLVMComdatRef as a *const, even though it's technically a *mut
+    #[allow(clippy::mutable_key_type)]
+    fn names_from_module(module: LLVMModuleRef) -> Result<HashMap<LLVMComdatRef, String>, String> {
+        let mut names = HashMap::new();
+        let globals = get_globals(module)
+            .chain(get_declared_functions(module))
+            .chain(get_defined_functions(module));
+        for global in globals {
+            let comdat = unsafe { LLVMGetComdat(global) };
+            if !comdat.is_null() && !names.contains_key(&comdat) {
+                names.insert(comdat, Self::name_from_global(global)?);
+            }
+        }
+        Ok(names)
+    }
+
+    /// Get the name of the comdat which the given function or global variable
+    /// belongs to.
+    ///
+    /// The LLVM C API has no getter for a comdat's name, so we recover it from
+    /// the printed IR of the global object: that has either `comdat($name)`,
+    /// or just `comdat` if the comdat has the same name as the global object.
+    fn name_from_global(global: LLVMValueRef) -> Result<String, String> {
+        let text = unsafe { print_to_string(global) };
+        // for functions, only look at the header, not the body
+        let header = text
+            .lines()
+            .find(|line| line.starts_with("define "))
+            .unwrap_or(&text);
+        let bytes = header.as_bytes();
+        let mut in_quotes = false;
+        for (i, &byte) in bytes.iter().enumerate() {
+            if byte == b'"' {
+                in_quotes = !in_quotes;
+            } else if !in_quotes
+                && header[i ..].starts_with("comdat")
+                && i > 0
+                && (bytes[i - 1] == b' ' || bytes[i - 1] == b',')
+            {
+                let rest = &header[i + "comdat".len() ..];
+                if let Some(name) = rest.strip_prefix("($") {
+                    return Self::parse_name(name);
+                } else if rest.is_empty() || rest.starts_with(' ') || rest.starts_with(',') {
+                    return Ok(unsafe { get_value_name(global) });
+                }
+            }
+        }
+        Err(format!("Failed to find the comdat in {:?}", header))
+    }
+
+    /// Parse a comdat name as printed in IR, which may be quoted (with
+    /// `\XX` hex escapes), up to the closing parenthesis
+    fn parse_name(text: &str) -> Result<String, String> {
+        let unterminated = || format!("Unterminated comdat name in {:?}", text);
+        match text.strip_prefix('"') {
+            Some(quoted) => {
+                let quoted = &quoted[.. quoted.find('"').ok_or_else(unterminated)?];
+                let mut bytes = Vec::with_capacity(quoted.len());
+                let mut escaped = quoted.bytes();
+                while let Some(byte) = escaped.next() {
+                    if byte == b'\\' {
+                        let hex: Vec<u8> = escaped.by_ref().take(2).collect();
+                        let byte = std::str::from_utf8(&hex)
+                            .ok()
+                            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                            .ok_or_else(|| format!("Invalid escape in comdat name {:?}", quoted))?;
+                        bytes.push(byte);
+                    } else {
+                        bytes.push(byte);
+                    }
+                }
+                Ok(String::from_utf8_lossy(&bytes).into_owned())
+            },
+            None => Ok(text[.. text.find(')').ok_or_else(unterminated)?].to_owned()),
+        }
+    }
 }
 
 impl SelectionKind {
@@ -928,9 +1513,16 @@ impl Default for DataLayout {
 
 impl DataLayout {
     pub(crate) fn from_module_ref(module: LLVMModuleRef) -> Self {
-        let layout_str = unsafe { get_data_layout_str(module) };
+        Self::parse(&unsafe { get_data_layout_str(module) })
+    }
+
+    /// Parse a data layout string, as described in the
+    /// [LLVM 14 docs on Data Layout](https://releases.llvm.org/14.0.0/docs/LangRef.html#data-layout).
+    ///
+    /// Panics if the string is malformed.
+    pub fn parse(layout_str: &str) -> Self {
         let mut data_layout = DataLayout {
-            layout_str,
+            layout_str: layout_str.to_owned(),
             ..Default::default()
         };
         for spec in data_layout.layout_str.split('-') {
@@ -0,0 +1,323 @@
+//! A lightweight framework for running analyses and transformations over a
+//! `Module`.
+//!
+//! Transformations implement [`ModulePass`](trait.ModulePass.html) or
+//! [`FunctionPass`](trait.FunctionPass.html), and are run in order by a
+//! [`PassManager`](struct.PassManager.html). Analyses implement
+//! [`FunctionAnalysis`](trait.FunctionAnalysis.html); their results are
+//! computed on demand by the [`AnalysisManager`](struct.AnalysisManager.html)
+//! and cached until a pass reports that it changed the function.
+//!
+//! ```
+//! use llvm_ir::pass::{AnalysisManager, FunctionAnalysis, FunctionPass, PassManager};
+//! use llvm_ir::types::Types;
+//! use llvm_ir::Function;
+//!
+//! /// Number of instructions in a function
+//! struct InstructionCount;
+//!
+//! impl FunctionAnalysis for InstructionCount {
+//!     type Result = usize;
+//!
+//!     fn run(function: &Function, _: &Types, _: &mut AnalysisManager) -> usize {
+//!         function.basic_blocks.iter().map(|bb| bb.instrs.len()).sum()
+//!     }
+//! }
+//!
+//! /// Prints the number of instructions in each function
+//! struct PrintInstructionCounts;
+//!
+//! impl FunctionPass for PrintInstructionCounts {
+//!     fn run_on_function(
+//!         &mut self,
+//!         function: &mut Function,
+//!         types: &Types,
+//!         analyses: &mut AnalysisManager,
+//!     ) -> bool {
+//!         let count = analyses.get::<InstructionCount>(function, types);
+//!         println!("{}: {} instructions", function.name, count);
+//!         false // the function wasn't changed
+//!     }
+//! }
+//!
+//! let mut passes = PassManager::new();
+//! passes.add_function_pass(PrintInstructionCounts);
+//! ```
+
+use crate::function::Function;
+use crate::module::Module;
+use crate::types::Types;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// An analysis of a single `Function`, whose results are cached by the
+/// [`AnalysisManager`](struct.AnalysisManager.html). Analyses are identified
+/// by their type.
+///
+/// The built-in analyses are the [`ControlFlowGraph`](../cfg/struct.ControlFlowGraph.html)
+/// and the [`DominatorTree`](../cfg/struct.DominatorTree.html).
+pub trait FunctionAnalysis: 'static {
+    /// Results are shared, so they must be `Send` and `Sync` for the
+    /// `AnalysisManager` to be.
+    type Result: Send + Sync + 'static;
+
+    /// Compute the analysis. `analyses` can be used to get the results of
+    /// other analyses this one depends on.
+    fn run(function: &Function, types: &Types, analyses: &mut AnalysisManager) -> Self::Result;
+}
+
+/// A pass which may analyze or transform a whole `Module`
+pub trait ModulePass {
+    /// Name used in the [`PassStatistics`](struct.PassStatistics.html)
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Run the pass, returning `true` if it changed the module
+    fn run_on_module(&mut self, module: &mut Module, analyses: &mut AnalysisManager) -> bool;
+}
+
+/// A pass which analyzes or transforms one `Function` at a time
+pub trait FunctionPass {
+    /// Name used in the [`PassStatistics`](struct.PassStatistics.html)
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Run the pass on one function, returning `true` if it changed the
+    /// function
+    fn run_on_function(
+        &mut self,
+        function: &mut Function,
+        types: &Types,
+        analyses: &mut AnalysisManager,
+    ) -> bool;
+}
+
+/// Computes and caches the results of
+/// [`FunctionAnalysis`](trait.FunctionAnalysis.html)es, per function.
+///
+/// Results are keyed by the function's name, so they must be invalidated
+/// whenever the function changes. The [`PassManager`](struct.PassManager.html)
+/// does this automatically when a pass reports a change.
+#[derive(Default)]
+pub struct AnalysisManager {
+    /// Map from (function name, analysis type) to the analysis result
+    results: HashMap<(String, TypeId), Arc<dyn Any + Send + Sync>>,
+    statistics: HashMap<TypeId, AnalysisStatistics>,
+}
+
+/// Statistics about one analysis, collected by the
+/// [`AnalysisManager`](struct.AnalysisManager.html)
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct AnalysisStatistics {
+    /// Name of the analysis type
+    pub name: &'static str,
+    /// Number of times the analysis was computed
+    pub computations: usize,
+    /// Number of times a cached result was returned instead
+    pub cache_hits: usize,
+    /// Total time spent computing the analysis, including the time spent
+    /// computing analyses it depends on
+    pub time: Duration,
+}
+
+impl AnalysisManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the result of the analysis `A` on `function`, computing it if it
+    /// isn't cached
+    pub fn get<A: FunctionAnalysis>(
+        &mut self,
+        function: &Function,
+        types: &Types,
+    ) -> Arc<A::Result> {
+        let key = (function.name.clone(), TypeId::of::<A>());
+        if let Some(result) = self.results.get(&key) {
+            let result = Arc::clone(result)
+                .downcast::<A::Result>()
+                .expect("results are keyed by analysis type");
+            self.statistics_for::<A>().cache_hits += 1;
+            return result;
+        }
+        let start = Instant::now();
+        let result = Arc::new(A::run(function, types, self));
+        let elapsed = start.elapsed();
+        let statistics = self.statistics_for::<A>();
+        statistics.computations += 1;
+        statistics.time += elapsed;
+        self.results
+            .insert(key, Arc::clone(&result) as Arc<dyn Any + Send + Sync>);
+        result
+    }
+
+    /// Get the result of the analysis `A` on the function with the given name,
+    /// only if it is already cached
+    pub fn get_cached<A: FunctionAnalysis>(&self, function_name: &str) -> Option<Arc<A::Result>> {
+        self.results
+            .get(&(function_name.to_owned(), TypeId::of::<A>()))
+            .map(|result| {
+                Arc::clone(result)
+                    .downcast::<A::Result>()
+                    .expect("results are keyed by analysis type")
+            })
+    }
+
+    /// Discard all cached results for the function with the given name
+    pub fn invalidate(&mut self, function_name: &str) {
+        self.results.retain(|(name, _), _| name != function_name);
+    }
+
+    /// Discard the cached result of the analysis `A` for the function with the
+    /// given name, keeping the results of other analyses
+    pub fn invalidate_analysis<A: FunctionAnalysis>(&mut self, function_name: &str) {
+        self.results
+            .remove(&(function_name.to_owned(), TypeId::of::<A>()));
+    }
+
+    /// Discard all cached results
+    pub fn invalidate_all(&mut self) {
+        self.results.clear();
+    }
+
+    /// Statistics for each analysis which has been requested, in no particular
+    /// order
+    pub fn statistics(&self) -> impl Iterator<Item = &AnalysisStatistics> {
+        self.statistics.values()
+    }
+
+    fn statistics_for<A: FunctionAnalysis>(&mut self) -> &mut AnalysisStatistics {
+        self.statistics
+            .entry(TypeId::of::<A>())
+            .or_insert_with(|| AnalysisStatistics {
+                name: std::any::type_name::<A>(),
+                computations: 0,
+                cache_hits: 0,
+                time: Duration::default(),
+            })
+    }
+}
+
+enum Pass {
+    Module(Box<dyn ModulePass>),
+    Function(Box<dyn FunctionPass>),
+}
+
+/// Statistics about one pass in a [`PassManager`](struct.PassManager.html)'s
+/// pipeline, accumulated over all runs of the pipeline
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PassStatistics {
+    /// The pass's [`name()`](trait.FunctionPass.html#method.name)
+    pub name: String,
+    /// Number of times the pass was run: once per module for `ModulePass`es,
+    /// and once per function for `FunctionPass`es
+    pub runs: usize,
+    /// Number of those runs which reported a change
+    pub changes: usize,
+    /// Total time spent in the pass, including computing the analyses it
+    /// requested
+    pub time: Duration,
+}
+
+/// Runs a pipeline of passes over a `Module`, in the order they were added.
+///
+/// The `PassManager` owns an [`AnalysisManager`](struct.AnalysisManager.html)
+/// shared by all the passes, and invalidates its cached results for a
+/// function when a `FunctionPass` reports changing that function, or for all
+/// functions when a `ModulePass` reports a change.
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Pass>,
+    statistics: Vec<PassStatistics>,
+    analyses: AnalysisManager,
+}
+
+impl PassManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a `ModulePass` to the end of the pipeline
+    pub fn add_module_pass(&mut self, pass: impl ModulePass + 'static) {
+        self.add(Pass::Module(Box::new(pass)));
+    }
+
+    /// Add a `FunctionPass` to the end of the pipeline. It will be run on each
+    /// function defined in the module, in order.
+    pub fn add_function_pass(&mut self, pass: impl FunctionPass + 'static) {
+        self.add(Pass::Function(Box::new(pass)));
+    }
+
+    fn add(&mut self, pass: Pass) {
+        let name = match &pass {
+            Pass::Module(pass) => pass.name(),
+            Pass::Function(pass) => pass.name(),
+        };
+        self.statistics.push(PassStatistics {
+            name: name.to_owned(),
+            runs: 0,
+            changes: 0,
+            time: Duration::default(),
+        });
+        self.passes.push(pass);
+    }
+
+    /// Run the pipeline on `module`, returning `true` if any pass changed it.
+    ///
+    /// Cached analysis results from previous runs are discarded first, since
+    /// `module` may be a different module, or may have been changed outside
+    /// the pipeline.
+    pub fn run(&mut self, module: &mut Module) -> bool {
+        self.analyses.invalidate_all();
+        let mut changed = false;
+        for (pass, statistics) in self.passes.iter_mut().zip(&mut self.statistics) {
+            match pass {
+                Pass::Module(pass) => {
+                    let start = Instant::now();
+                    let pass_changed = pass.run_on_module(module, &mut self.analyses);
+                    statistics.time += start.elapsed();
+                    statistics.runs += 1;
+                    if pass_changed {
+                        statistics.changes += 1;
+                        self.analyses.invalidate_all();
+                        changed = true;
+                    }
+                },
+                Pass::Function(pass) => {
+                    let Module {
+                        functions, types, ..
+                    } = &mut *module;
+                    for function in functions.iter_mut() {
+                        let start = Instant::now();
+                        let pass_changed =
+                            pass.run_on_function(function, types, &mut self.analyses);
+                        statistics.time += start.elapsed();
+                        statistics.runs += 1;
+                        if pass_changed {
+                            statistics.changes += 1;
+                            self.analyses.invalidate(&function.name);
+                            changed = true;
+                        }
+                    }
+                },
+            }
+        }
+        changed
+    }
+
+    /// Statistics for each pass in the pipeline, in order
+    pub fn statistics(&self) -> &[PassStatistics] {
+        &self.statistics
+    }
+
+    /// The `AnalysisManager` shared by the passes, e.g. to get its statistics
+    /// or results cached by the last run (which are valid until the module
+    /// changes)
+    pub fn analyses(&mut self) -> &mut AnalysisManager {
+        &mut self.analyses
+    }
+}
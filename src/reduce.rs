@@ -0,0 +1,587 @@
+//! Test-case reduction in the style of `llvm-reduce`; see
+//! [`Module::reduce()`](../module/struct.Module.html#method.reduce).
+
+use crate::constant::{Constant, ConstantRef};
+use crate::debugloc::HasDebugLoc;
+use crate::extract::declaration_of;
+use crate::function::Function;
+use crate::instruction::{self, Instruction};
+use crate::module::Module;
+use crate::name::Name;
+use crate::operand::Operand;
+use crate::terminator::{self, Terminator, Unreachable};
+use crate::types::{Type, TypeRef, Types};
+use crate::visit::{walk_mut, Visitor, VisitorMut};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+pub(crate) fn reduce(module: &Module, predicate: impl FnMut(&Module) -> bool) -> Module {
+    let mut reducer = Reducer {
+        current: module.clone(),
+        predicate,
+        // if the original module doesn't pass the verifier, we can't ask the
+        // reduced ones to
+        verify: module.verify().is_ok(),
+    };
+    if !(reducer.predicate)(module) {
+        panic!("Module::reduce: the predicate doesn't hold for the original module");
+    }
+    loop {
+        let mut progress = false;
+        progress |= reducer.run(count_symbols, remove_symbols);
+        progress |= reducer.run(count_bodies, remove_bodies);
+        progress |= reducer.run(count_blocks, remove_blocks);
+        progress |= reducer.run(count_instructions, remove_instructions);
+        progress |= reducer.run(count_arguments, remove_arguments);
+        progress |= reducer.run(count_operands, simplify_operands);
+        if !progress {
+            return reducer.current;
+        }
+    }
+}
+
+struct Reducer<P> {
+    current: Module,
+    predicate: P,
+    verify: bool,
+}
+
+impl<P: FnMut(&Module) -> bool> Reducer<P> {
+    /// Delta debugging over one kind of item: apply `reduce` to chunks of the
+    /// items, halving the chunk size down to a single item, and keep each
+    /// candidate for which the predicate still holds.
+    ///
+    /// `count` gives the number of items in a module. Applying `reduce` to a
+    /// range of items must reduce the count if it changes anything.
+    ///
+    /// Returns `true` if any candidate was kept.
+    fn run(&mut self, count: fn(&Module) -> usize, reduce: fn(&mut Module, Range<usize>)) -> bool {
+        let mut progress = false;
+        let mut chunk_size = count(&self.current);
+        while chunk_size > 0 {
+            let mut start = 0;
+            while start < count(&self.current) {
+                let mut candidate = self.current.clone();
+                reduce(&mut candidate, start .. start + chunk_size);
+                if count(&candidate) < count(&self.current) && self.is_interesting(&candidate) {
+                    self.current = candidate;
+                    progress = true;
+                } else {
+                    start += chunk_size;
+                }
+            }
+            chunk_size /= 2;
+        }
+        progress
+    }
+
+    fn is_interesting(&mut self, candidate: &Module) -> bool {
+        (!self.verify || candidate.verify().is_ok()) && (self.predicate)(candidate)
+    }
+}
+
+/// The items of `items` in `range`, which may extend past the end
+fn in_range<T>(items: Vec<T>, range: Range<usize>) -> impl Iterator<Item = T> {
+    items
+        .into_iter()
+        .skip(range.start)
+        .take(range.end - range.start)
+}
+
+fn func_name(func_name: &str) -> Name {
+    Name::from(func_name)
+}
+
+/// Functions, function declarations, global variables, aliases and ifuncs
+fn symbols(module: &Module) -> Vec<Name> {
+    module
+        .functions
+        .iter()
+        .map(|f| func_name(&f.name))
+        .chain(module.func_declarations.iter().map(|d| func_name(&d.name)))
+        .chain(module.global_vars.iter().map(|g| g.name.clone()))
+        .chain(module.global_aliases.iter().map(|a| a.name.clone()))
+        .chain(module.global_ifuncs.iter().map(|i| i.name.clone()))
+        .collect()
+}
+
+fn count_symbols(module: &Module) -> usize {
+    symbols(module).len()
+}
+
+/// Delete global symbols, replacing references to them with `undef`
+fn remove_symbols(module: &mut Module, range: Range<usize>) {
+    let names: HashSet<Name> = in_range(symbols(module), range).collect();
+    struct Undefiner<'a> {
+        types: &'a Types,
+        names: &'a HashSet<Name>,
+    }
+    impl VisitorMut for Undefiner<'_> {
+        fn visit_constant(&mut self, constant: &mut ConstantRef) {
+            if let Constant::GlobalReference { name, .. } = constant.as_ref() {
+                if self.names.contains(name) {
+                    let ty = self.types.type_of(&*constant);
+                    *constant = ConstantRef::new(Constant::Undef(ty));
+                    return;
+                }
+            }
+            walk_mut::constant(self, constant)
+        }
+    }
+    let Module {
+        types,
+        functions,
+        func_declarations,
+        global_vars,
+        global_aliases,
+        global_ifuncs,
+        ..
+    } = module;
+    let mut undefiner = Undefiner {
+        types,
+        names: &names,
+    };
+    functions
+        .iter_mut()
+        .for_each(|f| undefiner.visit_function(f));
+    global_vars
+        .iter_mut()
+        .for_each(|g| undefiner.visit_global_variable(g));
+    global_aliases
+        .iter_mut()
+        .for_each(|a| undefiner.visit_global_alias(a));
+    global_ifuncs
+        .iter_mut()
+        .for_each(|i| undefiner.visit_global_ifunc(i));
+
+    functions.retain(|f| !names.contains(&func_name(&f.name)));
+    func_declarations.retain(|d| !names.contains(&func_name(&d.name)));
+    global_vars.retain(|g| !names.contains(&g.name));
+    global_aliases.retain(|a| !names.contains(&a.name));
+    global_ifuncs.retain(|i| !names.contains(&i.name));
+}
+
+fn count_bodies(module: &Module) -> usize {
+    module.functions.len()
+}
+
+/// Turn function definitions into declarations
+fn remove_bodies(module: &mut Module, range: Range<usize>) {
+    let names: HashSet<String> = in_range(module.functions.iter().collect(), range)
+        .map(|f| f.name.clone())
+        .collect();
+    let (removed, kept) = std::mem::take(&mut module.functions)
+        .into_iter()
+        .partition::<Vec<_>, _>(|f| names.contains(&f.name));
+    module.functions = kept;
+    module
+        .func_declarations
+        .extend(removed.iter().map(declaration_of));
+}
+
+/// Non-entry blocks, as (function index, block name)
+fn blocks(module: &Module) -> Vec<(usize, Name)> {
+    module
+        .functions
+        .iter()
+        .enumerate()
+        .flat_map(|(i, f)| {
+            f.basic_blocks
+                .iter()
+                .skip(1)
+                .map(move |bb| (i, bb.name.clone()))
+        })
+        .collect()
+}
+
+fn count_blocks(module: &Module) -> usize {
+    blocks(module).len()
+}
+
+/// Delete basic blocks. Terminators which branch to a deleted block become
+/// `unreachable`, and uses of values defined in deleted blocks become `undef`.
+fn remove_blocks(module: &mut Module, range: Range<usize>) {
+    let mut by_func: HashMap<usize, HashSet<Name>> = HashMap::new();
+    for (func, block) in in_range(blocks(module), range) {
+        by_func.entry(func).or_default().insert(block);
+    }
+    for (func, removed) in by_func {
+        let func = &mut module.functions[func];
+        let types = &module.types;
+        let mut undefined = vec![];
+        for bb in &func.basic_blocks {
+            if removed.contains(&bb.name) {
+                undefined.extend(bb.instrs.iter().filter_map(|inst| {
+                    inst.try_get_result()
+                        .map(|name| (name.clone(), types.type_of(inst)))
+                }));
+                if let Some(name) = bb.term.try_get_result() {
+                    undefined.push((name.clone(), types.type_of(&bb.term)));
+                }
+            }
+        }
+        func.basic_blocks.retain(|bb| !removed.contains(&bb.name));
+        for bb in &mut func.basic_blocks {
+            if bb
+                .term
+                .successors()
+                .iter()
+                .any(|succ| removed.contains(*succ))
+            {
+                bb.term = Terminator::Unreachable(Unreachable {
+                    debugloc: bb.term.get_debug_loc().clone(),
+                });
+            }
+        }
+        undefine(func, undefined);
+        remove_dead_phi_entries(func);
+    }
+}
+
+/// Replace all uses of the given local values with `undef`
+fn undefine(func: &mut Function, values: Vec<(Name, TypeRef)>) {
+    for (name, ty) in values {
+        func.replace_all_uses(
+            &name,
+            Operand::ConstantOperand(ConstantRef::new(Constant::Undef(ty))),
+        );
+    }
+}
+
+/// Remove `Phi` entries for edges which no longer exist, and `Phi`s left
+/// without any entries
+fn remove_dead_phi_entries(func: &mut Function) {
+    let mut preds: HashMap<Name, HashSet<Name>> = HashMap::new();
+    for bb in &func.basic_blocks {
+        for succ in bb.term.successors() {
+            preds
+                .entry(succ.clone())
+                .or_default()
+                .insert(bb.name.clone());
+        }
+    }
+    let no_preds = HashSet::new();
+    let mut undefined = vec![];
+    for bb in &mut func.basic_blocks {
+        let preds = preds.get(&bb.name).unwrap_or(&no_preds);
+        for inst in &mut bb.instrs {
+            if let Instruction::Phi(phi) = inst {
+                phi.incoming_values.retain(|(_, from)| preds.contains(from));
+                if phi.incoming_values.is_empty() {
+                    undefined.push((phi.dest.clone(), phi.to_type.clone()));
+                }
+            }
+        }
+        bb.instrs.retain(
+            |inst| !matches!(inst, Instruction::Phi(phi) if phi.incoming_values.is_empty()),
+        );
+    }
+    undefine(func, undefined);
+}
+
+/// All instructions, as (function index, block index, instruction index)
+fn instructions(module: &Module) -> Vec<(usize, usize, usize)> {
+    let mut instructions = vec![];
+    for (f, func) in module.functions.iter().enumerate() {
+        for (b, bb) in func.basic_blocks.iter().enumerate() {
+            instructions.extend((0 .. bb.instrs.len()).map(|i| (f, b, i)));
+        }
+    }
+    instructions
+}
+
+fn count_instructions(module: &Module) -> usize {
+    instructions(module).len()
+}
+
+/// Delete instructions, replacing uses of their results with `undef`
+fn remove_instructions(module: &mut Module, range: Range<usize>) {
+    let mut by_func: HashMap<usize, HashSet<(usize, usize)>> = HashMap::new();
+    for (func, block, inst) in in_range(instructions(module), range) {
+        by_func.entry(func).or_default().insert((block, inst));
+    }
+    let types = &module.types;
+    for (func, removed) in by_func {
+        let func = &mut module.functions[func];
+        let mut undefined = vec![];
+        for (b, bb) in func.basic_blocks.iter_mut().enumerate() {
+            let mut i = 0;
+            bb.instrs.retain(|inst| {
+                let keep = !removed.contains(&(b, i));
+                if !keep {
+                    if let Some(name) = inst.try_get_result() {
+                        undefined.push((name.clone(), types.type_of(inst)));
+                    }
+                }
+                i += 1;
+                keep
+            });
+        }
+        undefine(func, undefined);
+    }
+}
+
+/// Parameters of defined functions, as (function index, parameter index)
+fn arguments(module: &Module) -> Vec<(usize, usize)> {
+    module
+        .functions
+        .iter()
+        .enumerate()
+        .flat_map(|(f, func)| (0 .. func.parameters.len()).map(move |p| (f, p)))
+        .collect()
+}
+
+fn count_arguments(module: &Module) -> usize {
+    arguments(module).len()
+}
+
+/// Delete function parameters, replacing their uses with `undef` and removing
+/// the corresponding arguments from direct calls
+fn remove_arguments(module: &mut Module, range: Range<usize>) {
+    let mut by_func: HashMap<usize, HashSet<usize>> = HashMap::new();
+    for (func, param) in in_range(arguments(module), range) {
+        by_func.entry(func).or_default().insert(param);
+    }
+    // function name -> (removed parameter indices, new function type)
+    let mut changed: HashMap<Name, (HashSet<usize>, TypeRef)> = HashMap::new();
+    for (func, removed) in by_func {
+        let func = &mut module.functions[func];
+        let mut undefined = vec![];
+        let mut i = 0;
+        func.parameters.retain(|param| {
+            let keep = !removed.contains(&i);
+            if !keep {
+                undefined.push((param.name.clone(), param.ty.clone()));
+            }
+            i += 1;
+            keep
+        });
+        undefine(func, undefined);
+        let new_ty = module.types.type_of(&*func);
+        changed.insert(func_name(&func.name), (removed, new_ty));
+    }
+
+    struct CallRewriter<'c> {
+        changed: &'c HashMap<Name, (HashSet<usize>, TypeRef)>,
+    }
+    impl CallRewriter<'_> {
+        /// If `callee` is one of the changed functions, remove the arguments
+        /// for its removed parameters and return its new type
+        fn rewrite_args<T>(&self, callee: &Operand, arguments: &mut Vec<T>) -> Option<TypeRef> {
+            let (removed, new_ty) = match callee {
+                Operand::ConstantOperand(constant) => match constant.as_ref() {
+                    Constant::GlobalReference { name, .. } => self.changed.get(name)?,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            let mut i = 0;
+            arguments.retain(|_| {
+                i += 1;
+                !removed.contains(&(i - 1))
+            });
+            Some(new_ty.clone())
+        }
+    }
+    impl VisitorMut for CallRewriter<'_> {
+        fn visit_constant(&mut self, constant: &mut ConstantRef) {
+            walk_mut::constant(self, constant);
+            if let Constant::GlobalReference { name, .. } = constant.as_ref() {
+                if let Some((_, new_ty)) = self.changed.get(name) {
+                    *constant = ConstantRef::new(Constant::GlobalReference {
+                        name: name.clone(),
+                        ty: new_ty.clone(),
+                    });
+                }
+            }
+        }
+
+        #[cfg_attr(feature = "llvm-14-or-lower", allow(unused_variables))]
+        fn visit_call(&mut self, call: &mut instruction::Call) {
+            if let Some(callee) = call.function.as_ref().right() {
+                if let Some(new_ty) = self.rewrite_args(callee, &mut call.arguments) {
+                    #[cfg(feature = "llvm-15-or-greater")]
+                    {
+                        call.function_ty = new_ty;
+                    }
+                }
+            }
+            walk_mut::call(self, call)
+        }
+
+        #[cfg_attr(feature = "llvm-14-or-lower", allow(unused_variables))]
+        fn visit_invoke(&mut self, invoke: &mut terminator::Invoke) {
+            if let Some(callee) = invoke.function.as_ref().right() {
+                if let Some(new_ty) = self.rewrite_args(callee, &mut invoke.arguments) {
+                    #[cfg(feature = "llvm-15-or-greater")]
+                    {
+                        invoke.function_ty = new_ty;
+                    }
+                }
+            }
+            walk_mut::invoke(self, invoke)
+        }
+
+        #[cfg_attr(feature = "llvm-14-or-lower", allow(unused_variables))]
+        fn visit_callbr(&mut self, callbr: &mut terminator::CallBr) {
+            if let Some(callee) = callbr.function.as_ref().right() {
+                if let Some(new_ty) = self.rewrite_args(callee, &mut callbr.arguments) {
+                    #[cfg(feature = "llvm-15-or-greater")]
+                    {
+                        callbr.function_ty = new_ty;
+                    }
+                }
+            }
+            walk_mut::callbr(self, callbr)
+        }
+    }
+    let mut rewriter = CallRewriter { changed: &changed };
+    rewriter.visit_module(module);
+}
+
+/// Can the operand be replaced by zero or `undef`? (Operands which already
+/// are simple constants can't; they would be counted forever.)
+fn is_simplifiable(operand: &Operand, types: &Types) -> bool {
+    let is_simple_constant = |constant: &ConstantRef| match constant.as_ref() {
+        Constant::Int { .. }
+        | Constant::Float(_)
+        | Constant::Null(_)
+        | Constant::AggregateZero(_)
+        | Constant::Undef(_)
+        | Constant::TokenNone => true,
+        #[cfg(feature = "llvm-12-or-greater")]
+        Constant::Poison(_) => true,
+        _ => false,
+    };
+    match operand {
+        Operand::LocalOperand { .. } => {},
+        Operand::ConstantOperand(constant) if !is_simple_constant(constant) => {},
+        _ => return false,
+    }
+    !matches!(
+        types.type_of(operand).as_ref(),
+        Type::VoidType
+            | Type::LabelType
+            | Type::MetadataType
+            | Type::TokenType
+            | Type::FuncType { .. }
+    )
+}
+
+/// Zero of the given type if it has one which is easy to build, else `undef`
+fn zero_or_undef(ty: TypeRef) -> Constant {
+    match ty.as_ref() {
+        Type::IntegerType { bits } => Constant::Int {
+            bits: *bits,
+            value: 0,
+        },
+        Type::PointerType { .. } => Constant::Null(ty),
+        Type::VectorType { .. } | Type::ArrayType { .. } | Type::StructType { .. } => {
+            Constant::AggregateZero(ty)
+        },
+        _ => Constant::Undef(ty),
+    }
+}
+
+/// Counts the simplifiable operands of instructions and terminators, in the
+/// order `OperandSimplifier` numbers them. Callees are left out.
+struct OperandCounter<'t> {
+    types: &'t Types,
+    count: usize,
+}
+
+impl Visitor for OperandCounter<'_> {
+    fn visit_operand(&mut self, operand: &Operand) {
+        if is_simplifiable(operand, self.types) {
+            self.count += 1;
+        }
+    }
+
+    fn visit_call(&mut self, call: &instruction::Call) {
+        call.arguments
+            .iter()
+            .for_each(|(arg, _)| self.visit_operand(arg));
+    }
+
+    fn visit_invoke(&mut self, invoke: &terminator::Invoke) {
+        invoke
+            .arguments
+            .iter()
+            .for_each(|(arg, _)| self.visit_operand(arg));
+    }
+
+    fn visit_callbr(&mut self, callbr: &terminator::CallBr) {
+        callbr
+            .arguments
+            .iter()
+            .for_each(|(arg, _)| self.visit_operand(arg));
+    }
+}
+
+/// Replaces the simplifiable operands whose numbers are in `range` by zero or
+/// `undef`
+struct OperandSimplifier<'t> {
+    types: &'t Types,
+    range: Range<usize>,
+    count: usize,
+}
+
+impl VisitorMut for OperandSimplifier<'_> {
+    fn visit_operand(&mut self, operand: &mut Operand) {
+        if !is_simplifiable(operand, self.types) {
+            return;
+        }
+        if self.range.contains(&self.count) {
+            let ty = self.types.type_of(&*operand);
+            *operand = Operand::ConstantOperand(ConstantRef::new(zero_or_undef(ty)));
+        }
+        self.count += 1;
+    }
+
+    fn visit_call(&mut self, call: &mut instruction::Call) {
+        call.arguments
+            .iter_mut()
+            .for_each(|(arg, _)| self.visit_operand(arg));
+    }
+
+    fn visit_invoke(&mut self, invoke: &mut terminator::Invoke) {
+        invoke
+            .arguments
+            .iter_mut()
+            .for_each(|(arg, _)| self.visit_operand(arg));
+    }
+
+    fn visit_callbr(&mut self, callbr: &mut terminator::CallBr) {
+        callbr
+            .arguments
+            .iter_mut()
+            .for_each(|(arg, _)| self.visit_operand(arg));
+    }
+}
+
+fn count_operands(module: &Module) -> usize {
+    let mut counter = OperandCounter {
+        types: &module.types,
+        count: 0,
+    };
+    module
+        .functions
+        .iter()
+        .for_each(|f| counter.visit_function(f));
+    counter.count
+}
+
+/// Replace operands with zero or `undef`
+fn simplify_operands(module: &mut Module, range: Range<usize>) {
+    let Module {
+        types, functions, ..
+    } = module;
+    let mut simplifier = OperandSimplifier {
+        types,
+        range,
+        count: 0,
+    };
+    functions
+        .iter_mut()
+        .for_each(|f| simplifier.visit_function(f));
+}
@@ -1,5 +1,7 @@
 use crate::debugloc::{DebugLoc, HasDebugLoc};
 use crate::function::{CallingConvention, FunctionAttribute, ParameterAttribute};
+#[cfg(feature = "llvm-18-or-greater")]
+use crate::instruction::{fmt_operand_bundles, OperandBundle};
 use crate::instruction::{HasResult, InlineAssembly};
 use crate::types::{Typed, Types};
 use crate::{Constant, ConstantRef, Name, Operand, Type, TypeRef};
@@ -128,6 +130,77 @@ impl Terminator {
             Terminator::CallBr(t) => Some(&t.result),
         }
     }
+
+    /// Like [`try_get_result()`](#method.try_get_result), but with a mutable
+    /// reference, e.g. for renaming the result.
+    pub fn try_get_result_mut(&mut self) -> Option<&mut Name> {
+        match self {
+            Terminator::Ret(_) => None,
+            Terminator::Br(_) => None,
+            Terminator::CondBr(_) => None,
+            Terminator::Switch(_) => None,
+            Terminator::IndirectBr(_) => None,
+            Terminator::Invoke(t) => Some(&mut t.result),
+            Terminator::Resume(_) => None,
+            Terminator::Unreachable(_) => None,
+            Terminator::CleanupRet(_) => None,
+            Terminator::CatchRet(_) => None,
+            Terminator::CatchSwitch(t) => Some(&mut t.result),
+            Terminator::CallBr(t) => Some(&mut t.result),
+        }
+    }
+
+    /// Get the names of the basic blocks the `Terminator` may transfer control
+    /// to, in the order LLVM lists them (so, for instance, the default
+    /// destination of a `Switch` comes first). A block may appear more than
+    /// once, e.g. for a `Switch` with several cases going to the same block.
+    pub fn successors(&self) -> Vec<&Name> {
+        match self {
+            Terminator::Ret(_) => vec![],
+            Terminator::Br(t) => vec![&t.dest],
+            Terminator::CondBr(t) => vec![&t.true_dest, &t.false_dest],
+            Terminator::Switch(t) => std::iter::once(&t.default_dest)
+                .chain(t.dests.iter().map(|(_, dest)| dest))
+                .collect(),
+            Terminator::IndirectBr(t) => t.possible_dests.iter().collect(),
+            Terminator::Invoke(t) => vec![&t.return_label, &t.exception_label],
+            Terminator::Resume(_) => vec![],
+            Terminator::Unreachable(_) => vec![],
+            Terminator::CleanupRet(t) => t.unwind_dest.iter().collect(),
+            Terminator::CatchRet(t) => vec![&t.successor],
+            Terminator::CatchSwitch(t) => t
+                .catch_handlers
+                .iter()
+                .chain(t.default_unwind_dest.iter())
+                .collect(),
+            Terminator::CallBr(t) => vec![&t.return_label],
+        }
+    }
+
+    /// Like [`successors()`](#method.successors), but with mutable references,
+    /// e.g. for retargeting branches.
+    pub fn successors_mut(&mut self) -> Vec<&mut Name> {
+        match self {
+            Terminator::Ret(_) => vec![],
+            Terminator::Br(t) => vec![&mut t.dest],
+            Terminator::CondBr(t) => vec![&mut t.true_dest, &mut t.false_dest],
+            Terminator::Switch(t) => std::iter::once(&mut t.default_dest)
+                .chain(t.dests.iter_mut().map(|(_, dest)| dest))
+                .collect(),
+            Terminator::IndirectBr(t) => t.possible_dests.iter_mut().collect(),
+            Terminator::Invoke(t) => vec![&mut t.return_label, &mut t.exception_label],
+            Terminator::Resume(_) => vec![],
+            Terminator::Unreachable(_) => vec![],
+            Terminator::CleanupRet(t) => t.unwind_dest.iter_mut().collect(),
+            Terminator::CatchRet(t) => vec![&mut t.successor],
+            Terminator::CatchSwitch(t) => t
+                .catch_handlers
+                .iter_mut()
+                .chain(t.default_unwind_dest.iter_mut())
+                .collect(),
+            Terminator::CallBr(t) => vec![&mut t.return_label],
+        }
+    }
 }
 
 macro_rules! impl_term {
@@ -342,7 +415,7 @@ impl Display for IndirectBr {
 /// See [LLVM 14 docs on the 'invoke' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#invoke-instruction)
 #[derive(PartialEq, Clone, Debug, Hash)]
 pub struct Invoke {
-    pub function: Either<InlineAssembly, Operand>,
+    pub function: Either<Box<InlineAssembly>, Operand>,
     #[cfg(feature = "llvm-15-or-greater")]
     pub function_ty: TypeRef,
     pub arguments: Vec<(Operand, Vec<ParameterAttribute>)>,
@@ -352,6 +425,8 @@ pub struct Invoke {
     pub exception_label: Name, // Should be the name of a basic block. If the callee returns with 'resume' or another exception-handling mechanism, control flow resumes here.
     pub function_attributes: Vec<FunctionAttribute>, // llvm-hs has the equivalent of Vec<Either<GroupID, FunctionAttribute>>, but I'm not sure how the GroupID option comes up
     pub calling_convention: CallingConvention,
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub operand_bundles: Vec<OperandBundle>, // getters for these were added to the LLVM C API in LLVM 18
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -401,9 +476,12 @@ impl Display for Invoke {
                 write!(f, "{}, ", arg)?;
             }
         }
+        write!(f, ")")?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        fmt_operand_bundles(f, &self.operand_bundles)?;
         write!(
             f,
-            ") to label {} unwind label {}",
+            " to label {} unwind label {}",
             &self.return_label, &self.exception_label,
         )?;
         if self.debugloc.is_some() {
@@ -567,7 +645,9 @@ impl Display for CatchSwitch {
 /// See [LLVM 14 docs on the 'callbr' instruction](https://releases.llvm.org/14.0.0/docs/LangRef.html#callbr-instruction)
 #[derive(PartialEq, Clone, Debug, Hash)]
 pub struct CallBr {
-    pub function: Either<InlineAssembly, Operand>,
+    pub function: Either<Box<InlineAssembly>, Operand>,
+    #[cfg(feature = "llvm-15-or-greater")]
+    pub function_ty: TypeRef,
     pub arguments: Vec<(Operand, Vec<ParameterAttribute>)>,
     pub return_attributes: Vec<ParameterAttribute>,
     pub result: Name, // The name of the variable that will get the result of the call (if the callee returns with 'ret')
@@ -576,6 +656,8 @@ pub struct CallBr {
     pub other_labels: (), //Vec<Name>, // Should be names of basic blocks. The callee may use an inline-asm 'goto' to resume control flow at one of these places.
     pub function_attributes: Vec<FunctionAttribute>,
     pub calling_convention: CallingConvention,
+    #[cfg(feature = "llvm-18-or-greater")]
+    pub operand_bundles: Vec<OperandBundle>, // getters for these were added to the LLVM C API in LLVM 18
     pub debugloc: Option<DebugLoc>,
     // --TODO not yet implemented-- pub metadata: InstructionMetadata,
 }
@@ -583,14 +665,24 @@ pub struct CallBr {
 impl_term!(CallBr, CallBr);
 impl_hasresult!(CallBr);
 
+#[cfg(feature = "llvm-14-or-lower")]
 impl Typed for CallBr {
     fn get_type(&self, types: &Types) -> TypeRef {
         match types.type_of(&self.function).as_ref() {
+            Type::PointerType { pointee_type, .. } => match pointee_type.as_ref() {
+                Type::FuncType { result_type, .. } => result_type.clone(),
+                ty => panic!("Expected CallBr's function argument to be of type pointer-to-function, got pointer-to-{:?}", ty),
+            },
+            ty => panic!("Expected CallBr's function argument to be of type pointer-to-function, got {:?}", ty),
+        }
+    }
+}
+#[cfg(feature = "llvm-15-or-greater")]
+impl Typed for CallBr {
+    fn get_type(&self, _types: &Types) -> TypeRef {
+        match self.function_ty.as_ref() {
             Type::FuncType { result_type, .. } => result_type.clone(),
-            ty => panic!(
-                "Expected the function argument of a CallBr to have type FuncType; got {:?}",
-                ty
-            ),
+            ty => panic!("Expected CallBr.function_ty to be a FuncType, got {:?}", ty),
         }
     }
 }
@@ -616,7 +708,10 @@ impl Display for CallBr {
                 write!(f, "{}, ", arg)?;
             }
         }
-        write!(f, ") to label {}", &self.return_label)?;
+        write!(f, ")")?;
+        #[cfg(feature = "llvm-18-or-greater")]
+        fmt_operand_bundles(f, &self.operand_bundles)?;
+        write!(f, " to label {}", &self.return_label)?;
         if self.debugloc.is_some() {
             write!(f, " (with debugloc)")?;
         }
@@ -838,6 +933,8 @@ impl Invoke {
                 .clone(),
             function_attributes: callinfo.function_attributes,
             calling_convention: callinfo.calling_convention,
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles: callinfo.operand_bundles,
             debugloc: DebugLoc::from_llvm_with_col(term, &mut ctx.string_interner),
             // metadata: InstructionMetadata::from_llvm_inst(term),
         }
@@ -978,6 +1075,8 @@ impl CallBr {
         let callinfo = CallInfo::from_llvm_ref(term, ctx, func_ctx);
         Self {
             function: callinfo.function,
+            #[cfg(feature = "llvm-15-or-greater")]
+            function_ty: callinfo.function_ty,
             arguments: callinfo.arguments,
             return_attributes: callinfo.return_attributes,
             result: Name::name_or_num(unsafe { get_value_name(term) }, &mut func_ctx.ctr),
@@ -989,6 +1088,8 @@ impl CallBr {
             other_labels: (),
             function_attributes: callinfo.function_attributes,
             calling_convention: callinfo.calling_convention,
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles: callinfo.operand_bundles,
             debugloc: DebugLoc::from_llvm_with_col(term, &mut ctx.string_interner),
             // metadata: InstructionMetadata::from_llvm_inst(term),
         }
@@ -0,0 +1,555 @@
+use crate::module::{DataLayout, Endianness, Mangling};
+use std::fmt;
+
+/// A target triple, parsed into its components.
+/// See [LLVM 14 docs on Target Triple](https://releases.llvm.org/14.0.0/docs/LangRef.html#target-triple)
+///
+/// Triples have the general form `<arch><sub_arch>-<vendor>-<os>-<environment>`,
+/// where the environment may additionally end with an explicit object format
+/// (e.g., `-elf`). As in LLVM itself, components which we don't recognize are
+/// kept as `Other`/`Unknown`, rather than causing an error.
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct TargetTriple {
+    /// The triple in string form, as it appeared in the module
+    pub triple_str: String,
+    pub arch: Arch,
+    /// Sub-architecture, if any; e.g., `v7a` for `armv7a`, or `e` for `arm64e`
+    pub sub_arch: Option<String>,
+    pub vendor: Vendor,
+    pub os: OS,
+    pub environment: Option<Environment>,
+    /// The object format, either given explicitly at the end of the triple or
+    /// else the default for the arch and OS
+    pub object_format: ObjectFormat,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[allow(non_camel_case_types)]
+pub enum Arch {
+    AArch64,
+    AArch64_BE,
+    /// AArch64 with 32-bit pointers (`arm64_32`), as used by watchOS
+    AArch64_32,
+    AMDGCN,
+    Arm,
+    ArmEB,
+    AVR,
+    BPFEL,
+    BPFEB,
+    Hexagon,
+    LoongArch32,
+    LoongArch64,
+    Mips,
+    Mipsel,
+    Mips64,
+    Mips64el,
+    MSP430,
+    NVPTX,
+    NVPTX64,
+    PowerPC,
+    PowerPCLE,
+    PowerPC64,
+    PowerPC64LE,
+    RISCV32,
+    RISCV64,
+    Sparc,
+    SparcV9,
+    SystemZ,
+    Thumb,
+    ThumbEB,
+    Wasm32,
+    Wasm64,
+    X86,
+    X86_64,
+    Unknown(String),
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub enum Vendor {
+    AMD,
+    Apple,
+    IBM,
+    Mesa,
+    NVIDIA,
+    PC,
+    SCEI,
+    SUSE,
+    Unknown,
+    Other(String),
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub enum OS {
+    AIX,
+    AMDHSA,
+    CUDA,
+    Darwin,
+    Emscripten,
+    FreeBSD,
+    Fuchsia,
+    IOS,
+    Linux,
+    MacOSX,
+    NetBSD,
+    OpenBSD,
+    Solaris,
+    TvOS,
+    WASI,
+    WatchOS,
+    Windows,
+    ZOS,
+    /// `none`, i.e., bare metal
+    None,
+    Unknown,
+    Other(String),
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub enum Environment {
+    Android,
+    Cygnus,
+    EABI,
+    EABIHF,
+    GNU,
+    GNUABI64,
+    GNUABIN32,
+    GNUEABI,
+    GNUEABIHF,
+    GNUILP32,
+    GNUX32,
+    Itanium,
+    MacABI,
+    MSVC,
+    Musl,
+    MuslEABI,
+    MuslEABIHF,
+    Simulator,
+    Other(String),
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum ObjectFormat {
+    COFF,
+    ELF,
+    GOFF,
+    MachO,
+    Wasm,
+    XCOFF,
+}
+
+impl TargetTriple {
+    /// Parse a target triple such as `x86_64-unknown-linux-gnu`.
+    ///
+    /// As in LLVM's `Triple::normalize()`, components after the arch are
+    /// classified by their contents rather than only their position, so
+    /// triples with the vendor omitted (`x86_64-linux-gnu`) or with an
+    /// unrecognized vendor (`x86_64-w64-mingw32`) are also understood.
+    pub fn parse(triple: &str) -> Self {
+        let mut components = triple.splitn(4, '-');
+        let (arch, sub_arch) = Arch::parse(components.next().unwrap_or(""));
+        // vendor, OS and environment. Recognized components go in the slot for
+        // their kind; the others fill the remaining slots in order.
+        let mut slots: [Option<&str>; 3] = [None; 3];
+        let mut unrecognized = vec![];
+        for component in components {
+            let is_env = match Environment::parse(component) {
+                Some(Environment::Other(_)) => false,
+                Some(_) => true,
+                None => ObjectFormat::parse_explicit(component).is_some(),
+            };
+            let slot = if !matches!(Vendor::parse(component), Vendor::Other(_)) {
+                Some(0)
+            } else if !matches!(OS::parse(component), OS::Other(_)) {
+                Some(1)
+            } else if is_env {
+                Some(2)
+            } else {
+                None
+            };
+            match slot {
+                Some(slot) if slots[slot].is_none() => slots[slot] = Some(component),
+                _ => unrecognized.push(component),
+            }
+        }
+        for component in unrecognized {
+            if let Some(slot) = slots.iter_mut().find(|slot| slot.is_none()) {
+                *slot = Some(component);
+            }
+        }
+        let [vendor, os_str, env] = slots;
+        let os = os_str.map_or(OS::Unknown, OS::parse);
+        let object_format = env
+            .and_then(ObjectFormat::parse_explicit)
+            .unwrap_or_else(|| ObjectFormat::default_for(&arch, &os));
+        // the MinGW and Cygwin OS names imply their environments
+        let environment = env.and_then(Environment::parse).or_else(|| match os_str {
+            Some(os) if os.starts_with("mingw32") => Some(Environment::GNU),
+            Some(os) if os.starts_with("cygwin") => Some(Environment::Cygnus),
+            _ => None,
+        });
+        Self {
+            triple_str: triple.to_owned(),
+            arch,
+            sub_arch,
+            vendor: vendor.map_or(Vendor::Unknown, Vendor::parse),
+            os,
+            environment,
+            object_format,
+        }
+    }
+
+    /// Width of pointers (in address space 0), in bits, or `None` if unknown.
+    /// This is usually determined by the arch, but some environments use
+    /// 32-bit pointers on a 64-bit arch, e.g. `x86_64-linux-gnux32`.
+    pub fn pointer_width(&self) -> Option<u32> {
+        match (&self.arch, &self.environment) {
+            (Arch::X86_64, Some(Environment::GNUX32))
+            | (Arch::Mips64 | Arch::Mips64el, Some(Environment::GNUABIN32))
+            | (Arch::AArch64 | Arch::AArch64_BE, Some(Environment::GNUILP32)) => Some(32),
+            _ => self.arch.pointer_width(),
+        }
+    }
+
+    /// Is this a 64-bit target (i.e., are pointers 64 bits)
+    pub fn is_64bit(&self) -> bool {
+        self.pointer_width() == Some(64)
+    }
+
+    /// Is this a 32-bit target (i.e., are pointers 32 bits)
+    pub fn is_32bit(&self) -> bool {
+        self.pointer_width() == Some(32)
+    }
+
+    pub fn is_windows(&self) -> bool {
+        self.os == OS::Windows
+    }
+
+    pub fn is_linux(&self) -> bool {
+        self.os == OS::Linux
+    }
+
+    /// Is this any of Apple's operating systems (macOS, iOS, etc)
+    pub fn is_darwin(&self) -> bool {
+        matches!(
+            self.os,
+            OS::Darwin | OS::MacOSX | OS::IOS | OS::TvOS | OS::WatchOS
+        )
+    }
+
+    pub fn is_wasm(&self) -> bool {
+        matches!(self.arch, Arch::Wasm32 | Arch::Wasm64)
+    }
+
+    pub fn is_msvc(&self) -> bool {
+        self.environment == Some(Environment::MSVC)
+    }
+
+    /// Check that this triple agrees with the given `DataLayout` where the
+    /// two overlap: pointer size (in address space 0), endianness, and symbol
+    /// mangling. Anything unknown on either side is not checked, and neither
+    /// is an empty `DataLayout`.
+    ///
+    /// Returns a description of the first disagreement found, if any.
+    pub fn check_data_layout(&self, data_layout: &DataLayout) -> Result<(), String> {
+        if data_layout.layout_str.is_empty() {
+            return Ok(());
+        }
+        if let Some(endianness) = self.arch.endianness() {
+            if endianness != data_layout.endianness {
+                return Err(format!(
+                    "triple {:?} is {:?}, but the data layout is {:?}",
+                    self.triple_str, endianness, data_layout.endianness
+                ));
+            }
+        }
+        if let Some(width) = self.pointer_width() {
+            let layout_width = data_layout.alignments.ptr_alignment(0).size;
+            if width != layout_width {
+                return Err(format!(
+                    "triple {:?} has {}-bit pointers, but the data layout has {}-bit pointers",
+                    self.triple_str, width, layout_width
+                ));
+            }
+        }
+        if let Some(mangling) = data_layout.mangling {
+            let consistent = match self.object_format {
+                ObjectFormat::ELF => matches!(mangling, Mangling::ELF | Mangling::MIPS),
+                ObjectFormat::MachO => mangling == Mangling::MachO,
+                ObjectFormat::COFF => {
+                    matches!(mangling, Mangling::WindowsCOFF | Mangling::WindowsX86COFF)
+                },
+                #[cfg(feature = "llvm-11-or-greater")]
+                ObjectFormat::XCOFF => mangling == Mangling::XCOFF,
+                _ => true,
+            };
+            if !consistent {
+                return Err(format!(
+                    "triple {:?} uses {:?} objects, but the data layout specifies {:?} mangling",
+                    self.triple_str, self.object_format, mangling
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TargetTriple {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.triple_str)
+    }
+}
+
+impl Arch {
+    /// Parse the arch component of a triple, also returning the
+    /// sub-architecture, if any
+    fn parse(arch: &str) -> (Self, Option<String>) {
+        let parsed = match arch {
+            "aarch64" | "arm64" => Arch::AArch64,
+            "aarch64_be" => Arch::AArch64_BE,
+            // checked before the `arm64`/`aarch64` prefixes below would take
+            // `_32` as a sub-architecture
+            "aarch64_32" | "arm64_32" => Arch::AArch64_32,
+            "amdgcn" => Arch::AMDGCN,
+            "avr" => Arch::AVR,
+            "bpf" | "bpfel" => Arch::BPFEL,
+            "bpfeb" => Arch::BPFEB,
+            "hexagon" => Arch::Hexagon,
+            "i386" | "i486" | "i586" | "i686" | "x86" => Arch::X86,
+            "loongarch32" => Arch::LoongArch32,
+            "loongarch64" => Arch::LoongArch64,
+            "mips" | "mipseb" => Arch::Mips,
+            "mipsel" => Arch::Mipsel,
+            "mips64" | "mips64eb" => Arch::Mips64,
+            "mips64el" => Arch::Mips64el,
+            "msp430" => Arch::MSP430,
+            "nvptx" => Arch::NVPTX,
+            "nvptx64" => Arch::NVPTX64,
+            "powerpc" | "ppc" | "ppc32" => Arch::PowerPC,
+            "powerpcle" | "ppcle" | "ppc32le" => Arch::PowerPCLE,
+            "powerpc64" | "ppc64" => Arch::PowerPC64,
+            "powerpc64le" | "ppc64le" => Arch::PowerPC64LE,
+            "riscv32" => Arch::RISCV32,
+            "riscv64" => Arch::RISCV64,
+            "sparc" => Arch::Sparc,
+            "sparcv9" | "sparc64" => Arch::SparcV9,
+            "s390x" | "systemz" => Arch::SystemZ,
+            "wasm32" => Arch::Wasm32,
+            "wasm64" => Arch::Wasm64,
+            "x86_64" | "amd64" | "x86_64h" => Arch::X86_64,
+            _ => {
+                // Arm-family arches carry their sub-architecture as a suffix
+                let families: [(&str, Arch); 6] = [
+                    ("armeb", Arch::ArmEB),
+                    ("arm64", Arch::AArch64),
+                    ("arm", Arch::Arm),
+                    ("thumbeb", Arch::ThumbEB),
+                    ("thumb", Arch::Thumb),
+                    ("aarch64", Arch::AArch64),
+                ];
+                for (prefix, family) in families {
+                    if let Some(sub_arch) = arch.strip_prefix(prefix) {
+                        let sub_arch = if sub_arch.is_empty() {
+                            None
+                        } else {
+                            Some(sub_arch.to_owned())
+                        };
+                        return (family, sub_arch);
+                    }
+                }
+                Arch::Unknown(arch.to_owned())
+            },
+        };
+        (parsed, None)
+    }
+
+    /// Pointer width for this arch, in bits, or `None` if unknown. Some
+    /// environments override this; see
+    /// [`TargetTriple::pointer_width()`](struct.TargetTriple.html#method.pointer_width).
+    pub fn pointer_width(&self) -> Option<u32> {
+        match self {
+            Arch::AVR | Arch::MSP430 => Some(16),
+            Arch::AArch64_32
+            | Arch::Arm
+            | Arch::ArmEB
+            | Arch::Hexagon
+            | Arch::LoongArch32
+            | Arch::Mips
+            | Arch::Mipsel
+            | Arch::NVPTX
+            | Arch::PowerPC
+            | Arch::PowerPCLE
+            | Arch::RISCV32
+            | Arch::Sparc
+            | Arch::Thumb
+            | Arch::ThumbEB
+            | Arch::Wasm32
+            | Arch::X86 => Some(32),
+            Arch::AArch64
+            | Arch::AArch64_BE
+            | Arch::AMDGCN
+            | Arch::BPFEL
+            | Arch::BPFEB
+            | Arch::LoongArch64
+            | Arch::Mips64
+            | Arch::Mips64el
+            | Arch::NVPTX64
+            | Arch::PowerPC64
+            | Arch::PowerPC64LE
+            | Arch::RISCV64
+            | Arch::SparcV9
+            | Arch::SystemZ
+            | Arch::Wasm64
+            | Arch::X86_64 => Some(64),
+            Arch::Unknown(_) => None,
+        }
+    }
+
+    /// Endianness of this arch, or `None` if unknown
+    pub fn endianness(&self) -> Option<Endianness> {
+        match self {
+            Arch::AArch64_BE
+            | Arch::ArmEB
+            | Arch::BPFEB
+            | Arch::Mips
+            | Arch::Mips64
+            | Arch::PowerPC
+            | Arch::PowerPC64
+            | Arch::Sparc
+            | Arch::SparcV9
+            | Arch::SystemZ
+            | Arch::ThumbEB => Some(Endianness::BigEndian),
+            Arch::Unknown(_) => None,
+            _ => Some(Endianness::LittleEndian),
+        }
+    }
+}
+
+impl Vendor {
+    fn parse(vendor: &str) -> Self {
+        match vendor {
+            "amd" => Vendor::AMD,
+            "apple" => Vendor::Apple,
+            "ibm" => Vendor::IBM,
+            "mesa" => Vendor::Mesa,
+            "nvidia" => Vendor::NVIDIA,
+            "pc" => Vendor::PC,
+            "scei" => Vendor::SCEI,
+            "suse" => Vendor::SUSE,
+            "unknown" | "" => Vendor::Unknown,
+            _ => Vendor::Other(vendor.to_owned()),
+        }
+    }
+}
+
+impl OS {
+    /// OS components may carry a version suffix, e.g. `macosx10.15.0`, which
+    /// we ignore
+    fn parse(os: &str) -> Self {
+        let prefixes: [(&str, OS); 22] = [
+            ("aix", OS::AIX),
+            ("amdhsa", OS::AMDHSA),
+            ("cuda", OS::CUDA),
+            ("darwin", OS::Darwin),
+            ("emscripten", OS::Emscripten),
+            ("freebsd", OS::FreeBSD),
+            ("fuchsia", OS::Fuchsia),
+            ("ios", OS::IOS),
+            ("linux", OS::Linux),
+            ("macosx", OS::MacOSX),
+            ("macos", OS::MacOSX),
+            ("netbsd", OS::NetBSD),
+            ("openbsd", OS::OpenBSD),
+            ("solaris", OS::Solaris),
+            ("tvos", OS::TvOS),
+            ("wasi", OS::WASI),
+            ("watchos", OS::WatchOS),
+            ("windows", OS::Windows),
+            ("win32", OS::Windows),
+            ("mingw32", OS::Windows),
+            ("cygwin", OS::Windows),
+            ("zos", OS::ZOS),
+        ];
+        match os {
+            "none" => OS::None,
+            "unknown" | "" => OS::Unknown,
+            _ => prefixes
+                .iter()
+                .find(|(prefix, _)| os.starts_with(prefix))
+                .map(|(_, os)| os.clone())
+                .unwrap_or_else(|| OS::Other(os.to_owned())),
+        }
+    }
+}
+
+impl Environment {
+    /// Environment components may carry a version suffix (e.g., `android24`)
+    /// and/or an explicit object format (e.g., `gnu-elf`), which we ignore.
+    /// Returns `None` if the component is empty or `unknown`, or consists of
+    /// only an object format.
+    fn parse(env: &str) -> Option<Self> {
+        let env = env.split('-').next().unwrap_or("");
+        // longer prefixes first, so that e.g. `gnueabihf` isn't parsed as `gnu`
+        let prefixes: [(&str, Environment); 18] = [
+            ("android", Environment::Android),
+            ("cygnus", Environment::Cygnus),
+            ("eabihf", Environment::EABIHF),
+            ("eabi", Environment::EABI),
+            ("gnuabi64", Environment::GNUABI64),
+            ("gnuabin32", Environment::GNUABIN32),
+            ("gnueabihf", Environment::GNUEABIHF),
+            ("gnueabi", Environment::GNUEABI),
+            ("gnu_ilp32", Environment::GNUILP32),
+            ("gnux32", Environment::GNUX32),
+            ("gnu", Environment::GNU),
+            ("itanium", Environment::Itanium),
+            ("macabi", Environment::MacABI),
+            ("msvc", Environment::MSVC),
+            ("musleabihf", Environment::MuslEABIHF),
+            ("musleabi", Environment::MuslEABI),
+            ("musl", Environment::Musl),
+            ("simulator", Environment::Simulator),
+        ];
+        match env {
+            "" | "unknown" => None,
+            _ if ObjectFormat::parse_explicit(env).is_some() => None,
+            _ => Some(
+                prefixes
+                    .iter()
+                    .find(|(prefix, _)| env.starts_with(prefix))
+                    .map(|(_, env)| env.clone())
+                    .unwrap_or_else(|| Environment::Other(env.to_owned())),
+            ),
+        }
+    }
+}
+
+impl ObjectFormat {
+    /// Parse an object format given explicitly at the end of the environment
+    /// component, e.g. the `elf` in `x86_64-pc-windows-msvc-elf`
+    fn parse_explicit(env: &str) -> Option<Self> {
+        let formats: [(&str, ObjectFormat); 6] = [
+            ("xcoff", ObjectFormat::XCOFF),
+            ("coff", ObjectFormat::COFF),
+            ("elf", ObjectFormat::ELF),
+            ("goff", ObjectFormat::GOFF),
+            ("macho", ObjectFormat::MachO),
+            ("wasm", ObjectFormat::Wasm),
+        ];
+        formats
+            .iter()
+            .find(|(suffix, _)| env.ends_with(suffix))
+            .map(|(_, format)| *format)
+    }
+
+    /// The object format LLVM uses by default for the given arch and OS
+    fn default_for(arch: &Arch, os: &OS) -> Self {
+        match (arch, os) {
+            (Arch::Wasm32 | Arch::Wasm64, _) => ObjectFormat::Wasm,
+            (_, OS::AIX) => ObjectFormat::XCOFF,
+            (_, OS::ZOS) => ObjectFormat::GOFF,
+            (_, OS::Darwin | OS::MacOSX | OS::IOS | OS::TvOS | OS::WatchOS) => ObjectFormat::MachO,
+            (_, OS::Windows) => ObjectFormat::COFF,
+            _ => ObjectFormat::ELF,
+        }
+    }
+}
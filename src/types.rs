@@ -76,12 +76,15 @@ pub enum Type {
     /// See [LLVM 14 docs on Token Type](https://releases.llvm.org/14.0.0/docs/LangRef.html#token-type)
     TokenType,
     /// See [LLVM 16 docs on Target Extension Type](https://releases.llvm.org/16.0.0/docs/LangRef.html#target-extension-type).
-    ///
-    /// `TargetExtType` needs more fields, but the necessary getter functions
-    /// are apparently not exposed in the LLVM C API (only the C++ API).
-    /// See discussion in #39.
     #[cfg(feature = "llvm-16-or-greater")]
-    TargetExtType, // TODO ideally we want something like TargetExtType { name: String, contained_types: Vec<TypeRef>, contained_ints: Vec<u32> }
+    TargetExtType {
+        /// e.g., `"spirv.Image"` or `"aarch64.svcount"`
+        name: String,
+        /// The type parameters, in order
+        contained_types: Vec<TypeRef>,
+        /// The integer parameters, in order
+        contained_ints: Vec<u32>,
+    },
 }
 
 impl Display for Type {
@@ -161,9 +164,11 @@ impl Display for Type {
             Type::LabelType => write!(f, "label"),
             Type::TokenType => write!(f, "token"),
             #[cfg(feature = "llvm-16-or-greater")]
-            Type::TargetExtType => write!(f, "target()"),
-                // someday if/when TargetExtType contains other fields, we need something like the below:
-                /*
+            Type::TargetExtType {
+                name,
+                contained_types,
+                contained_ints,
+            } => {
                 // Name, then type parameters first then integer parameters.
                 let members = [name]
                     .iter()
@@ -172,10 +177,8 @@ impl Display for Type {
                     .chain(contained_ints.iter().map(ToString::to_string))
                     .collect::<Vec<_>>()
                     .join(", ");
-                write!(f, "target({members})")?;
-
-                Ok(())
-                */
+                write!(f, "target({members})")
+            },
         }
     }
 }
@@ -277,6 +280,12 @@ impl Typed for FPType {
     }
 }
 
+impl<T: Typed + ?Sized> Typed for Box<T> {
+    fn get_type(&self, types: &Types) -> TypeRef {
+        types.type_of(self.as_ref())
+    }
+}
+
 impl<A, B> Typed for Either<A, B>
 where
     A: Typed,
@@ -329,10 +338,9 @@ pub(crate) struct TypesBuilder {
     label_type: TypeRef,
     /// `TypeRef` to `Type::TokenType`
     token_type: TypeRef,
-    /// `TypeRef` to `Type::TargetExtType`
-    // someday: Map of `(name, contained_types, contained_ints)` to the corresponding `Type::TargetExtType`. See notes on Type::TargetExtType
+    /// Map of `(name, contained_types, contained_ints)` to the corresponding `Type::TargetExtType`
     #[cfg(feature = "llvm-16-or-greater")]
-    target_ext_type: TypeRef,
+    target_ext_types: TypeCache<(String, Vec<TypeRef>, Vec<u32>)>,
     /// internal cache of already-seen `LLVMTypeRef`s so we can quickly produce
     /// the corresponding `TypeRef` without re-parsing the type
     llvm_type_map: HashMap<LLVMTypeRef, TypeRef>,
@@ -358,7 +366,7 @@ impl TypesBuilder {
             label_type: TypeRef::new(Type::LabelType),
             token_type: TypeRef::new(Type::TokenType),
             #[cfg(feature = "llvm-16-or-greater")]
-            target_ext_type: TypeRef::new(Type::TargetExtType),
+            target_ext_types: TypeCache::new(),
             llvm_type_map: HashMap::new(),
         }
     }
@@ -385,7 +393,7 @@ impl TypesBuilder {
             label_type: self.label_type,
             token_type: self.token_type,
             #[cfg(feature = "llvm-16-or-greater")]
-            target_ext_type: self.target_ext_type,
+            target_ext_types: self.target_ext_types,
         }
     }
 }
@@ -601,15 +609,23 @@ impl TypesBuilder {
         self.token_type.clone()
     }
 
-    /// Get the target extension type
+    /// Get the target extension type with the given name, contained types,
+    /// and contained ints
     #[cfg(feature = "llvm-16-or-greater")]
     pub fn target_ext_type(
         &mut self,
-        // name: String, // TODO not exposed in the LLVM C API; see notes on Type::TargetExtType
-        // contained_types: Vec<TypeRef>, // TODO not exposed in the LLVM C API; see notes on Type::TargetExtType
-        // contained_ints: Vec<u32>, // TODO not exposed in the LLVM C API; see notes on Type::TargetExtType
+        name: String,
+        contained_types: Vec<TypeRef>,
+        contained_ints: Vec<u32>,
     ) -> TypeRef {
-        self.target_ext_type.clone()
+        self.target_ext_types.lookup_or_insert(
+            (name.clone(), contained_types.clone(), contained_ints.clone()),
+            || Type::TargetExtType {
+                name,
+                contained_types,
+                contained_ints,
+            },
+        )
     }
 }
 
@@ -672,9 +688,9 @@ pub struct Types {
     label_type: TypeRef,
     /// `TypeRef` to `Type::TokenType`
     token_type: TypeRef,
-    /// `TypeRef` to `Type::TargetExtType`
+    /// Map of `(name, contained_types, contained_ints)` to the corresponding `Type::TargetExtType`
     #[cfg(feature = "llvm-16-or-greater")]
-    target_ext_type: TypeRef,
+    target_ext_types: TypeCache<(String, Vec<TypeRef>, Vec<u32>)>,
 }
 
 impl Types {
@@ -913,11 +929,19 @@ impl Types {
     #[cfg(feature = "llvm-16-or-greater")]
     pub fn target_ext_type(
         &self,
-        // name: String, // TODO not exposed in the LLVM C API; see notes on Type::TargetExtType
-        // contained_types: Vec<TypeRef>, // TODO not exposed in the LLVM C API; see notes on Type::TargetExtType
-        // contained_ints: Vec<u32>, // TODO not exposed in the LLVM C API; see notes on Type::TargetExtType
+        name: String,
+        contained_types: Vec<TypeRef>,
+        contained_ints: Vec<u32>,
     ) -> TypeRef {
-        self.target_ext_type.clone()
+        self.target_ext_types
+            .lookup(&(name.clone(), contained_types.clone(), contained_ints.clone()))
+            .unwrap_or_else(|| {
+                TypeRef::new(Type::TargetExtType {
+                    name,
+                    contained_types,
+                    contained_ints,
+                })
+            })
     }
 
     /// Get a `TypeRef` for the given `Type`
@@ -960,7 +984,9 @@ impl Types {
             Type::LabelType => self.label_type(),
             Type::TokenType => self.token_type(),
             #[cfg(feature="llvm-16-or-greater")]
-            Type::TargetExtType => self.target_ext_type(),
+            Type::TargetExtType { name, contained_types, contained_ints } => {
+                self.target_ext_type(name.clone(), contained_types.clone(), contained_ints.clone())
+            },
         }
     }
 }
@@ -1149,7 +1175,22 @@ impl TypesBuilder {
             LLVMTypeKind::LLVMLabelTypeKind => self.label_type(),
             LLVMTypeKind::LLVMTokenTypeKind => self.token_type(),
             #[cfg(feature = "llvm-16-or-greater")]
-            LLVMTypeKind::LLVMTargetExtTypeKind => self.target_ext_type(),
+            LLVMTypeKind::LLVMTargetExtTypeKind => {
+                let contained_types = {
+                    let num_types = unsafe { LLVMGetNumContainedTypes(ty) };
+                    let mut types: Vec<LLVMTypeRef> = Vec::with_capacity(num_types as usize);
+                    unsafe {
+                        LLVMGetSubtypes(ty, types.as_mut_ptr());
+                        types.set_len(num_types as usize);
+                    };
+                    types
+                        .into_iter()
+                        .map(|t| self.type_from_llvm_ref(t))
+                        .collect()
+                };
+                let (name, contained_ints) = target_ext_name_and_ints(ty);
+                self.target_ext_type(name, contained_types, contained_ints)
+            },
         }
     }
 
@@ -1177,3 +1218,44 @@ impl TypesBuilder {
         self.struct_of(element_types, unsafe { LLVMIsPackedStruct(ty) } != 0)
     }
 }
+
+/// The LLVM C API (at least through LLVM 19) has no getters for the name or
+/// the integer parameters of a target extension type, so we recover those
+/// from its printed form, e.g. `target("spirv.Image", void, 1, 0)`.
+/// (The type parameters are available via `LLVMGetSubtypes()`.)
+#[cfg(feature = "llvm-16-or-greater")]
+fn target_ext_name_and_ints(ty: LLVMTypeRef) -> (String, Vec<u32>) {
+    let printed = unsafe { print_type_to_string(ty) };
+    let (escaped_name, params) = printed
+        .strip_prefix("target(\"")
+        .and_then(|s| s.strip_suffix(')'))
+        .and_then(|s| s.split_once('"'))
+        .unwrap_or_else(|| panic!("Unexpected format for target extension type: {:?}", printed));
+
+    // LLVM prints `"`, `\`, and nonprintable characters in the name as `\XX`
+    let mut name = Vec::with_capacity(escaped_name.len());
+    let mut bytes = escaped_name.bytes();
+    while let Some(b) = bytes.next() {
+        if b == b'\\' {
+            let hex: Vec<u8> = bytes.by_ref().take(2).collect();
+            let escaped = std::str::from_utf8(&hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .unwrap_or_else(|| panic!("Bad escape in target extension type name: {:?}", printed));
+            name.push(escaped);
+        } else {
+            name.push(b);
+        }
+    }
+
+    // The type parameters come first, then the integer parameters; no type
+    // prints as a bare integer, so the integer parameters are exactly the
+    // trailing parameters which parse as integers
+    let mut ints: Vec<u32> = params
+        .rsplit(", ")
+        .map_while(|param| param.parse().ok())
+        .collect();
+    ints.reverse();
+
+    (String::from_utf8_lossy(&name).into_owned(), ints)
+}
@@ -0,0 +1,894 @@
+//! Well-formedness checks on a `Module`; see
+//! [`Module::verify()`](../module/struct.Module.html#method.verify).
+
+use crate::builder::{
+    is_addr_space_cast, is_bitcastable, is_fp, is_fp_narrowing, is_fp_to_int, is_fp_widening,
+    is_int, is_int_to_fp, is_int_to_ptr, is_narrowing, is_ptr_to_int, is_widening, scalar_type,
+};
+use crate::cfg::{ControlFlowGraph, DominatorTree};
+use crate::constant::Constant;
+use crate::function::Function;
+use crate::instruction::{InlineAssembly, Instruction};
+use crate::module::Module;
+use crate::name::Name;
+use crate::operand::Operand;
+use crate::terminator::Terminator;
+use crate::types::{NamedStructDef, Type, TypeRef, Types};
+use crate::visit::Visitor;
+use either::Either;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+/// A problem found by [`Module::verify()`](../module/struct.Module.html#method.verify)
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+pub struct VerifierError {
+    /// Name of the function at fault
+    pub function: String,
+    /// Name of the basic block at fault, or `None` if the problem isn't within
+    /// a particular block (e.g., duplicate parameter names)
+    pub block: Option<Name>,
+    /// The instruction or terminator at fault, as printed by its `Display`
+    /// impl, or `None` if the problem isn't with a particular instruction
+    pub instruction: Option<String>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "in function {:?}", self.function)?;
+        if let Some(block) = &self.block {
+            write!(f, ", block {}", block)?;
+        }
+        if let Some(instruction) = &self.instruction {
+            write!(f, ", at `{}`", instruction)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for VerifierError {}
+
+pub(crate) fn verify_module(module: &Module) -> Result<(), Vec<VerifierError>> {
+    let errors: Vec<VerifierError> = module
+        .functions
+        .iter()
+        .flat_map(|func| FunctionVerifier::new(module, func).verify())
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Where a local value is defined
+#[derive(Clone, Copy)]
+enum DefSite {
+    Parameter,
+    /// Defined in the block with the given index, by the instruction with the
+    /// given index (or by the terminator, if the index is the number of
+    /// instructions in the block)
+    Block(usize, usize),
+    /// Defined by the `invoke` terminating the block with the first index,
+    /// and only available along its edge to its normal destination, the
+    /// block with the second index
+    Invoke(usize, usize),
+}
+
+struct Def {
+    site: DefSite,
+    /// `None` if we couldn't determine the type, because the defining
+    /// instruction is itself ill-formed
+    ty: Option<TypeRef>,
+}
+
+/// An index into an aggregate type
+enum Index {
+    Constant(u64),
+    /// Not a compile-time constant
+    Dynamic,
+}
+
+impl From<&Operand> for Index {
+    fn from(op: &Operand) -> Self {
+        match op {
+            Operand::ConstantOperand(c) => match c.as_ref() {
+                Constant::Int { value, .. } => Index::Constant(*value),
+                _ => Index::Dynamic,
+            },
+            _ => Index::Dynamic,
+        }
+    }
+}
+
+/// Collects the local values used by an instruction or terminator
+#[derive(Default)]
+struct LocalUses(Vec<(Name, TypeRef)>);
+
+impl Visitor for LocalUses {
+    fn visit_operand(&mut self, operand: &Operand) {
+        // constants can't refer to local values, so no need to walk into them
+        if let Operand::LocalOperand { name, ty } = operand {
+            self.0.push((name.clone(), ty.clone()));
+        }
+    }
+}
+
+struct FunctionVerifier<'m> {
+    module: &'m Module,
+    types: &'m Types,
+    func: &'m Function,
+    cfg: ControlFlowGraph,
+    domtree: DominatorTree,
+    defs: HashMap<&'m Name, Def>,
+    errors: Vec<VerifierError>,
+}
+
+impl<'m> FunctionVerifier<'m> {
+    fn new(module: &'m Module, func: &'m Function) -> Self {
+        let cfg = ControlFlowGraph::new(func);
+        let domtree = DominatorTree::new(&cfg);
+        Self {
+            module,
+            types: &module.types,
+            func,
+            cfg,
+            domtree,
+            defs: HashMap::new(),
+            errors: vec![],
+        }
+    }
+
+    fn error(&mut self, block: Option<usize>, instruction: Option<String>, message: String) {
+        self.errors.push(VerifierError {
+            function: self.func.name.clone(),
+            block: block.map(|b| self.func.basic_blocks[b].name.clone()),
+            instruction,
+            message,
+        });
+    }
+
+    fn verify(mut self) -> Vec<VerifierError> {
+        if self.func.basic_blocks.is_empty() {
+            self.error(None, None, "function has no basic blocks".into());
+            return self.errors;
+        }
+        self.collect_defs();
+        if !self.cfg.preds(0).is_empty() {
+            self.error(
+                Some(0),
+                None,
+                "the entry block must not have predecessors".into(),
+            );
+        }
+        let func = self.func;
+        for (b, bb) in func.basic_blocks.iter().enumerate() {
+            let mut seen_non_phi = false;
+            for (i, inst) in bb.instrs.iter().enumerate() {
+                match inst {
+                    Instruction::Phi(phi) => {
+                        if seen_non_phi {
+                            self.error(
+                                Some(b),
+                                Some(inst.to_string()),
+                                "phi is not at the start of its block".into(),
+                            );
+                        }
+                        self.check_phi_uses(b, &phi.incoming_values, inst);
+                    },
+                    _ => {
+                        seen_non_phi = true;
+                        let mut uses = LocalUses::default();
+                        uses.visit_instruction(inst);
+                        for (name, ty) in &uses.0 {
+                            self.check_use(b, i, None, name, ty, || inst.to_string());
+                        }
+                    },
+                }
+            }
+            let mut uses = LocalUses::default();
+            uses.visit_terminator(&bb.term);
+            for (name, ty) in &uses.0 {
+                self.check_use(b, bb.instrs.len(), None, name, ty, || bb.term.to_string());
+            }
+            for succ in bb.term.successors() {
+                if self.cfg.index_of(succ).is_none() {
+                    self.error(
+                        Some(b),
+                        Some(bb.term.to_string()),
+                        format!("branch target {} is not a block of the function", succ),
+                    );
+                }
+            }
+        }
+        self.errors
+    }
+
+    /// Record where each local value is defined and its type, reporting
+    /// duplicate definitions and instructions with ill-typed operands
+    fn collect_defs(&mut self) {
+        let func = self.func;
+        let mut names: HashSet<&Name> = HashSet::new();
+        for param in &func.parameters {
+            if !names.insert(&param.name) {
+                self.error(
+                    None,
+                    None,
+                    format!("{} is defined more than once", param.name),
+                );
+            }
+            self.defs.insert(
+                &param.name,
+                Def {
+                    site: DefSite::Parameter,
+                    ty: Some(param.ty.clone()),
+                },
+            );
+        }
+        for (b, bb) in func.basic_blocks.iter().enumerate() {
+            if !names.insert(&bb.name) {
+                self.error(
+                    Some(b),
+                    None,
+                    format!("{} is defined more than once", bb.name),
+                );
+            }
+            for (i, inst) in bb.instrs.iter().enumerate() {
+                let messages = self.check_instruction(inst);
+                let ty = if messages.is_empty() {
+                    inst.try_get_result().map(|_| self.types.type_of(inst))
+                } else {
+                    None
+                };
+                for message in messages {
+                    self.error(Some(b), Some(inst.to_string()), message);
+                }
+                if let Some(name) = inst.try_get_result() {
+                    if !names.insert(name) {
+                        self.error(
+                            Some(b),
+                            Some(inst.to_string()),
+                            format!("{} is defined more than once", name),
+                        );
+                    }
+                    self.defs.insert(
+                        name,
+                        Def {
+                            site: DefSite::Block(b, i),
+                            ty,
+                        },
+                    );
+                }
+            }
+            let messages = self.check_terminator(&bb.term);
+            let ty = if messages.is_empty() {
+                self.terminator_result_type(&bb.term)
+            } else {
+                None
+            };
+            for message in messages {
+                self.error(Some(b), Some(bb.term.to_string()), message);
+            }
+            if let Some(name) = bb.term.try_get_result() {
+                if !names.insert(name) {
+                    self.error(
+                        Some(b),
+                        Some(bb.term.to_string()),
+                        format!("{} is defined more than once", name),
+                    );
+                }
+                let site = match &bb.term {
+                    Terminator::Invoke(t) => match self.cfg.index_of(&t.return_label) {
+                        Some(normal) => DefSite::Invoke(b, normal),
+                        None => DefSite::Block(b, bb.instrs.len()),
+                    },
+                    _ => DefSite::Block(b, bb.instrs.len()),
+                };
+                self.defs.insert(name, Def { site, ty });
+            }
+        }
+    }
+
+    /// Check a use of the local value `name` with type `ty`, in block `block`
+    /// at instruction index `index` (the number of instructions in the block,
+    /// for the terminator; one more than that for a use by a `Phi` in a
+    /// successor, in which case `phi_block` is the block of the `Phi`)
+    fn check_use(
+        &mut self,
+        block: usize,
+        index: usize,
+        phi_block: Option<usize>,
+        name: &Name,
+        ty: &TypeRef,
+        inst: impl Fn() -> String,
+    ) {
+        let def = match self.defs.get(name) {
+            Some(def) => def,
+            None => {
+                let message = if self.cfg.index_of(name).is_some() {
+                    format!("{} is a basic block, not a value", name)
+                } else {
+                    format!("{} is used but never defined", name)
+                };
+                self.error(Some(block), Some(inst()), message);
+                return;
+            },
+        };
+        let site = def.site;
+        if let Some(def_ty) = &def.ty {
+            if def_ty != ty {
+                let message = format!("{} is used as {} but defined as {}", name, ty, def_ty);
+                self.error(Some(block), Some(inst()), message);
+            }
+        }
+        // uses in unreachable blocks are exempt, as in LLVM
+        if !self.domtree.is_reachable(block) {
+            return;
+        }
+        let dominated = match site {
+            DefSite::Parameter => true,
+            DefSite::Block(def_block, def_index) if def_block == block => def_index < index,
+            DefSite::Block(def_block, _) => self.domtree.dominates(def_block, block),
+            DefSite::Invoke(def_block, normal) => {
+                (block == def_block && phi_block == Some(normal))
+                    || self.edge_dominates(def_block, normal, block)
+            },
+        };
+        if !dominated {
+            let message = format!("the definition of {} does not dominate this use", name);
+            self.error(Some(block), Some(inst()), message);
+        }
+    }
+
+    /// Does every path from the entry to `block` go through the edge from
+    /// `from` to `to`? This requires `to` to dominate `block`, and the edge to
+    /// be the only way into `to` other than from blocks `to` dominates.
+    fn edge_dominates(&self, from: usize, to: usize, block: usize) -> bool {
+        if !self.domtree.dominates(to, block) {
+            return false;
+        }
+        let mut edges_from = 0;
+        for &pred in self.cfg.preds(to) {
+            if pred == from {
+                edges_from += 1;
+            } else if !self.domtree.dominates(to, pred) {
+                return false;
+            }
+        }
+        edges_from == 1
+    }
+
+    /// Check the incoming values of a `Phi` in block `block`: that the
+    /// incoming blocks are exactly the predecessors, and that each value is
+    /// available at the end of its incoming block
+    fn check_phi_uses(&mut self, block: usize, incoming: &[(Operand, Name)], inst: &Instruction) {
+        let mut incoming_blocks: Vec<&Name> = incoming.iter().map(|(_, bb)| bb).collect();
+        let mut preds: Vec<&Name> = self
+            .cfg
+            .preds(block)
+            .iter()
+            .map(|&p| &self.func.basic_blocks[p].name)
+            .collect();
+        incoming_blocks.sort();
+        preds.sort();
+        if incoming_blocks != preds {
+            let list = |names: &[&Name]| {
+                names
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            let message = format!(
+                "phi incoming blocks [{}] don't match the block's predecessors [{}]",
+                list(&incoming_blocks),
+                list(&preds)
+            );
+            self.error(Some(block), Some(inst.to_string()), message);
+        }
+        for (value, from) in incoming {
+            if let Operand::LocalOperand { name, ty } = value {
+                // treat the use as happening at the very end of the incoming block
+                if let Some(from) = self.cfg.index_of(from) {
+                    let end = self.func.basic_blocks[from].instrs.len() + 1;
+                    self.check_use(from, end, Some(block), name, ty, || inst.to_string());
+                }
+            }
+        }
+    }
+
+    /// Check the operand types of an instruction; returns a description of
+    /// each problem found
+    fn check_instruction(&self, inst: &Instruction) -> Vec<String> {
+        let mut errs = vec![];
+        match inst {
+            Instruction::Add(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::Sub(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::Mul(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::UDiv(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::SDiv(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::URem(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::SRem(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::And(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::Or(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::Xor(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::Shl(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::LShr(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::AShr(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_int),
+            Instruction::FAdd(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_fp),
+            Instruction::FSub(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_fp),
+            Instruction::FMul(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_fp),
+            Instruction::FDiv(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_fp),
+            Instruction::FRem(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_fp),
+            Instruction::FNeg(i) => {
+                let ty = self.types.type_of(&i.operand);
+                if !is_fp(scalar_type(&ty)) {
+                    errs.push(format!("expected a floating-point operand, got {}", ty));
+                }
+            },
+            Instruction::ICmp(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, |ty| {
+                is_int(ty) || matches!(ty, Type::PointerType { .. })
+            }),
+            Instruction::FCmp(i) => self.check_binop(&mut errs, &i.operand0, &i.operand1, is_fp),
+            Instruction::ExtractElement(i) => {
+                let ty = self.types.type_of(&i.vector);
+                if !matches!(ty.as_ref(), Type::VectorType { .. }) {
+                    errs.push(format!("expected a vector operand, got {}", ty));
+                }
+            },
+            Instruction::InsertElement(i) => {
+                let ty = self.types.type_of(&i.vector);
+                match ty.as_ref() {
+                    Type::VectorType { element_type, .. } => {
+                        let elem_ty = self.types.type_of(&i.element);
+                        if &elem_ty != element_type {
+                            errs.push(format!(
+                                "expected an element of type {}, got {}",
+                                element_type, elem_ty
+                            ));
+                        }
+                    },
+                    _ => errs.push(format!("expected a vector operand, got {}", ty)),
+                }
+                let index_ty = self.types.type_of(&i.index);
+                if !is_int(&index_ty) {
+                    errs.push(format!("expected an integer index, got {}", index_ty));
+                }
+            },
+            Instruction::ShuffleVector(i) => {
+                let ty0 = self.types.type_of(&i.operand0);
+                let ty1 = self.types.type_of(&i.operand1);
+                if ty0 != ty1 {
+                    errs.push(format!(
+                        "operands have different types: {} and {}",
+                        ty0, ty1
+                    ));
+                }
+                let mask_ty = self.types.type_of(&i.mask);
+                if !matches!(ty0.as_ref(), Type::VectorType { .. })
+                    || !matches!(mask_ty.as_ref(), Type::VectorType { .. })
+                {
+                    errs.push(format!(
+                        "expected vector operands and mask, got {} and {}",
+                        ty0, mask_ty
+                    ));
+                }
+            },
+            Instruction::ExtractValue(i) => {
+                let ty = self.types.type_of(&i.aggregate);
+                let indices = i.indices.iter().map(|&i| Index::Constant(i.into()));
+                if let Err(e) = self.indexed_type(&ty, indices) {
+                    errs.push(e);
+                }
+            },
+            Instruction::InsertValue(i) => {
+                let ty = self.types.type_of(&i.aggregate);
+                let indices = i.indices.iter().map(|&i| Index::Constant(i.into()));
+                match self.indexed_type(&ty, indices) {
+                    Err(e) => errs.push(e),
+                    Ok(elem_ty) => {
+                        let ty = self.types.type_of(&i.element);
+                        if ty != elem_ty {
+                            errs.push(format!(
+                                "expected an element of type {}, got {}",
+                                elem_ty, ty
+                            ));
+                        }
+                    },
+                }
+            },
+            Instruction::Alloca(i) => {
+                let ty = self.types.type_of(&i.num_elements);
+                if !is_int(&ty) {
+                    errs.push(format!(
+                        "expected an integer number of elements, got {}",
+                        ty
+                    ));
+                }
+            },
+            Instruction::Load(i) => self.check_pointer(&mut errs, &i.address, None),
+            Instruction::Store(i) => {
+                let ty = self.types.type_of(&i.value);
+                self.check_pointer(&mut errs, &i.address, Some(&ty));
+            },
+            Instruction::CmpXchg(i) => {
+                let ty = self.types.type_of(&i.expected);
+                let replacement_ty = self.types.type_of(&i.replacement);
+                if ty != replacement_ty {
+                    errs.push(format!(
+                        "expected and replacement values have different types: {} and {}",
+                        ty, replacement_ty
+                    ));
+                }
+                self.check_pointer(&mut errs, &i.address, Some(&ty));
+            },
+            Instruction::AtomicRMW(i) => {
+                let ty = self.types.type_of(&i.value);
+                self.check_pointer(&mut errs, &i.address, Some(&ty));
+            },
+            Instruction::GetElementPtr(i) => self.check_gep(&mut errs, i),
+            Instruction::Trunc(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_narrowing)
+            },
+            Instruction::ZExt(i) => self.check_cast(&mut errs, &i.operand, &i.to_type, is_widening),
+            Instruction::SExt(i) => self.check_cast(&mut errs, &i.operand, &i.to_type, is_widening),
+            Instruction::PtrToInt(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_ptr_to_int)
+            },
+            Instruction::IntToPtr(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_int_to_ptr)
+            },
+            Instruction::FPTrunc(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_fp_narrowing)
+            },
+            Instruction::FPExt(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_fp_widening)
+            },
+            Instruction::FPToUI(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_fp_to_int)
+            },
+            Instruction::FPToSI(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_fp_to_int)
+            },
+            Instruction::UIToFP(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_int_to_fp)
+            },
+            Instruction::SIToFP(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_int_to_fp)
+            },
+            Instruction::BitCast(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_bitcastable)
+            },
+            Instruction::AddrSpaceCast(i) => {
+                self.check_cast(&mut errs, &i.operand, &i.to_type, is_addr_space_cast)
+            },
+            Instruction::Phi(i) => {
+                for (value, _) in &i.incoming_values {
+                    let ty = self.types.type_of(value);
+                    if ty != i.to_type {
+                        errs.push(format!(
+                            "expected incoming values of type {}, got {}",
+                            i.to_type, ty
+                        ));
+                    }
+                }
+            },
+            Instruction::Select(i) => {
+                let ty = self.types.type_of(&i.condition);
+                if *scalar_type(&ty) != (Type::IntegerType { bits: 1 }) {
+                    errs.push(format!("expected an i1 condition, got {}", ty));
+                }
+                let true_ty = self.types.type_of(&i.true_value);
+                let false_ty = self.types.type_of(&i.false_value);
+                if true_ty != false_ty {
+                    errs.push(format!(
+                        "values have different types: {} and {}",
+                        true_ty, false_ty
+                    ));
+                }
+            },
+            #[cfg(feature = "llvm-10-or-greater")]
+            Instruction::Freeze(i) => {
+                let ty = self.types.type_of(&i.operand);
+                if matches!(ty.as_ref(), Type::VoidType | Type::FuncType { .. }) {
+                    errs.push(format!("expected a first-class operand, got {}", ty));
+                }
+            },
+            Instruction::Call(i) => self.check_call(
+                &mut errs,
+                &i.function,
+                #[cfg(feature = "llvm-15-or-greater")]
+                &i.function_ty,
+                i.arguments.iter().map(|(arg, _)| arg),
+                i.dest.is_some(),
+            ),
+            _ => {},
+        }
+        errs
+    }
+
+    /// Check the operand types of a terminator; returns a description of each
+    /// problem found
+    fn check_terminator(&self, term: &Terminator) -> Vec<String> {
+        let mut errs = vec![];
+        match term {
+            Terminator::Ret(t) => {
+                let ty = match &t.return_operand {
+                    Some(op) => self.types.type_of(op),
+                    None => self.types.void(),
+                };
+                if ty != self.func.return_type {
+                    errs.push(format!(
+                        "function returns {}, but this returns {}",
+                        self.func.return_type, ty
+                    ));
+                }
+            },
+            Terminator::CondBr(t) => {
+                let ty = self.types.type_of(&t.condition);
+                if *ty != (Type::IntegerType { bits: 1 }) {
+                    errs.push(format!("expected an i1 condition, got {}", ty));
+                }
+            },
+            Terminator::Switch(t) => {
+                let ty = self.types.type_of(&t.operand);
+                if !is_int(&ty) {
+                    errs.push(format!("expected an integer operand, got {}", ty));
+                }
+                for (value, _) in &t.dests {
+                    let value_ty = self.types.type_of(value);
+                    if value_ty != ty {
+                        errs.push(format!(
+                            "expected case values of type {}, got {}",
+                            ty, value_ty
+                        ));
+                    }
+                }
+            },
+            Terminator::IndirectBr(t) => {
+                let ty = self.types.type_of(&t.operand);
+                if !matches!(ty.as_ref(), Type::PointerType { .. }) {
+                    errs.push(format!("expected a pointer operand, got {}", ty));
+                }
+            },
+            Terminator::Invoke(t) => self.check_call(
+                &mut errs,
+                &t.function,
+                #[cfg(feature = "llvm-15-or-greater")]
+                &t.function_ty,
+                t.arguments.iter().map(|(arg, _)| arg),
+                false,
+            ),
+            Terminator::CallBr(t) => self.check_call(
+                &mut errs,
+                &t.function,
+                #[cfg(feature = "llvm-15-or-greater")]
+                &t.function_ty,
+                t.arguments.iter().map(|(arg, _)| arg),
+                false,
+            ),
+            _ => {},
+        }
+        errs
+    }
+
+    /// Type of the result of `term` (if any), which must have passed
+    /// `check_terminator()`
+    fn terminator_result_type(&self, term: &Terminator) -> Option<TypeRef> {
+        match term {
+            Terminator::Invoke(t) => Some(self.types.type_of(t)),
+            Terminator::CatchSwitch(_) => Some(self.types.token_type()),
+            Terminator::CallBr(t) => Some(self.types.type_of(t)),
+            _ => None,
+        }
+    }
+
+    fn check_binop(
+        &self,
+        errs: &mut Vec<String>,
+        operand0: &Operand,
+        operand1: &Operand,
+        is_valid: fn(&Type) -> bool,
+    ) {
+        let ty0 = self.types.type_of(operand0);
+        let ty1 = self.types.type_of(operand1);
+        if ty0 != ty1 {
+            errs.push(format!(
+                "operands have different types: {} and {}",
+                ty0, ty1
+            ));
+        } else if !is_valid(scalar_type(&ty0)) {
+            errs.push(format!("invalid operand type {}", ty0));
+        }
+    }
+
+    fn check_cast(
+        &self,
+        errs: &mut Vec<String>,
+        operand: &Operand,
+        to_type: &TypeRef,
+        is_valid: fn(&TypeRef, &TypeRef) -> bool,
+    ) {
+        let from_type = self.types.type_of(operand);
+        if !is_valid(&from_type, to_type) {
+            errs.push(format!("can't cast {} to {}", from_type, to_type));
+        }
+    }
+
+    /// Check that `address` is a pointer (or vector of pointers), and (for
+    /// LLVM 14 and lower) that it points to `pointee`, if given
+    fn check_pointer(&self, errs: &mut Vec<String>, address: &Operand, pointee: Option<&TypeRef>) {
+        let ty = self.types.type_of(address);
+        match scalar_type(&ty) {
+            #[cfg(feature = "llvm-14-or-lower")]
+            Type::PointerType { pointee_type, .. } => {
+                if let Some(pointee) = pointee {
+                    if pointee_type != pointee {
+                        errs.push(format!("expected a pointer to {}, got {}", pointee, ty));
+                    }
+                }
+            },
+            #[cfg(feature = "llvm-15-or-greater")]
+            Type::PointerType { .. } => {
+                let _ = pointee;
+            },
+            _ => errs.push(format!("expected a pointer, got {}", ty)),
+        }
+    }
+
+    fn check_gep(&self, errs: &mut Vec<String>, gep: &crate::instruction::GetElementPtr) {
+        let len_before = errs.len();
+        self.check_pointer(errs, &gep.address, None);
+        for index in &gep.indices {
+            let ty = self.types.type_of(index);
+            if !is_int(scalar_type(&ty)) {
+                errs.push(format!("expected an integer index, got {}", ty));
+            }
+        }
+        if errs.len() > len_before {
+            return;
+        }
+        #[cfg(feature = "llvm-14-or-lower")]
+        let source_element_type = match scalar_type(&self.types.type_of(&gep.address)) {
+            Type::PointerType { pointee_type, .. } => pointee_type.clone(),
+            _ => unreachable!("checked above"),
+        };
+        #[cfg(feature = "llvm-15-or-greater")]
+        let source_element_type = gep.source_element_type.clone();
+        // the first index steps over the pointer, and doesn't change the type
+        let indices = gep.indices.iter().skip(1).map(Index::from);
+        if let Err(e) = self.indexed_type(&source_element_type, indices) {
+            errs.push(e);
+        }
+    }
+
+    /// Check a call of `function` with `arguments`. `has_result` is whether
+    /// the call has a result `Name`.
+    fn check_call<'o>(
+        &self,
+        errs: &mut Vec<String>,
+        function: &Either<Box<InlineAssembly>, Operand>,
+        #[cfg(feature = "llvm-15-or-greater")] function_ty: &TypeRef,
+        arguments: impl ExactSizeIterator<Item = &'o Operand>,
+        has_result: bool,
+    ) {
+        #[cfg(feature = "llvm-14-or-lower")]
+        let pointee_type = {
+            let ty = self.types.type_of(function);
+            match ty.as_ref() {
+                Type::PointerType { pointee_type, .. } => pointee_type.clone(),
+                _ => {
+                    errs.push(format!(
+                        "expected the callee to be a pointer to a function, got {}",
+                        ty
+                    ));
+                    return;
+                },
+            }
+        };
+        #[cfg(feature = "llvm-14-or-lower")]
+        let function_ty = &pointee_type;
+        #[cfg(feature = "llvm-15-or-greater")]
+        if let Either::Right(op) = function {
+            let ty = self.types.type_of(op);
+            if !matches!(ty.as_ref(), Type::PointerType { .. }) {
+                errs.push(format!("expected the callee to be a pointer, got {}", ty));
+            }
+        }
+        let (result_type, param_types, is_var_arg) = match function_ty.as_ref() {
+            Type::FuncType {
+                result_type,
+                param_types,
+                is_var_arg,
+            } => (result_type, param_types, *is_var_arg),
+            _ => {
+                errs.push(format!("expected a function type, got {}", function_ty));
+                return;
+            },
+        };
+        let num_args = arguments.len();
+        if num_args < param_types.len() || (!is_var_arg && num_args > param_types.len()) {
+            errs.push(format!(
+                "function of type {} called with {} arguments",
+                function_ty, num_args
+            ));
+        }
+        for (arg, param_ty) in arguments.zip(param_types) {
+            let arg_ty = self.types.type_of(arg);
+            if &arg_ty != param_ty {
+                errs.push(format!(
+                    "expected an argument of type {}, got {}",
+                    param_ty, arg_ty
+                ));
+            }
+        }
+        if has_result && **result_type == Type::VoidType {
+            errs.push("call of a function returning void has a result".into());
+        }
+        // if the callee is a function of this module, it should have the type
+        // it's called with
+        if let Either::Right(Operand::ConstantOperand(c)) = function {
+            if let Constant::GlobalReference {
+                name: Name::Name(name),
+                ..
+            } = c.as_ref()
+            {
+                let callee_ty = if let Some(f) = self.module.get_func_by_name(name) {
+                    Some(self.types.type_of(f))
+                } else {
+                    self.module.get_func_decl_by_name(name).map(|f| {
+                        self.types.func_type(
+                            f.return_type.clone(),
+                            f.parameters.iter().map(|p| p.ty.clone()).collect(),
+                            f.is_var_arg,
+                        )
+                    })
+                };
+                if let Some(callee_ty) = callee_ty {
+                    if &callee_ty != function_ty {
+                        errs.push(format!(
+                            "@{} has type {}, but is called as {}",
+                            name, callee_ty, function_ty
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the type reached by indexing into `ty` with `indices`, as for
+    /// `extractvalue` or the indices after the first in `getelementptr`
+    fn indexed_type(
+        &self,
+        ty: &TypeRef,
+        indices: impl Iterator<Item = Index>,
+    ) -> Result<TypeRef, String> {
+        let mut cur = ty.clone();
+        for index in indices {
+            if let Type::NamedStructType { name } = cur.as_ref() {
+                cur = match self.types.named_struct_def(name) {
+                    Some(NamedStructDef::Defined(def)) => def.clone(),
+                    Some(NamedStructDef::Opaque) | None => {
+                        return Err(format!("can't index into opaque struct type {}", cur));
+                    },
+                };
+            }
+            cur = match (cur.as_ref(), index) {
+                (Type::ArrayType { element_type, .. }, _)
+                | (Type::VectorType { element_type, .. }, _) => element_type.clone(),
+                (Type::StructType { element_types, .. }, Index::Constant(i)) => {
+                    match element_types.get(i as usize) {
+                        Some(ty) => ty.clone(),
+                        None => return Err(format!("index {} out of range for {}", i, cur)),
+                    }
+                },
+                (Type::StructType { .. }, Index::Dynamic) => {
+                    return Err(format!("index into {} must be a constant integer", cur));
+                },
+                _ => return Err(format!("can't index into type {}", cur)),
+            };
+        }
+        Ok(cur)
+    }
+}
@@ -0,0 +1,745 @@
+//! Traversal of the IR with the `Visitor` and `VisitorMut` traits.
+//!
+//! Each trait has one method per kind of IR node: `visit_module`,
+//! `visit_function`, `visit_basic_block`, one method per `Instruction`,
+//! `Terminator` and constant-expression variant (e.g. `visit_add`,
+//! `visit_call`, `visit_constant_gep`), `visit_operand` and `visit_constant`.
+//! By default, each method just continues the traversal into the node's
+//! children, by calling the corresponding function in the [`walk`] (or
+//! [`walk_mut`]) module; the traversal goes Module → Function → BasicBlock →
+//! Instruction/Terminator → Operand → Constant. So it's enough to override the
+//! methods for the nodes you're interested in. An overriding method can call
+//! the `walk` function itself to keep visiting the node's children, or not
+//! call it to skip them.
+//!
+//! For instance, to collect the names of all functions called directly:
+//!
+//! ```
+//! use llvm_ir::visit::{walk, Visitor};
+//! use llvm_ir::{instruction::Call, Constant, Module, Name, Operand};
+//!
+//! #[derive(Default)]
+//! struct Callees(Vec<Name>);
+//!
+//! impl Visitor for Callees {
+//!     fn visit_call(&mut self, call: &Call) {
+//!         if let Some(Operand::ConstantOperand(c)) = call.function.as_ref().right() {
+//!             if let Constant::GlobalReference { name, .. } = c.as_ref() {
+//!                 self.0.push(name.clone());
+//!             }
+//!         }
+//!         walk::call(self, call);
+//!     }
+//! }
+//!
+//! fn callees(module: &Module) -> Vec<Name> {
+//!     let mut callees = Callees::default();
+//!     callees.visit_module(module);
+//!     callees.0
+//! }
+//! ```
+//!
+//! `VisitorMut` has the same methods, but taking `&mut` references, so it can
+//! rewrite the IR in place. Since `Constant`s are shared and immutable,
+//! `VisitorMut::visit_constant` gets a `&mut ConstantRef`, and rewrites it by
+//! replacing it with a new `ConstantRef`. The default implementation
+//! (`walk_mut::constant`) does this automatically whenever a nested constant
+//! was replaced.
+//!
+//! Names of values and basic blocks (`Name`s) and types (`TypeRef`s) are not
+//! visited.
+
+use crate::basicblock::BasicBlock;
+use crate::constant::{self, Constant, ConstantRef};
+use crate::function::Function;
+use crate::instruction::{self, InlineAssembly, Instruction, LandingPadClause};
+use crate::module::{GlobalAlias, GlobalIFunc, GlobalVariable, Module};
+use crate::operand::Operand;
+use crate::terminator::{self, Terminator};
+use either::Either;
+
+/// Parts of the IR which the visitors can walk through
+trait Walk {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V);
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V);
+}
+
+impl Walk for Operand {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_operand(self)
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        visitor.visit_operand(self)
+    }
+}
+
+impl Walk for ConstantRef {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_constant(self)
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        visitor.visit_constant(self)
+    }
+}
+
+impl<T: Walk> Walk for Vec<T> {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        for t in self {
+            t.walk(visitor);
+        }
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        for t in self {
+            t.walk_mut(visitor);
+        }
+    }
+}
+
+impl<T: Walk> Walk for Option<T> {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        if let Some(t) = self {
+            t.walk(visitor);
+        }
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        if let Some(t) = self {
+            t.walk_mut(visitor);
+        }
+    }
+}
+
+/// Pairs such as phi incoming values, call arguments and switch cases: only
+/// the first element is walked, the second being a `Name` or attributes
+impl<T: Walk, U> Walk for (T, U) {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        self.0.walk(visitor)
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        self.0.walk_mut(visitor)
+    }
+}
+
+/// The callee of a call, invoke or callbr
+impl Walk for Either<Box<InlineAssembly>, Operand> {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        if let Either::Right(operand) = self {
+            operand.walk(visitor);
+        }
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        if let Either::Right(operand) = self {
+            operand.walk_mut(visitor);
+        }
+    }
+}
+
+impl Walk for LandingPadClause {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        match self {
+            LandingPadClause::Catch(c) | LandingPadClause::Filter(c) => c.walk(visitor),
+        }
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        match self {
+            LandingPadClause::Catch(c) | LandingPadClause::Filter(c) => c.walk_mut(visitor),
+        }
+    }
+}
+
+#[cfg(feature = "llvm-18-or-greater")]
+impl Walk for instruction::OperandBundle {
+    fn walk<V: Visitor + ?Sized>(&self, visitor: &mut V) {
+        self.inputs.walk(visitor)
+    }
+
+    fn walk_mut<V: VisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        self.inputs.walk_mut(visitor)
+    }
+}
+
+/// Generates the `Visitor` and `VisitorMut` traits and the `walk` and
+/// `walk_mut` modules from the lists of `Instruction`, `Terminator` and
+/// constant-expression variants, along with the fields of each which the
+/// traversal should continue into
+macro_rules! visitors {
+    (
+        instructions { $(
+            $(#[$iattr:meta])*
+            $ivariant:ident($ity:path) => $ivisit:ident / $iwalk:ident {
+                $( $(#[$ifattr:meta])* $ifield:ident ),*
+            }
+        )* }
+        terminators { $(
+            $tvariant:ident($tty:path) => $tvisit:ident / $twalk:ident {
+                $( $(#[$tfattr:meta])* $tfield:ident ),*
+            }
+        )* }
+        constants { $(
+            $(#[$cattr:meta])*
+            $cvariant:ident($cty:path) => $cvisit:ident / $cwalk:ident {
+                $( $cfield:ident ),*
+            }
+        )* }
+    ) => {
+        /// Visits the IR by shared reference. See the [module-level
+        /// docs](index.html).
+        pub trait Visitor {
+            fn visit_module(&mut self, module: &Module) {
+                walk::module(self, module)
+            }
+
+            fn visit_function(&mut self, function: &Function) {
+                walk::function(self, function)
+            }
+
+            fn visit_global_variable(&mut self, global: &GlobalVariable) {
+                walk::global_variable(self, global)
+            }
+
+            fn visit_global_alias(&mut self, alias: &GlobalAlias) {
+                walk::global_alias(self, alias)
+            }
+
+            fn visit_global_ifunc(&mut self, ifunc: &GlobalIFunc) {
+                walk::global_ifunc(self, ifunc)
+            }
+
+            fn visit_basic_block(&mut self, bb: &BasicBlock) {
+                walk::basic_block(self, bb)
+            }
+
+            /// Called for every `Instruction`, before the method for the
+            /// specific kind of instruction (which `walk::instruction` calls)
+            fn visit_instruction(&mut self, instruction: &Instruction) {
+                walk::instruction(self, instruction)
+            }
+
+            /// Called for every `Terminator`, before the method for the
+            /// specific kind of terminator (which `walk::terminator` calls)
+            fn visit_terminator(&mut self, terminator: &Terminator) {
+                walk::terminator(self, terminator)
+            }
+
+            fn visit_operand(&mut self, operand: &Operand) {
+                walk::operand(self, operand)
+            }
+
+            /// Called for every constant, including nested ones, before the
+            /// method for the specific kind of constant expression (which
+            /// `walk::constant` calls)
+            fn visit_constant(&mut self, constant: &ConstantRef) {
+                walk::constant(self, constant)
+            }
+
+            $(
+                $(#[$iattr])*
+                fn $ivisit(&mut self, instruction: &$ity) {
+                    walk::$iwalk(self, instruction)
+                }
+            )*
+
+            $(
+                fn $tvisit(&mut self, terminator: &$tty) {
+                    walk::$twalk(self, terminator)
+                }
+            )*
+
+            $(
+                $(#[$cattr])*
+                fn $cvisit(&mut self, expr: &$cty) {
+                    walk::$cwalk(self, expr)
+                }
+            )*
+        }
+
+        /// Visits the IR by mutable reference, allowing it to be rewritten
+        /// in place. See the [module-level docs](index.html).
+        pub trait VisitorMut {
+            fn visit_module(&mut self, module: &mut Module) {
+                walk_mut::module(self, module)
+            }
+
+            fn visit_function(&mut self, function: &mut Function) {
+                walk_mut::function(self, function)
+            }
+
+            fn visit_global_variable(&mut self, global: &mut GlobalVariable) {
+                walk_mut::global_variable(self, global)
+            }
+
+            fn visit_global_alias(&mut self, alias: &mut GlobalAlias) {
+                walk_mut::global_alias(self, alias)
+            }
+
+            fn visit_global_ifunc(&mut self, ifunc: &mut GlobalIFunc) {
+                walk_mut::global_ifunc(self, ifunc)
+            }
+
+            fn visit_basic_block(&mut self, bb: &mut BasicBlock) {
+                walk_mut::basic_block(self, bb)
+            }
+
+            fn visit_instruction(&mut self, instruction: &mut Instruction) {
+                walk_mut::instruction(self, instruction)
+            }
+
+            fn visit_terminator(&mut self, terminator: &mut Terminator) {
+                walk_mut::terminator(self, terminator)
+            }
+
+            fn visit_operand(&mut self, operand: &mut Operand) {
+                walk_mut::operand(self, operand)
+            }
+
+            /// Rewrite a constant by assigning a new `ConstantRef`
+            fn visit_constant(&mut self, constant: &mut ConstantRef) {
+                walk_mut::constant(self, constant)
+            }
+
+            $(
+                $(#[$iattr])*
+                fn $ivisit(&mut self, instruction: &mut $ity) {
+                    walk_mut::$iwalk(self, instruction)
+                }
+            )*
+
+            $(
+                fn $tvisit(&mut self, terminator: &mut $tty) {
+                    walk_mut::$twalk(self, terminator)
+                }
+            )*
+
+            $(
+                $(#[$cattr])*
+                fn $cvisit(&mut self, expr: &mut $cty) {
+                    walk_mut::$cwalk(self, expr)
+                }
+            )*
+        }
+
+        /// Default traversals for `Visitor`: each function visits the
+        /// children of the given node
+        pub mod walk {
+            use super::*;
+
+            pub fn module<V: Visitor + ?Sized>(visitor: &mut V, module: &Module) {
+                for global in &module.global_vars {
+                    visitor.visit_global_variable(global);
+                }
+                for alias in &module.global_aliases {
+                    visitor.visit_global_alias(alias);
+                }
+                for ifunc in &module.global_ifuncs {
+                    visitor.visit_global_ifunc(ifunc);
+                }
+                for function in &module.functions {
+                    visitor.visit_function(function);
+                }
+            }
+
+            pub fn function<V: Visitor + ?Sized>(visitor: &mut V, function: &Function) {
+                function.prefix_data.walk(visitor);
+                function.prologue_data.walk(visitor);
+                function.personality_function.walk(visitor);
+                for bb in &function.basic_blocks {
+                    visitor.visit_basic_block(bb);
+                }
+            }
+
+            pub fn global_variable<V: Visitor + ?Sized>(visitor: &mut V, global: &GlobalVariable) {
+                global.initializer.walk(visitor);
+            }
+
+            pub fn global_alias<V: Visitor + ?Sized>(visitor: &mut V, alias: &GlobalAlias) {
+                alias.aliasee.walk(visitor);
+            }
+
+            pub fn global_ifunc<V: Visitor + ?Sized>(visitor: &mut V, ifunc: &GlobalIFunc) {
+                ifunc.resolver_fn.walk(visitor);
+            }
+
+            pub fn basic_block<V: Visitor + ?Sized>(visitor: &mut V, bb: &BasicBlock) {
+                for instruction in &bb.instrs {
+                    visitor.visit_instruction(instruction);
+                }
+                visitor.visit_terminator(&bb.term);
+            }
+
+            pub fn instruction<V: Visitor + ?Sized>(visitor: &mut V, instruction: &Instruction) {
+                match instruction {
+                    $(
+                        $(#[$iattr])*
+                        Instruction::$ivariant(i) => visitor.$ivisit(i),
+                    )*
+                }
+            }
+
+            pub fn terminator<V: Visitor + ?Sized>(visitor: &mut V, terminator: &Terminator) {
+                match terminator {
+                    $(
+                        Terminator::$tvariant(t) => visitor.$tvisit(t),
+                    )*
+                }
+            }
+
+            pub fn operand<V: Visitor + ?Sized>(visitor: &mut V, operand: &Operand) {
+                if let Operand::ConstantOperand(constant) = operand {
+                    visitor.visit_constant(constant);
+                }
+            }
+
+            pub fn constant<V: Visitor + ?Sized>(visitor: &mut V, constant: &ConstantRef) {
+                match constant.as_ref() {
+                    Constant::Struct { values, .. } => values.walk(visitor),
+                    Constant::Array { elements, .. } => elements.walk(visitor),
+                    Constant::Vector(elements) => elements.walk(visitor),
+                    #[cfg(feature = "llvm-19-or-greater")]
+                    Constant::PtrAuth { ptr, key, disc, addr_disc } => {
+                        ptr.walk(visitor);
+                        key.walk(visitor);
+                        disc.walk(visitor);
+                        addr_disc.walk(visitor);
+                    },
+                    $(
+                        $(#[$cattr])*
+                        Constant::$cvariant(expr) => visitor.$cvisit(expr),
+                    )*
+                    _ => {},
+                }
+            }
+
+            $(
+                $(#[$iattr])*
+                #[allow(unused_variables)] // for nodes without operands
+                pub fn $iwalk<V: Visitor + ?Sized>(visitor: &mut V, instruction: &$ity) {
+                    $(
+                        $(#[$ifattr])*
+                        instruction.$ifield.walk(visitor);
+                    )*
+                }
+            )*
+
+            $(
+                #[allow(unused_variables)] // for nodes without operands
+                pub fn $twalk<V: Visitor + ?Sized>(visitor: &mut V, terminator: &$tty) {
+                    $(
+                        $(#[$tfattr])*
+                        terminator.$tfield.walk(visitor);
+                    )*
+                }
+            )*
+
+            $(
+                $(#[$cattr])*
+                pub fn $cwalk<V: Visitor + ?Sized>(visitor: &mut V, expr: &$cty) {
+                    $(
+                        expr.$cfield.walk(visitor);
+                    )*
+                }
+            )*
+        }
+
+        /// Default traversals for `VisitorMut`: each function visits the
+        /// children of the given node
+        pub mod walk_mut {
+            use super::*;
+
+            pub fn module<V: VisitorMut + ?Sized>(visitor: &mut V, module: &mut Module) {
+                for global in &mut module.global_vars {
+                    visitor.visit_global_variable(global);
+                }
+                for alias in &mut module.global_aliases {
+                    visitor.visit_global_alias(alias);
+                }
+                for ifunc in &mut module.global_ifuncs {
+                    visitor.visit_global_ifunc(ifunc);
+                }
+                for function in &mut module.functions {
+                    visitor.visit_function(function);
+                }
+            }
+
+            pub fn function<V: VisitorMut + ?Sized>(visitor: &mut V, function: &mut Function) {
+                function.prefix_data.walk_mut(visitor);
+                function.prologue_data.walk_mut(visitor);
+                function.personality_function.walk_mut(visitor);
+                for bb in &mut function.basic_blocks {
+                    visitor.visit_basic_block(bb);
+                }
+            }
+
+            pub fn global_variable<V: VisitorMut + ?Sized>(
+                visitor: &mut V,
+                global: &mut GlobalVariable,
+            ) {
+                global.initializer.walk_mut(visitor);
+            }
+
+            pub fn global_alias<V: VisitorMut + ?Sized>(visitor: &mut V, alias: &mut GlobalAlias) {
+                alias.aliasee.walk_mut(visitor);
+            }
+
+            pub fn global_ifunc<V: VisitorMut + ?Sized>(visitor: &mut V, ifunc: &mut GlobalIFunc) {
+                ifunc.resolver_fn.walk_mut(visitor);
+            }
+
+            pub fn basic_block<V: VisitorMut + ?Sized>(visitor: &mut V, bb: &mut BasicBlock) {
+                for instruction in &mut bb.instrs {
+                    visitor.visit_instruction(instruction);
+                }
+                visitor.visit_terminator(&mut bb.term);
+            }
+
+            pub fn instruction<V: VisitorMut + ?Sized>(
+                visitor: &mut V,
+                instruction: &mut Instruction,
+            ) {
+                match instruction {
+                    $(
+                        $(#[$iattr])*
+                        Instruction::$ivariant(i) => visitor.$ivisit(i),
+                    )*
+                }
+            }
+
+            pub fn terminator<V: VisitorMut + ?Sized>(
+                visitor: &mut V,
+                terminator: &mut Terminator,
+            ) {
+                match terminator {
+                    $(
+                        Terminator::$tvariant(t) => visitor.$tvisit(t),
+                    )*
+                }
+            }
+
+            pub fn operand<V: VisitorMut + ?Sized>(visitor: &mut V, operand: &mut Operand) {
+                if let Operand::ConstantOperand(constant) = operand {
+                    visitor.visit_constant(constant);
+                }
+            }
+
+            /// Visits the operands of the constant (if any), and if any of
+            /// them were replaced, replaces `constant` with a new
+            /// `ConstantRef` referring to the updated operands
+            pub fn constant<V: VisitorMut + ?Sized>(visitor: &mut V, constant: &mut ConstantRef) {
+                let mut updated = Constant::clone(constant);
+                match &mut updated {
+                    Constant::Struct { values, .. } => values.walk_mut(visitor),
+                    Constant::Array { elements, .. } => elements.walk_mut(visitor),
+                    Constant::Vector(elements) => elements.walk_mut(visitor),
+                    #[cfg(feature = "llvm-19-or-greater")]
+                    Constant::PtrAuth { ptr, key, disc, addr_disc } => {
+                        ptr.walk_mut(visitor);
+                        key.walk_mut(visitor);
+                        disc.walk_mut(visitor);
+                        addr_disc.walk_mut(visitor);
+                    },
+                    $(
+                        $(#[$cattr])*
+                        Constant::$cvariant(expr) => visitor.$cvisit(expr),
+                    )*
+                    _ => return,
+                }
+                if updated != **constant {
+                    *constant = ConstantRef::new(updated);
+                }
+            }
+
+            $(
+                $(#[$iattr])*
+                #[allow(unused_variables)] // for nodes without operands
+                pub fn $iwalk<V: VisitorMut + ?Sized>(visitor: &mut V, instruction: &mut $ity) {
+                    $(
+                        $(#[$ifattr])*
+                        instruction.$ifield.walk_mut(visitor);
+                    )*
+                }
+            )*
+
+            $(
+                #[allow(unused_variables)] // for nodes without operands
+                pub fn $twalk<V: VisitorMut + ?Sized>(visitor: &mut V, terminator: &mut $tty) {
+                    $(
+                        $(#[$tfattr])*
+                        terminator.$tfield.walk_mut(visitor);
+                    )*
+                }
+            )*
+
+            $(
+                $(#[$cattr])*
+                pub fn $cwalk<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut $cty) {
+                    $(
+                        expr.$cfield.walk_mut(visitor);
+                    )*
+                }
+            )*
+        }
+    };
+}
+
+visitors! {
+    instructions {
+        Add(instruction::Add) => visit_add / add { operand0, operand1 }
+        Sub(instruction::Sub) => visit_sub / sub { operand0, operand1 }
+        Mul(instruction::Mul) => visit_mul / mul { operand0, operand1 }
+        UDiv(instruction::UDiv) => visit_udiv / udiv { operand0, operand1 }
+        SDiv(instruction::SDiv) => visit_sdiv / sdiv { operand0, operand1 }
+        URem(instruction::URem) => visit_urem / urem { operand0, operand1 }
+        SRem(instruction::SRem) => visit_srem / srem { operand0, operand1 }
+        And(instruction::And) => visit_and / and { operand0, operand1 }
+        Or(instruction::Or) => visit_or / or { operand0, operand1 }
+        Xor(instruction::Xor) => visit_xor / xor { operand0, operand1 }
+        Shl(instruction::Shl) => visit_shl / shl { operand0, operand1 }
+        LShr(instruction::LShr) => visit_lshr / lshr { operand0, operand1 }
+        AShr(instruction::AShr) => visit_ashr / ashr { operand0, operand1 }
+        FAdd(instruction::FAdd) => visit_fadd / fadd { operand0, operand1 }
+        FSub(instruction::FSub) => visit_fsub / fsub { operand0, operand1 }
+        FMul(instruction::FMul) => visit_fmul / fmul { operand0, operand1 }
+        FDiv(instruction::FDiv) => visit_fdiv / fdiv { operand0, operand1 }
+        FRem(instruction::FRem) => visit_frem / frem { operand0, operand1 }
+        FNeg(instruction::FNeg) => visit_fneg / fneg { operand }
+        ExtractElement(instruction::ExtractElement) => visit_extract_element / extract_element { vector, index }
+        InsertElement(instruction::InsertElement) => visit_insert_element / insert_element { vector, element, index }
+        ShuffleVector(instruction::ShuffleVector) => visit_shuffle_vector / shuffle_vector { operand0, operand1, mask }
+        ExtractValue(instruction::ExtractValue) => visit_extract_value / extract_value { aggregate }
+        InsertValue(instruction::InsertValue) => visit_insert_value / insert_value { aggregate, element }
+        Alloca(instruction::Alloca) => visit_alloca / alloca { num_elements }
+        Load(instruction::Load) => visit_load / load { address }
+        Store(instruction::Store) => visit_store / store { address, value }
+        Fence(instruction::Fence) => visit_fence / fence {}
+        CmpXchg(instruction::CmpXchg) => visit_cmpxchg / cmpxchg { address, expected, replacement }
+        AtomicRMW(instruction::AtomicRMW) => visit_atomicrmw / atomicrmw { address, value }
+        GetElementPtr(instruction::GetElementPtr) => visit_gep / gep { address, indices }
+        Trunc(instruction::Trunc) => visit_trunc / trunc { operand }
+        ZExt(instruction::ZExt) => visit_zext / zext { operand }
+        SExt(instruction::SExt) => visit_sext / sext { operand }
+        FPTrunc(instruction::FPTrunc) => visit_fptrunc / fptrunc { operand }
+        FPExt(instruction::FPExt) => visit_fpext / fpext { operand }
+        FPToUI(instruction::FPToUI) => visit_fptoui / fptoui { operand }
+        FPToSI(instruction::FPToSI) => visit_fptosi / fptosi { operand }
+        UIToFP(instruction::UIToFP) => visit_uitofp / uitofp { operand }
+        SIToFP(instruction::SIToFP) => visit_sitofp / sitofp { operand }
+        PtrToInt(instruction::PtrToInt) => visit_ptrtoint / ptrtoint { operand }
+        IntToPtr(instruction::IntToPtr) => visit_inttoptr / inttoptr { operand }
+        BitCast(instruction::BitCast) => visit_bitcast / bitcast { operand }
+        AddrSpaceCast(instruction::AddrSpaceCast) => visit_addrspacecast / addrspacecast { operand }
+        ICmp(instruction::ICmp) => visit_icmp / icmp { operand0, operand1 }
+        FCmp(instruction::FCmp) => visit_fcmp / fcmp { operand0, operand1 }
+        Phi(instruction::Phi) => visit_phi / phi { incoming_values }
+        Select(instruction::Select) => visit_select / select { condition, true_value, false_value }
+        #[cfg(feature = "llvm-10-or-greater")]
+        Freeze(instruction::Freeze) => visit_freeze / freeze { operand }
+        Call(instruction::Call) => visit_call / call {
+            function,
+            arguments,
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles
+        }
+        VAArg(instruction::VAArg) => visit_va_arg / va_arg { arg_list }
+        LandingPad(instruction::LandingPad) => visit_landingpad / landingpad { clauses }
+        CatchPad(instruction::CatchPad) => visit_catchpad / catchpad { catch_switch, args }
+        CleanupPad(instruction::CleanupPad) => visit_cleanuppad / cleanuppad { parent_pad, args }
+    }
+    terminators {
+        Ret(terminator::Ret) => visit_ret / ret { return_operand }
+        Br(terminator::Br) => visit_br / br {}
+        CondBr(terminator::CondBr) => visit_cond_br / cond_br { condition }
+        Switch(terminator::Switch) => visit_switch / switch { operand, dests }
+        IndirectBr(terminator::IndirectBr) => visit_indirect_br / indirect_br { operand }
+        Invoke(terminator::Invoke) => visit_invoke / invoke {
+            function,
+            arguments,
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles
+        }
+        Resume(terminator::Resume) => visit_resume / resume { operand }
+        Unreachable(terminator::Unreachable) => visit_unreachable / unreachable {}
+        CleanupRet(terminator::CleanupRet) => visit_cleanupret / cleanupret { cleanup_pad }
+        CatchRet(terminator::CatchRet) => visit_catchret / catchret { catch_pad }
+        CatchSwitch(terminator::CatchSwitch) => visit_catchswitch / catchswitch { parent_pad }
+        CallBr(terminator::CallBr) => visit_callbr / callbr {
+            function,
+            arguments,
+            #[cfg(feature = "llvm-18-or-greater")]
+            operand_bundles
+        }
+    }
+    constants {
+        Add(constant::Add) => visit_constant_add / constant_add { operand0, operand1 }
+        Sub(constant::Sub) => visit_constant_sub / constant_sub { operand0, operand1 }
+        Mul(constant::Mul) => visit_constant_mul / constant_mul { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        UDiv(constant::UDiv) => visit_constant_udiv / constant_udiv { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        SDiv(constant::SDiv) => visit_constant_sdiv / constant_sdiv { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        URem(constant::URem) => visit_constant_urem / constant_urem { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        SRem(constant::SRem) => visit_constant_srem / constant_srem { operand0, operand1 }
+        #[cfg(feature = "llvm-17-or-lower")]
+        And(constant::And) => visit_constant_and / constant_and { operand0, operand1 }
+        #[cfg(feature = "llvm-17-or-lower")]
+        Or(constant::Or) => visit_constant_or / constant_or { operand0, operand1 }
+        Xor(constant::Xor) => visit_constant_xor / constant_xor { operand0, operand1 }
+        #[cfg(feature = "llvm-18-or-lower")]
+        Shl(constant::Shl) => visit_constant_shl / constant_shl { operand0, operand1 }
+        #[cfg(feature = "llvm-17-or-lower")]
+        LShr(constant::LShr) => visit_constant_lshr / constant_lshr { operand0, operand1 }
+        #[cfg(feature = "llvm-17-or-lower")]
+        AShr(constant::AShr) => visit_constant_ashr / constant_ashr { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        FAdd(constant::FAdd) => visit_constant_fadd / constant_fadd { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        FSub(constant::FSub) => visit_constant_fsub / constant_fsub { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        FMul(constant::FMul) => visit_constant_fmul / constant_fmul { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        FDiv(constant::FDiv) => visit_constant_fdiv / constant_fdiv { operand0, operand1 }
+        #[cfg(feature = "llvm-14-or-lower")]
+        FRem(constant::FRem) => visit_constant_frem / constant_frem { operand0, operand1 }
+        ExtractElement(constant::ExtractElement) => visit_constant_extract_element / constant_extract_element { vector, index }
+        InsertElement(constant::InsertElement) => visit_constant_insert_element / constant_insert_element { vector, element, index }
+        ShuffleVector(constant::ShuffleVector) => visit_constant_shuffle_vector / constant_shuffle_vector { operand0, operand1, mask }
+        #[cfg(feature = "llvm-14-or-lower")]
+        ExtractValue(constant::ExtractValue) => visit_constant_extract_value / constant_extract_value { aggregate }
+        #[cfg(feature = "llvm-14-or-lower")]
+        InsertValue(constant::InsertValue) => visit_constant_insert_value / constant_insert_value { aggregate, element }
+        GetElementPtr(constant::GetElementPtr) => visit_constant_gep / constant_gep { address, indices }
+        Trunc(constant::Trunc) => visit_constant_trunc / constant_trunc { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        ZExt(constant::ZExt) => visit_constant_zext / constant_zext { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        SExt(constant::SExt) => visit_constant_sext / constant_sext { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        FPTrunc(constant::FPTrunc) => visit_constant_fptrunc / constant_fptrunc { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        FPExt(constant::FPExt) => visit_constant_fpext / constant_fpext { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        FPToUI(constant::FPToUI) => visit_constant_fptoui / constant_fptoui { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        FPToSI(constant::FPToSI) => visit_constant_fptosi / constant_fptosi { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        UIToFP(constant::UIToFP) => visit_constant_uitofp / constant_uitofp { operand }
+        #[cfg(feature = "llvm-17-or-lower")]
+        SIToFP(constant::SIToFP) => visit_constant_sitofp / constant_sitofp { operand }
+        PtrToInt(constant::PtrToInt) => visit_constant_ptrtoint / constant_ptrtoint { operand }
+        IntToPtr(constant::IntToPtr) => visit_constant_inttoptr / constant_inttoptr { operand }
+        BitCast(constant::BitCast) => visit_constant_bitcast / constant_bitcast { operand }
+        AddrSpaceCast(constant::AddrSpaceCast) => visit_constant_addrspacecast / constant_addrspacecast { operand }
+        #[cfg(feature = "llvm-18-or-lower")]
+        ICmp(constant::ICmp) => visit_constant_icmp / constant_icmp { operand0, operand1 }
+        #[cfg(feature = "llvm-18-or-lower")]
+        FCmp(constant::FCmp) => visit_constant_fcmp / constant_fcmp { operand0, operand1 }
+        #[cfg(feature = "llvm-16-or-lower")]
+        Select(constant::Select) => visit_constant_select / constant_select { condition, true_value, false_value }
+    }
+}
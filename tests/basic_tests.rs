@@ -1,13 +1,19 @@
 use either::Either;
 use itertools::Itertools;
+use llvm_ir::constant::Float;
 use llvm_ir::function::{FunctionAttribute, ParameterAttribute};
 use llvm_ir::instruction;
-use llvm_ir::module::{Alignment, Endianness, Mangling, PointerLayout};
+use llvm_ir::module::{
+    Alignment, DataLayout, DataLayoutBuilder, Endianness, Linkage, Mangling, PointerLayout,
+    SelectionKind,
+};
 use llvm_ir::terminator;
+use llvm_ir::triple::{Arch, Environment, ObjectFormat, TargetTriple, Vendor, OS};
 use llvm_ir::types::{FPType, NamedStructDef, Typed};
 use llvm_ir::HasDebugLoc;
 use llvm_ir::{
-    Constant, ConstantRef, Instruction, IntPredicate, Module, Name, Operand, Terminator, Type,
+    Constant, ConstantRef, ExtractOptions, Instruction, IntPredicate, LinkError, Module, Name,
+    Operand, Terminator, Type,
 };
 #[cfg(feature = "llvm-16-or-greater")]
 use llvm_ir::function::MemoryEffect;
@@ -2394,7 +2400,7 @@ fn param_and_func_attributes() {
     let param = &f.parameters[0];
     assert_eq!(param.attributes.len(), 1);
     #[cfg(feature = "llvm-11-or-lower")]
-    assert_eq!(param.attributes[0], ParameterAttribute::UnknownAttribute);
+    assert_eq!(param.attributes[0], ParameterAttribute::ByVal);
     #[cfg(feature = "llvm-12-or-greater")]
     match &param.attributes[0] {
         ParameterAttribute::ByVal(ty) => match ty.as_ref() {
@@ -3083,6 +3089,150 @@ fn datalayouts() {
     );
 }
 
+#[test]
+fn target_triples() {
+    init_logging();
+    let path = llvm_bc_dir().join("hello.bc");
+    let module = Module::from_bc_path(&path).expect("Failed to parse module");
+    let triple = module.parsed_target_triple().unwrap();
+    assert_eq!(triple.arch, Arch::X86_64);
+    assert_eq!(triple.vendor, Vendor::Apple);
+    assert_eq!(triple.os, OS::MacOSX);
+    assert_eq!(triple.environment, None);
+    assert_eq!(triple.object_format, ObjectFormat::MachO);
+    assert!(triple.is_64bit());
+    assert!(triple.is_darwin());
+    assert!(!triple.is_windows());
+    assert_eq!(triple.check_data_layout(&module.data_layout), Ok(()));
+
+    let triple = TargetTriple::parse("armv7a-none-eabihf");
+    assert_eq!(triple.arch, Arch::Arm);
+    assert_eq!(triple.sub_arch.as_deref(), Some("v7a"));
+    assert_eq!(triple.vendor, Vendor::Unknown);
+    assert_eq!(triple.os, OS::None);
+    assert_eq!(triple.environment, Some(Environment::EABIHF));
+    assert!(triple.is_32bit());
+
+    let triple = TargetTriple::parse("x86_64-pc-windows-msvc");
+    assert!(triple.is_windows());
+    assert!(triple.is_msvc());
+    assert_eq!(triple.object_format, ObjectFormat::COFF);
+    let triple = TargetTriple::parse("x86_64-pc-windows-msvc-elf");
+    assert_eq!(triple.environment, Some(Environment::MSVC));
+    assert_eq!(triple.object_format, ObjectFormat::ELF);
+
+    let triple = TargetTriple::parse("wasm32-unknown-unknown");
+    assert!(triple.is_wasm());
+    assert_eq!(triple.os, OS::Unknown);
+    assert_eq!(triple.object_format, ObjectFormat::Wasm);
+    // a wasm32 triple with x86_64's data layout is inconsistent
+    assert!(triple.check_data_layout(&module.data_layout).is_err());
+
+    // vendor omitted
+    let triple = TargetTriple::parse("aarch64-linux-gnu");
+    assert_eq!(triple.arch, Arch::AArch64);
+    assert_eq!(triple.vendor, Vendor::Unknown);
+    assert_eq!(triple.os, OS::Linux);
+    assert_eq!(triple.environment, Some(Environment::GNU));
+    assert_eq!(triple.object_format, ObjectFormat::ELF);
+
+    // unrecognized vendor; the OS implies the environment
+    let triple = TargetTriple::parse("x86_64-w64-mingw32");
+    assert_eq!(triple.vendor, Vendor::Other("w64".to_owned()));
+    assert_eq!(triple.os, OS::Windows);
+    assert_eq!(triple.environment, Some(Environment::GNU));
+    assert_eq!(triple.object_format, ObjectFormat::COFF);
+    assert!(triple.is_windows());
+
+    // 32-bit pointers on a 64-bit arch
+    let triple = TargetTriple::parse("x86_64-pc-linux-gnux32");
+    assert!(triple.is_32bit());
+    let arm64_32 = TargetTriple::parse("arm64_32-apple-watchos");
+    assert_eq!(arm64_32.arch, Arch::AArch64_32);
+    assert_eq!(arm64_32.sub_arch, None);
+    assert_eq!(arm64_32.os, OS::WatchOS);
+    assert!(arm64_32.is_32bit());
+    assert_eq!(
+        TargetTriple::parse("aarch64_32-unknown-linux").arch,
+        Arch::AArch64_32
+    );
+    let x32 = Module::from_ir_str(
+        r#"target datalayout = "e-m:e-p:32:32-p270:32:32-p271:32:32-p272:64:64-i64:64-f80:128-n8:16:32:64-S128""#,
+    )
+    .expect("Failed to parse module");
+    assert_eq!(triple.check_data_layout(&x32.data_layout), Ok(()));
+    assert!(triple.check_data_layout(&module.data_layout).is_err());
+}
+
+#[test]
+fn build_datalayouts() {
+    init_logging();
+    let data_layout = DataLayoutBuilder::new()
+        .mangling(Mangling::ELF)
+        .pointer_layout(
+            1,
+            PointerLayout {
+                size: 32,
+                alignment: Alignment { abi: 32, pref: 32 },
+                index_size: 32,
+            },
+        )
+        .pointer_layout(
+            2,
+            PointerLayout {
+                size: 128,
+                alignment: Alignment { abi: 128, pref: 128 },
+                index_size: 64,
+            },
+        )
+        .int_alignment(64, Alignment { abi: 64, pref: 64 })
+        .fp_alignment(80, Alignment { abi: 128, pref: 128 })
+        .native_int_widths(vec![64, 32, 16, 8])
+        .non_integral_address_space(2)
+        .stack_alignment(128)
+        .build();
+    assert_eq!(
+        data_layout.layout_str,
+        "e-m:e-p1:32:32-p2:128:128:128:64-i64:64-f80:128-n8:16:32:64-S128-ni:2"
+    );
+    assert_eq!(data_layout.alignments.ptr_alignment(1).size, 32);
+    assert_eq!(data_layout.alignments.ptr_alignment(2).index_size, 64);
+    assert_eq!(data_layout.alignments.ptr_alignment(3).size, 64);
+    assert_eq!(
+        data_layout.alignments.explicit_fp_alignments().get(&80),
+        Some(&Alignment { abi: 128, pref: 128 })
+    );
+    // round-trips through parsing
+    assert_eq!(
+        DataLayout::parse(&data_layout.layout_str).canonical_string(),
+        data_layout.layout_str
+    );
+
+    // the per-target defaults are already in canonical form, except for
+    // riscv64's redundant "p:64:64" and, from LLVM 19, aarch64's trailing "Fn32"
+    let x86_64 = DataLayout::x86_64_linux();
+    assert_eq!(x86_64.canonical_string(), x86_64.layout_str);
+    let aarch64 = DataLayout::aarch64_linux();
+    #[cfg(feature = "llvm-18-or-lower")]
+    assert_eq!(aarch64.canonical_string(), aarch64.layout_str);
+    #[cfg(feature = "llvm-19-or-greater")]
+    assert_eq!(
+        aarch64.canonical_string(),
+        "e-m:e-p270:32:32-p271:32:32-p272:64:64-Fn32-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128"
+    );
+    let wasm32 = DataLayout::wasm32();
+    assert_eq!(wasm32.canonical_string(), wasm32.layout_str);
+    assert_eq!(wasm32.alignments.ptr_alignment(0).size, 32);
+    assert_eq!(
+        TargetTriple::parse("wasm32-unknown-unknown").check_data_layout(&wasm32),
+        Ok(())
+    );
+    assert_eq!(
+        DataLayout::riscv64_linux().canonical_string(),
+        "e-m:e-i64:64-i128:128-n64-S128"
+    );
+}
+
 #[test]
 fn throw() {
     let _ = env_logger::builder().is_test(true).try_init(); // capture log messages with test harness
@@ -3121,6 +3271,1767 @@ fn fences() {
 }
 */
 
+#[cfg(feature = "llvm-11-or-greater")]
+#[test]
+fn float_constants() -> Result<(), Box<dyn std::error::Error>> {
+    init_logging();
+    let ir = "
+        define half @half() { ret half 0xH3C00 }
+        define bfloat @bfloat() { ret bfloat 0xRC000 }
+        define fp128 @quad() { ret fp128 0xL00000000000000004000800000000000 }
+        define x86_fp80 @x86_fp80() { ret x86_fp80 0xKBFFFC000000000000000 }
+        define ppc_fp128 @ppc_fp128() { ret ppc_fp128 0xM3FF00000000000000000000000000000 }
+    ";
+    let module = Module::from_ir_str(ir)?;
+    let returned_float = |name: &str| -> Float {
+        let func = module.get_func_by_name(name).unwrap();
+        let ret: &terminator::Ret = &func.basic_blocks[0]
+            .term
+            .clone()
+            .try_into()
+            .unwrap_or_else(|_| panic!("Terminator should be a Ret"));
+        match ret.return_operand.as_ref().and_then(Operand::as_constant) {
+            Some(Constant::Float(float)) => float.clone(),
+            op => panic!("Expected a float constant, got {:?}", op),
+        }
+    };
+
+    let half = returned_float("half");
+    assert_eq!(half, Float::Half(0x3c00));
+    assert_eq!(half.to_f64(), 1.0);
+    assert_eq!(&half.to_string(), "half 0xH3C00");
+
+    let bfloat = returned_float("bfloat");
+    assert_eq!(bfloat, Float::BFloat(0xc000));
+    assert_eq!(bfloat.to_f64(), -2.0);
+    assert_eq!(&bfloat.to_string(), "bfloat 0xRC000");
+
+    let quad = returned_float("quad");
+    assert_eq!(quad, Float::Quadruple(0x4000_8000_0000_0000 << 64));
+    assert_eq!(quad.to_f64(), 3.0);
+    assert_eq!(
+        &quad.to_string(),
+        "fp128 0xL00000000000000004000800000000000"
+    );
+
+    let x86_fp80 = returned_float("x86_fp80");
+    assert_eq!(x86_fp80, Float::X86_FP80(0xbfff, 0xc000_0000_0000_0000));
+    assert_eq!(x86_fp80.to_f64(), -1.5);
+    assert_eq!(&x86_fp80.to_string(), "x86_fp80 0xKBFFFC000000000000000");
+
+    let ppc_fp128 = returned_float("ppc_fp128");
+    assert_eq!(ppc_fp128, Float::PPC_FP128(0, 0x3ff0_0000_0000_0000));
+    assert_eq!(ppc_fp128.to_f64(), 1.0);
+    assert_eq!(
+        &ppc_fp128.to_string(),
+        "ppc_fp128 0xM3FF00000000000000000000000000000"
+    );
+    Ok(())
+}
+
+#[test]
+fn blockaddress() -> Result<(), Box<dyn std::error::Error>> {
+    init_logging();
+    let ir = "
+        define i8* @f(i32) {
+            br label %named
+          named:
+            br label %2
+          2:
+            ret i8* blockaddress(@f, %2)
+        }
+    ";
+    let module = Module::from_ir_str(ir)?;
+    let func = module.get_func_by_name("f").unwrap();
+    let ret: &terminator::Ret = &func.basic_blocks[2]
+        .term
+        .clone()
+        .try_into()
+        .unwrap_or_else(|_| panic!("Terminator should be a Ret"));
+    let blockaddr = ret.return_operand.as_ref().and_then(Operand::as_constant);
+    assert_eq!(
+        blockaddr,
+        Some(&Constant::BlockAddress {
+            function: Name::from("f"),
+            block: Name::from(2),
+        })
+    );
+    assert_eq!(&blockaddr.unwrap().to_string(), "blockaddress(@f, %2)");
+    Ok(())
+}
+
+#[cfg(feature = "llvm-16-or-greater")]
+#[test]
+fn target_ext_types() -> Result<(), Box<dyn std::error::Error>> {
+    init_logging();
+    let ir = r#"
+        declare void @f(target("spirv.Image", void, 1, 0), target("aarch64.svcount"))
+    "#;
+    let module = Module::from_ir_str(ir)?;
+    let f = module.get_func_decl_by_name("f").unwrap();
+    let image = &f.parameters[0].ty;
+    assert_eq!(
+        image,
+        &module
+            .types
+            .target_ext_type("spirv.Image".into(), vec![module.types.void()], vec![1, 0])
+    );
+    assert_eq!(&image.to_string(), r#"target("spirv.Image", void, 1, 0)"#);
+    let svcount = &f.parameters[1].ty;
+    assert_eq!(
+        svcount,
+        &module
+            .types
+            .target_ext_type("aarch64.svcount".into(), vec![], vec![])
+    );
+    assert_eq!(&svcount.to_string(), r#"target("aarch64.svcount")"#);
+    Ok(())
+}
+
+#[test]
+fn landingpad_clauses() -> Result<(), Box<dyn std::error::Error>> {
+    init_logging();
+    let ir = "
+        declare i32 @__gxx_personality_v0(...)
+        declare void @g()
+        define void @f() personality i32 (...)* @__gxx_personality_v0 {
+            invoke void @g() to label %ok unwind label %lpad
+          ok:
+            ret void
+          lpad:
+            %lp = landingpad { i8*, i32 } cleanup catch i8* null filter [1 x i8*] [i8* null]
+            resume { i8*, i32 } %lp
+        }
+    ";
+    let module = Module::from_ir_str(ir)?;
+    let func = module.get_func_by_name("f").unwrap();
+    let lpad = func
+        .get_bb_by_name(&Name::from("lpad"))
+        .expect("Failed to find lpad bb");
+    let landingpad: &instruction::LandingPad = &lpad.instrs[0]
+        .clone()
+        .try_into()
+        .unwrap_or_else(|_| panic!("Expected a landingpad, got {:?}", &lpad.instrs[0]));
+    assert!(landingpad.cleanup);
+    assert_eq!(landingpad.clauses.len(), 2);
+    match &landingpad.clauses[0] {
+        instruction::LandingPadClause::Catch(c) => {
+            assert!(matches!(c.as_ref(), Constant::Null(_)))
+        },
+        clause => panic!("Expected a catch clause, got {:?}", clause),
+    }
+    match &landingpad.clauses[1] {
+        instruction::LandingPadClause::Filter(c) => match module.type_of(c).as_ref() {
+            Type::ArrayType { num_elements, .. } => assert_eq!(*num_elements, 1),
+            ty => panic!("Expected filter clause to have array type, got {:?}", ty),
+        },
+        clause => panic!("Expected a filter clause, got {:?}", clause),
+    }
+    Ok(())
+}
+
+#[test]
+fn intrinsic_calls() -> Result<(), Box<dyn std::error::Error>> {
+    use llvm_ir::instruction::{Intrinsic, IntrinsicArgs};
+    init_logging();
+    let ir = "
+        declare void @llvm.memcpy.p0i8.p0i8.i64(i8*, i8*, i64, i1)
+        declare { i32, i1 } @llvm.uadd.with.overflow.i32(i32, i32)
+        declare i64 @llvm.ctlz.i64(i64, i1)
+        declare i32 @llvm.vector.reduce.add.v4i32(<4 x i32>)
+        declare void @g(i8*)
+        define void @f(i8* %d, i8* %s, i32 %x, i64 %y, <4 x i32> %v) {
+            call void @llvm.memcpy.p0i8.p0i8.i64(i8* %d, i8* %s, i64 16, i1 false)
+            %1 = call { i32, i1 } @llvm.uadd.with.overflow.i32(i32 %x, i32 3)
+            %2 = call i64 @llvm.ctlz.i64(i64 %y, i1 true)
+            %3 = call i32 @llvm.vector.reduce.add.v4i32(<4 x i32> %v)
+            call void @g(i8* %d)
+            ret void
+        }
+    ";
+    let module = Module::from_ir_str(ir)?;
+    let func = module.get_func_by_name("f").unwrap();
+    let calls: Vec<instruction::Call> = func.basic_blocks[0]
+        .instrs
+        .iter()
+        .map(|i| i.clone().try_into().expect("Expected a call"))
+        .collect();
+
+    let memcpy = calls[0].as_intrinsic(&module).expect("Expected memcpy");
+    assert_eq!(memcpy.intrinsic, Intrinsic::Memcpy);
+    assert_eq!(memcpy.overloaded_types.len(), 3);
+    assert_eq!(memcpy.overloaded_types[2], module.types.i64());
+    match memcpy.args {
+        IntrinsicArgs::MemTransfer { dest, src, len, is_volatile } => {
+            assert_eq!(dest, &calls[0].arguments[0].0);
+            assert_eq!(src, &calls[0].arguments[1].0);
+            assert_eq!(&len.to_string(), "i64 16");
+            assert_eq!(&is_volatile.to_string(), "i1 false");
+        },
+        args => panic!("Expected MemTransfer args, got {:?}", args),
+    }
+
+    let uadd = calls[1].as_intrinsic(&module).expect("Expected uadd.with.overflow");
+    assert_eq!(uadd.intrinsic, Intrinsic::UAddWithOverflow);
+    assert_eq!(uadd.overloaded_types, vec![module.types.i32()]);
+    assert!(matches!(uadd.args, IntrinsicArgs::Arithmetic { .. }));
+
+    let ctlz = calls[2].as_intrinsic(&module).expect("Expected ctlz");
+    assert_eq!(ctlz.intrinsic, Intrinsic::Ctlz);
+    match ctlz.args {
+        IntrinsicArgs::BitCount { is_zero_poison, .. } => {
+            assert_eq!(&is_zero_poison.unwrap().to_string(), "i1 true")
+        },
+        args => panic!("Expected BitCount args, got {:?}", args),
+    }
+
+    let reduce = calls[3].as_intrinsic(&module).expect("Expected vector.reduce.add");
+    assert_eq!(reduce.intrinsic, Intrinsic::VectorReduceAdd);
+    assert_eq!(&reduce.overloaded_types[0].to_string(), "<4 x i32>");
+
+    assert!(calls[4].as_intrinsic(&module).is_none());
+    Ok(())
+}
+
+#[test]
+fn string_function_attributes() -> Result<(), Box<dyn std::error::Error>> {
+    use llvm_ir::function::{DenormalModeKind, FramePointerKind};
+    init_logging();
+    let ir = r#"
+        define void @f() #0 {
+            ret void
+        }
+        attributes #0 = { "target-cpu"="x86-64" "target-features"="+avx2,-sse4a,+sse4a" "frame-pointer"="non-leaf" "no-trapping-math"="true" "stack-probe-size"="4096" "denormal-fp-math"="preserve-sign,ieee" }
+    "#;
+    let module = Module::from_ir_str(ir)?;
+    let func = module.get_func_by_name("f").unwrap();
+    assert_eq!(func.target_cpu(), Some("x86-64"));
+    let features = func.target_features().unwrap();
+    assert!(features.is_enabled("avx2"));
+    assert!(features.is_enabled("sse4a"));
+    assert!(features.disabled.is_empty());
+    assert_eq!(func.frame_pointer(), Some(FramePointerKind::NonLeaf));
+    assert_eq!(func.no_trapping_math(), Some(true));
+    assert_eq!(func.stack_probe_size(), Some(4096));
+    let denormal = func.denormal_fp_math().unwrap();
+    assert_eq!(denormal.output, DenormalModeKind::PreserveSign);
+    assert_eq!(denormal.input, DenormalModeKind::IEEE);
+    assert_eq!(func.denormal_fp_math_f32(), None);
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "llvm-12-or-greater")]
+fn modern_attributes() -> Result<(), Box<dyn std::error::Error>> {
+    init_logging();
+    let ir = "
+        define void @f(i8* readonly %p) #0 {
+            ret void
+        }
+        attributes #0 = { mustprogress hot vscale_range(2,4) }
+    ";
+    let module = Module::from_ir_str(ir)?;
+    let func = module.get_func_by_name("f").unwrap();
+    assert!(func.function_attributes.contains(&FunctionAttribute::MustProgress));
+    assert!(func.function_attributes.contains(&FunctionAttribute::Hot));
+    assert!(func.function_attributes.contains(&FunctionAttribute::VScaleRange {
+        min: 2,
+        max: Some(4),
+    }));
+    assert_eq!(func.parameters[0].attributes, vec![ParameterAttribute::ReadOnly]);
+    Ok(())
+}
+
+#[test]
+fn effective_call_attributes() -> Result<(), Box<dyn std::error::Error>> {
+    init_logging();
+    let ir = "
+        declare noalias i8* @g(i8* nocapture dereferenceable(16) align 4, i32) nounwind
+        @h = alias i8* (i8*, i32), i8* (i8*, i32)* @g
+        define void @f(i8* %p) {
+            %1 = call i8* @h(i8* nonnull dereferenceable(8) align 8 %p, i32 0) cold
+            ret void
+        }
+    ";
+    let module = Module::from_ir_str(ir)?;
+    let func = module.get_func_by_name("f").unwrap();
+    let call: instruction::Call = func.basic_blocks[0].instrs[0].clone().try_into().unwrap();
+    assert_eq!(call.function_attributes, vec![FunctionAttribute::Cold]);
+    let fn_attrs = call.effective_function_attributes(&module);
+    assert!(fn_attrs.contains(&FunctionAttribute::Cold));
+    assert!(fn_attrs.contains(&FunctionAttribute::NoUnwind));
+    let arg_attrs = call.effective_argument_attributes(&module, 0);
+    assert!(arg_attrs.contains(&ParameterAttribute::NonNull));
+    assert!(arg_attrs.contains(&ParameterAttribute::NoCapture));
+    // the stronger of the call site's and the callee's values
+    assert!(arg_attrs.contains(&ParameterAttribute::Dereferenceable(16)));
+    assert!(arg_attrs.contains(&ParameterAttribute::Alignment(8)));
+    assert_eq!(arg_attrs.len(), 4);
+    assert!(call.effective_argument_attributes(&module, 1).is_empty());
+    assert_eq!(
+        call.effective_return_attributes(&module),
+        vec![ParameterAttribute::NoAlias]
+    );
+    Ok(())
+}
+
+#[test]
+fn comdats() {
+    init_logging();
+    let ir = r#"
+        $inline_fn = comdat any
+        $"weird name" = comdat largest
+        $shared = comdat nodeduplicate
+        $"quote\22d" = comdat any
+
+        @inline_fn.data = linkonce_odr global i32 1, comdat($inline_fn)
+        @g = global i32 2, comdat($"weird name")
+        @shared = global [6 x i8] c"comdat", comdat
+        @no_comdat = global i32 3
+
+        define linkonce_odr i32 @inline_fn() comdat {
+          ret i32 1
+        }
+
+        define i32 @f() comdat($shared) {
+          ret i32 0
+        }
+
+        define void @only_fn() comdat($"quote\22d") {
+          ret void
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    assert_eq!(
+        module.get_func_by_name("inline_fn").unwrap().comdat.as_deref(),
+        Some("inline_fn")
+    );
+    assert_eq!(
+        module.get_func_by_name("f").unwrap().comdat.as_deref(),
+        Some("shared")
+    );
+    assert_eq!(
+        module.get_func_by_name("only_fn").unwrap().comdat.as_deref(),
+        Some("quote\"d")
+    );
+    let global_comdat = |name: &str| {
+        module
+            .get_global_var_by_name(&Name::from(name))
+            .unwrap()
+            .comdat
+            .clone()
+    };
+    assert_eq!(global_comdat("inline_fn.data").as_deref(), Some("inline_fn"));
+    assert_eq!(global_comdat("g").as_deref(), Some("weird name"));
+    assert_eq!(global_comdat("shared").as_deref(), Some("shared"));
+    assert_eq!(global_comdat("no_comdat"), None);
+
+    assert_eq!(module.comdats.len(), 4);
+    assert_eq!(
+        module.get_comdat_by_name("inline_fn").unwrap().selection_kind,
+        SelectionKind::Any
+    );
+    assert_eq!(
+        module.get_comdat_by_name("weird name").unwrap().selection_kind,
+        SelectionKind::Largest
+    );
+    assert_eq!(
+        module.get_comdat_by_name("shared").unwrap().selection_kind,
+        SelectionKind::NoDuplicates
+    );
+}
+
+#[test]
+fn prefix_and_prologue_data() {
+    init_logging();
+    let ir = r#"
+        declare i32 @__gxx_personality_v0(...)
+
+        define void @prefixed() prefix i32 123 {
+          ret void
+        }
+
+        define void @prologued() prologue i8 144 {
+          ret void
+        }
+
+        define void @both() prefix <{ i8, i8 }> <{ i8 1, i8 2 }> prologue i8 144 {
+          ret void
+        }
+
+        define void @null_prefix() prefix i8* null {
+          ret void
+        }
+
+        define void @personality_only() personality i32 (...)* @__gxx_personality_v0 {
+          ret void
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+
+    let f = module.get_func_by_name("prefixed").unwrap();
+    assert_eq!(
+        f.prefix_data.as_deref(),
+        Some(&Constant::Int { bits: 32, value: 123 })
+    );
+    assert_eq!(f.prologue_data, None);
+
+    let f = module.get_func_by_name("prologued").unwrap();
+    assert_eq!(f.prefix_data, None);
+    assert_eq!(
+        f.prologue_data.as_deref(),
+        Some(&Constant::Int { bits: 8, value: 144 })
+    );
+
+    let f = module.get_func_by_name("both").unwrap();
+    match f.prefix_data.as_deref() {
+        Some(Constant::Struct { values, is_packed, .. }) => {
+            assert!(is_packed);
+            assert_eq!(values.len(), 2);
+        },
+        other => panic!("Expected a packed struct, got {:?}", other),
+    }
+    assert_eq!(
+        f.prologue_data.as_deref(),
+        Some(&Constant::Int { bits: 8, value: 144 })
+    );
+
+    let f = module.get_func_by_name("null_prefix").unwrap();
+    assert!(matches!(f.prefix_data.as_deref(), Some(Constant::Null(_))));
+    assert_eq!(f.prologue_data, None);
+
+    let f = module.get_func_by_name("personality_only").unwrap();
+    assert!(f.personality_function.is_some());
+    assert_eq!(f.prefix_data, None);
+    assert_eq!(f.prologue_data, None);
+}
+
+#[test]
+fn visitors() -> Result<(), Box<dyn std::error::Error>> {
+    use llvm_ir::visit::{walk, Visitor, VisitorMut};
+    init_logging();
+    let ir = "
+        @arr = global [4 x i32] zeroinitializer
+        @p = global i32* getelementptr ([4 x i32], [4 x i32]* @arr, i64 0, i64 1)
+        define i32 @f(i32 %x, i1 %c) {
+            %1 = add i32 %x, 1
+            %2 = add i32 %1, 1
+            br i1 %c, label %yes, label %no
+        yes:
+            %3 = load i32, i32* getelementptr ([4 x i32], [4 x i32]* @arr, i64 0, i64 1)
+            ret i32 %3
+        no:
+            ret i32 %2
+        }
+    ";
+    let mut module = Module::from_ir_str(ir)?;
+
+    #[derive(Default)]
+    struct Counter {
+        adds: usize,
+        geps: usize,
+        rets: usize,
+        constants: usize,
+    }
+    impl Visitor for Counter {
+        fn visit_add(&mut self, add: &instruction::Add) {
+            self.adds += 1;
+            walk::add(self, add);
+        }
+        fn visit_ret(&mut self, ret: &terminator::Ret) {
+            self.rets += 1;
+            walk::ret(self, ret);
+        }
+        fn visit_constant_gep(&mut self, gep: &llvm_ir::constant::GetElementPtr) {
+            self.geps += 1;
+            walk::constant_gep(self, gep);
+        }
+        fn visit_constant(&mut self, constant: &ConstantRef) {
+            self.constants += 1;
+            walk::constant(self, constant);
+        }
+    }
+    let mut counter = Counter::default();
+    counter.visit_module(&module);
+    assert_eq!(counter.adds, 2);
+    assert_eq!(counter.rets, 2);
+    assert_eq!(counter.geps, 2); // one in @p's initializer, one in the load
+    // @arr's initializer; @p's initializer, and its 3 operands, twice (in @p
+    // and in the load); and the two `i32 1`s
+    assert_eq!(counter.constants, 1 + 4 * 2 + 2);
+
+    // Replace every `i32 1` / `i64 1`, including within constant expressions,
+    // with 7 of the same width
+    struct Sevens;
+    impl VisitorMut for Sevens {
+        fn visit_constant(&mut self, constant: &mut ConstantRef) {
+            if let Constant::Int { bits, value: 1 } = constant.as_ref() {
+                *constant = ConstantRef::new(Constant::Int { bits: *bits, value: 7 });
+            } else {
+                llvm_ir::visit::walk_mut::constant(self, constant);
+            }
+        }
+    }
+    Sevens.visit_module(&mut module);
+    let func = module.get_func_by_name("f").unwrap();
+    assert_eq!(
+        func.basic_blocks[0].instrs[0].to_string(),
+        "%1 = add i32 %x, i32 7"
+    );
+    let p = module.get_global_var_by_name(&Name::from("p")).unwrap();
+    match p.initializer.as_deref() {
+        Some(Constant::GetElementPtr(gep)) => {
+            assert_eq!(
+                gep.indices[1].as_ref(),
+                &Constant::Int { bits: 64, value: 7 }
+            );
+        },
+        other => panic!("Expected a GEP, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn ir_builder() {
+    use llvm_ir::IRBuilder;
+    init_logging();
+    let mut module = Module::from_ir_str("declare void @g(i32)").expect("Failed to parse module");
+    let i32_ty = module.types.i32();
+    #[cfg(feature = "llvm-14-or-lower")]
+    let ptr_ty = module.types.pointer_to(i32_ty.clone());
+    #[cfg(feature = "llvm-15-or-greater")]
+    let ptr_ty = module.types.pointer();
+    let g_ty = module.types.func_type(module.types.void(), vec![i32_ty.clone()], false);
+    let g = Operand::ConstantOperand(ConstantRef::new(Constant::GlobalReference {
+        name: Name::from("g"),
+        ty: g_ty.clone(),
+    }));
+
+    // sums the `n` i32s at `p`, calling @g on each of them
+    let mut func = llvm_ir::Function::new("sum");
+    func.return_type = i32_ty.clone();
+    let mut builder = IRBuilder::new(&mut func, &module.types);
+    let p = builder.add_param(ptr_ty);
+    let n = builder.add_param(i32_ty.clone());
+    let entry = builder.append_block();
+    let body = builder.append_block();
+    let exit = builder.append_block();
+    builder.position_at_end(&entry);
+    builder.br(body.clone());
+    builder.position_at_end(&body);
+    let i = builder.phi(i32_ty.clone(), vec![(builder.const_int(32, 0), entry.clone())]);
+    let addr = builder.gep(i32_ty.clone(), p, vec![i.clone()]);
+    let val = builder.load(i32_ty.clone(), addr, 4);
+    assert_eq!(builder.call(g_ty, g, vec![val.clone()]), None);
+    let acc = builder.phi(i32_ty.clone(), vec![(builder.const_int(32, 0), entry)]);
+    let new_acc = builder.add(acc.clone(), val);
+    let next = builder.add(i.clone(), builder.const_int(32, 1));
+    builder.add_incoming(&i, next.clone(), body.clone());
+    builder.add_incoming(&acc, new_acc.clone(), body.clone());
+    let done = builder.icmp(IntPredicate::EQ, next, n);
+    builder.cond_br(done, exit.clone(), body.clone());
+    builder.position_at_end(&exit);
+    builder.ret(new_acc);
+
+    assert_eq!(func.parameters[0].name, Name::from(0));
+    assert_eq!(func.parameters[1].name, Name::from(1));
+    let names: Vec<&Name> = func.basic_blocks.iter().map(|bb| &bb.name).collect();
+    assert_eq!(names, vec![&Name::from(2), &Name::from(3), &Name::from(4)]);
+    let bb = func.get_bb_by_name(&body).unwrap();
+    assert_eq!(bb.instrs.len(), 8);
+    // both phis come first, even though `acc` was created after the `gep`
+    let phi: &instruction::Phi = &bb.instrs[0].clone().try_into().expect("Should be a phi");
+    assert_eq!(phi.dest, Name::from(5));
+    assert_eq!(phi.incoming_values.len(), 2);
+    assert_eq!(phi.incoming_values[1].1, Name::from(3));
+    let phi: &instruction::Phi = &bb.instrs[1].clone().try_into().expect("Should be a phi");
+    assert_eq!(phi.dest, Name::from(8));
+    let gep: &instruction::GetElementPtr = &bb.instrs[2].clone().try_into().expect("Should be a gep");
+    assert_eq!(gep.dest, Name::from(6));
+    #[cfg(feature = "llvm-14-or-greater")]
+    assert_eq!(gep.source_element_type, i32_ty);
+    let load: &instruction::Load = &bb.instrs[3].clone().try_into().expect("Should be a load");
+    #[cfg(feature = "llvm-15-or-greater")]
+    assert_eq!(load.loaded_ty, i32_ty);
+    assert_eq!(module.types.type_of(load), i32_ty);
+    let call: &instruction::Call = &bb.instrs[4].clone().try_into().expect("Should be a call");
+    assert_eq!(call.dest, None);
+    assert_eq!(bb.instrs[5].to_string(), "%9 = add i32 %8, i32 %7");
+    match &bb.term {
+        Terminator::CondBr(condbr) => {
+            assert_eq!(condbr.true_dest, Name::from(4));
+            assert_eq!(condbr.false_dest, Name::from(3));
+        },
+        term => panic!("Expected a CondBr, got {:?}", term),
+    }
+    let exit = func.get_bb_by_name(&exit).unwrap();
+    assert_eq!(exit.term.to_string(), "ret i32 %9");
+    module.functions.push(func);
+    assert_eq!(module.verify(), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "IRBuilder::add: operands have different types")]
+fn ir_builder_type_mismatch() {
+    use llvm_ir::IRBuilder;
+    let module = Module::from_ir_str("").expect("Failed to parse module");
+    let mut func = llvm_ir::Function::new("f");
+    let mut builder = IRBuilder::new(&mut func, &module.types);
+    let x = builder.add_param(module.types.i32());
+    let y = builder.add_param(module.types.i64());
+    let entry = builder.append_block();
+    builder.position_at_end(&entry);
+    builder.add(x, y);
+}
+
+#[test]
+#[should_panic(expected = "IRBuilder::bitcast: can't cast i32 to i64")]
+fn ir_builder_bitcast_size_mismatch() {
+    use llvm_ir::IRBuilder;
+    let module = Module::from_ir_str("").expect("Failed to parse module");
+    let mut func = llvm_ir::Function::new("f");
+    let mut builder = IRBuilder::new(&mut func, &module.types);
+    let x = builder.add_param(module.types.i32());
+    let entry = builder.append_block();
+    builder.position_at_end(&entry);
+    builder.bitcast(x, module.types.i64());
+}
+
+#[test]
+fn verify() {
+    init_logging();
+    let ir = r#"
+        %pair = type { i32, i64 }
+
+        declare void @g(i32)
+
+        define i64 @f(%pair* %p, i32 %n) {
+        entry:
+            %cmp = icmp sgt i32 %n, 0
+            br i1 %cmp, label %loop, label %exit
+        loop:
+            %i = phi i32 [ 0, %entry ], [ %next, %loop ]
+            call void @g(i32 %i)
+            %next = add i32 %i, 1
+            %done = icmp eq i32 %next, %n
+            br i1 %done, label %exit, label %loop
+        exit:
+            %addr = getelementptr %pair, %pair* %p, i64 0, i32 1
+            %v = load i64, i64* %addr
+            %agg = load %pair, %pair* %p
+            %w = extractvalue %pair %agg, 1
+            %sum = add i64 %v, %w
+            switch i32 %n, label %ret [ i32 1, label %ret
+                                        i32 2, label %ret ]
+        ret:
+            %r = phi i64 [ %sum, %exit ], [ %sum, %exit ], [ %sum, %exit ]
+            ret i64 %r
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    assert_eq!(module.verify(), Ok(()));
+
+    let messages = |module: &Module| -> Vec<String> {
+        module
+            .verify()
+            .expect_err("Expected the module to fail verification")
+            .into_iter()
+            .map(|e| e.message)
+            .collect()
+    };
+    fn loop_instr(module: &mut Module, index: usize) -> &mut Instruction {
+        &mut module.functions[0].basic_blocks[1].instrs[index]
+    }
+
+    // use of an undefined name
+    let mut broken = module.clone();
+    let i32_ty = broken.types.i32();
+    if let Instruction::Add(add) = loop_instr(&mut broken, 2) {
+        add.operand0 = Operand::LocalOperand {
+            name: Name::from("nope"),
+            ty: i32_ty,
+        };
+    }
+    assert_eq!(messages(&broken), vec!["%nope is used but never defined"]);
+
+    // use before definition, in the same block
+    let mut broken = module.clone();
+    let next = loop_instr(&mut broken, 2).clone();
+    broken.functions[0].basic_blocks[1].instrs.remove(2);
+    broken.functions[0].basic_blocks[1].instrs.push(next);
+    assert_eq!(
+        messages(&broken),
+        vec!["the definition of %next does not dominate this use"]
+    );
+
+    // operands of different types
+    let mut broken = module.clone();
+    if let Instruction::Add(add) = loop_instr(&mut broken, 2) {
+        add.operand1 = Operand::ConstantOperand(ConstantRef::new(Constant::Int {
+            bits: 64,
+            value: 1,
+        }));
+    }
+    assert_eq!(
+        messages(&broken),
+        vec!["operands have different types: i32 and i64"]
+    );
+
+    // call not matching the callee's signature
+    let mut broken = module.clone();
+    if let Instruction::Call(call) = loop_instr(&mut broken, 1) {
+        call.arguments.clear();
+    }
+    let errors = broken.verify().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].function, "f");
+    assert_eq!(errors[0].block, Some(Name::from("loop")));
+    assert!(errors[0].message.contains("called with 0 arguments"));
+
+    // phi missing an incoming block, and a branch to a nonexistent block
+    let mut broken = module.clone();
+    let f = &mut broken.functions[0];
+    if let Instruction::Phi(phi) = &mut f.basic_blocks[1].instrs[0] {
+        phi.incoming_values.pop();
+    }
+    if let Terminator::CondBr(condbr) = &mut f.basic_blocks[1].term {
+        condbr.true_dest = Name::from("nowhere");
+    }
+    let errors = broken.verify().unwrap_err();
+    assert_eq!(
+        errors.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(),
+        vec![
+            "phi incoming blocks [%entry] don't match the block's predecessors [%entry, %loop]",
+            "branch target %nowhere is not a block of the function",
+        ]
+    );
+    assert_eq!(
+        errors[1].to_string(),
+        "in function \"f\", block %loop, at `br i1 %done, label %nowhere, label %loop`: branch target %nowhere is not a block of the function",
+    );
+
+    // casts, insertelement, freeze and callbr
+    let ir = r#"
+        define void @h(float %x, <2 x i32> %v, i32 %n) {
+        entry:
+            %t = fptrunc float %x to half
+            %e = fpext float %x to double
+            %u = fptoui float %x to i32
+            %s = sitofp i32 %n to float
+            %c = addrspacecast i8* null to i8 addrspace(1)*
+            %bc = bitcast i32 %n to float
+            %ins = insertelement <2 x i32> %v, i32 %n, i32 0
+            %fr = freeze i32 %n
+            callbr void asm "", "r,X"(i32 %n, i8* blockaddress(@h, %fail)) to label %normal [label %fail]
+        normal:
+            ret void
+        fail:
+            ret void
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    assert_eq!(module.verify(), Ok(()));
+    let mut broken = module.clone();
+    let double_ty = broken.types.fp(FPType::Double);
+    let half_ty = broken.types.fp(FPType::Half);
+    let i64_ty = broken.types.i64();
+    let i64_op = Operand::ConstantOperand(ConstantRef::new(Constant::Int {
+        bits: 64,
+        value: 0,
+    }));
+    let void_op = Operand::ConstantOperand(ConstantRef::new(Constant::Undef(broken.types.void())));
+    let x = Operand::LocalOperand {
+        name: Name::from("x"),
+        ty: broken.types.single(),
+    };
+    let types = &broken.types;
+    let entry = &mut broken.functions[0].basic_blocks[0];
+    for inst in &mut entry.instrs {
+        match inst {
+            Instruction::FPTrunc(i) => i.to_type = double_ty.clone(),
+            Instruction::FPExt(i) => i.to_type = half_ty.clone(),
+            Instruction::FPToUI(i) => i.to_type = double_ty.clone(),
+            Instruction::SIToFP(i) => i.operand = x.clone(),
+            Instruction::AddrSpaceCast(i) => i.to_type = types.type_of(&i.operand),
+            Instruction::BitCast(i) => i.to_type = i64_ty.clone(),
+            Instruction::InsertElement(i) => i.element = i64_op.clone(),
+            #[cfg(feature = "llvm-10-or-greater")]
+            Instruction::Freeze(i) => i.operand = void_op.clone(),
+            _ => {},
+        }
+    }
+    if let Terminator::CallBr(callbr) = &mut entry.term {
+        callbr.arguments.pop();
+    }
+    assert_eq!(
+        messages(&broken),
+        vec![
+            "can't cast float to double",
+            "can't cast float to half",
+            "can't cast float to double",
+            "can't cast float to float",
+            "can't cast i8* to i8*",
+            "can't cast i32 to i64",
+            "expected an element of type i32, got i64",
+            "expected a first-class operand, got void",
+            "function of type void (i32, i8*) called with 1 arguments",
+        ]
+    );
+
+    // the result of an invoke is only available along its normal edge
+    let ir = r#"
+        declare i32 @may_throw()
+        declare i32 @__gxx_personality_v0(...)
+
+        define i32 @inv() personality i32 (...)* @__gxx_personality_v0 {
+        entry:
+            %r = invoke i32 @may_throw() to label %ok unwind label %lpad
+        ok:
+            br label %exit
+        lpad:
+            %lp = landingpad { i8*, i32 } cleanup
+            br label %exit
+        exit:
+            %p = phi i32 [ %r, %ok ], [ 0, %lpad ]
+            ret i32 %p
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    assert_eq!(module.verify(), Ok(()));
+    let mut broken = module.clone();
+    let r = Operand::LocalOperand {
+        name: Name::from("r"),
+        ty: broken.types.i32(),
+    };
+    match &mut broken.functions[0].basic_blocks[3].instrs[0] {
+        Instruction::Phi(phi) => phi.incoming_values[1].0 = r,
+        inst => panic!("Expected a phi, got {}", inst),
+    }
+    assert_eq!(
+        messages(&broken),
+        vec!["the definition of %r does not dominate this use"]
+    );
+}
+
+#[test]
+fn function_rewriting() {
+    init_logging();
+    let ir = r#"
+        define i32 @f(i32 %x, i1 %c) {
+        entry:
+            %a = add i32 %x, 1
+            %b = mul i32 %a, 2
+            br i1 %c, label %left, label %join
+        left:
+            br label %join
+        dead:
+            br label %join
+        join:
+            %p = phi i32 [ %b, %entry ], [ %a, %left ], [ 0, %dead ]
+            ret i32 %p
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let five = Operand::ConstantOperand(ConstantRef::new(Constant::Int {
+        bits: 32,
+        value: 5,
+    }));
+    let phi = |module: &Module| -> instruction::Phi {
+        let join = module.functions[0].get_bb_by_name(&Name::from("join")).unwrap();
+        join.instrs[0].clone().try_into().expect("Should be a phi")
+    };
+    let block_names = |module: &Module| -> Vec<String> {
+        module.functions[0]
+            .basic_blocks
+            .iter()
+            .map(|bb| bb.name.to_string())
+            .collect()
+    };
+
+    let f = &mut module.functions[0];
+    f.remove_block(&Name::from("dead"));
+    f.replace_all_uses(&Name::from("a"), five.clone());
+    assert!(!f.has_uses(&Name::from("a")));
+    f.remove_instruction(&Name::from("entry"), 0);
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(
+        phi(&module).incoming_values,
+        vec![
+            (
+                Operand::LocalOperand {
+                    name: Name::from("b"),
+                    ty: module.types.i32()
+                },
+                Name::from("entry")
+            ),
+            (five.clone(), Name::from("left")),
+        ]
+    );
+
+    let f = &mut module.functions[0];
+    f.split_block_at(&Name::from("entry"), 1, Name::from("entry.split"));
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(block_names(&module), vec!["%entry", "%entry.split", "%left", "%join"]);
+    assert_eq!(phi(&module).incoming_values[0].1, Name::from("entry.split"));
+
+    let f = &mut module.functions[0];
+    f.merge_blocks(&Name::from("entry"), &Name::from("entry.split"));
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(block_names(&module), vec!["%entry", "%left", "%join"]);
+    assert_eq!(phi(&module).incoming_values[0].1, Name::from("entry"));
+
+    // now both edges out of %entry go to %left, which has two predecessors
+    let f = &mut module.functions[0];
+    f.redirect_edge(&Name::from("entry"), &Name::from("join"), &Name::from("left"));
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(
+        phi(&module).incoming_values,
+        vec![(five.clone(), Name::from("left"))]
+    );
+
+    let f = &mut module.functions[0];
+    f.merge_blocks(&Name::from("left"), &Name::from("join"));
+    assert_eq!(module.verify(), Ok(()));
+    let left = module.functions[0].get_bb_by_name(&Name::from("left")).unwrap();
+    assert_eq!(left.term.to_string(), "ret i32 5");
+
+    // redirecting an edge to a block with phis, which `from` isn't yet a
+    // predecessor of
+    let ir = r#"
+        define i32 @g(i1 %c, i1 %d) {
+        a:
+            br i1 %c, label %join, label %b
+        b:
+            br i1 %d, label %exit, label %other
+        other:
+            br label %join
+        exit:
+            ret i32 0
+        join:
+            %p = phi i32 [ 1, %a ], [ 2, %other ]
+            ret i32 %p
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let three = Operand::ConstantOperand(ConstantRef::new(Constant::Int {
+        bits: 32,
+        value: 3,
+    }));
+    let f = &mut module.functions[0];
+    let join = f
+        .basic_blocks
+        .iter_mut()
+        .find(|bb| bb.name == Name::from("join"))
+        .unwrap();
+    match &mut join.instrs[0] {
+        Instruction::Phi(phi) => phi.incoming_values.push((three, Name::from("b"))),
+        inst => panic!("Expected a phi, got {}", inst),
+    }
+    f.redirect_edge(&Name::from("b"), &Name::from("exit"), &Name::from("join"));
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(phi(&module).incoming_values.len(), 3);
+
+    // removing an unreachable block whose value feeds a phi
+    let ir = r#"
+        define i32 @h(i32 %x) {
+        entry:
+            br label %join
+        dead:
+            %d = add i32 %x, 1
+            br label %join
+        join:
+            %p = phi i32 [ %x, %entry ], [ %d, %dead ]
+            ret i32 %p
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let dead = module.functions[0].remove_block(&Name::from("dead"));
+    assert_eq!(dead.instrs.len(), 1);
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(phi(&module).incoming_values.len(), 1);
+}
+
+#[test]
+fn renumbering() {
+    init_logging();
+    let ir = r#"
+        define i32 @f(i32 %0) {
+            %2 = add i32 %0, 1
+            %3 = add i32 %2, 1
+            br label %4
+        4:
+            %5 = phi i32 [ %3, %1 ]
+            %named = mul i32 %5, %5
+            ret i32 %named
+        }
+
+        define i32 @g(i32 %x) {
+        entry:
+            %y = add i32 %x, 1
+            br label %exit
+        exit:
+            %z = phi i32 [ %y, %entry ]
+            %w = mul i32 %z, %z
+            ret i32 %w
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+
+    let f = &mut module.functions[0];
+    let two = Operand::LocalOperand {
+        name: Name::from(2),
+        ty: module.types.i32(),
+    };
+    f.replace_all_uses(&Name::from(3), two);
+    f.remove_instruction(&Name::from(1), 1);
+    f.renumber();
+    assert_eq!(module.verify(), Ok(()));
+    let f = &module.functions[0];
+    let names: Vec<String> = f
+        .basic_blocks
+        .iter()
+        .map(|bb| bb.name.to_string())
+        .collect();
+    assert_eq!(names, vec!["%1", "%3"]);
+    assert_eq!(f.basic_blocks[0].term.to_string(), "br label %3");
+    assert_eq!(
+        f.basic_blocks[1].instrs[0].to_string(),
+        "%4 = phi i32 [ i32 %2, %1 ]"
+    );
+    assert_eq!(
+        f.basic_blocks[1].instrs[1].to_string(),
+        "%named = mul i32 %4, i32 %4"
+    );
+
+    // @f and @g are the same, up to the names of their locals
+    let mut f = module.functions[0].clone();
+    let mut g = module.functions[1].clone();
+    assert_ne!(f.basic_blocks, g.basic_blocks);
+    f.canonicalize_names();
+    g.canonicalize_names();
+    assert_eq!(f.parameters, g.parameters);
+    assert_eq!(f.basic_blocks, g.basic_blocks);
+    assert_eq!(g.basic_blocks[1].term.to_string(), "ret i32 %5");
+
+    // `Module::renumber()` also updates `blockaddress`es outside the function
+    let ir = r#"
+        @tbl = internal global [1 x i8*] [i8* blockaddress(@h, %3)]
+
+        define void @h(i32 %0) {
+            %2 = add i32 %0, 1
+            br label %3
+        3:
+            ret void
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    module.functions[0].remove_instruction(&Name::from(1), 0);
+    module.renumber();
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(module.functions[0].basic_blocks[1].name, Name::from(2));
+    let tbl = module.get_global_var_by_name(&Name::from("tbl")).unwrap();
+    let initializer = tbl.initializer.as_ref().unwrap().to_string();
+    assert!(initializer.contains("blockaddress(@h, %2)"), "{}", initializer);
+}
+
+#[test]
+fn link_modules() {
+    init_logging();
+    let first = r#"
+        %struct.S = type opaque
+
+        @arr = appending global [1 x i32] [i32 1]
+        @s = external global %struct.S
+
+        declare i32 @g(%struct.S*)
+
+        define internal i32 @helper() {
+            ret i32 1
+        }
+
+        define weak i32 @w() {
+            ret i32 10
+        }
+
+        define i32 @f(%struct.S* %s) {
+            %a = call i32 @g(%struct.S* %s)
+            %b = call i32 @helper()
+            %c = add i32 %a, %b
+            ret i32 %c
+        }
+    "#;
+    let second = r#"
+        %struct.S = type { i32 }
+
+        @arr = appending global [2 x i32] [i32 2, i32 3]
+        @s = global %struct.S { i32 5 }
+
+        define internal i32 @helper() {
+            ret i32 2
+        }
+
+        define weak i32 @w() {
+            ret i32 20
+        }
+
+        define i32 @g(%struct.S* %s) {
+            %v = call i32 @helper()
+            ret i32 %v
+        }
+    "#;
+    let parse = |ir| Module::from_ir_str(ir).expect("Failed to parse module");
+    let linked = Module::link(parse(first), parse(second)).expect("Failed to link");
+    assert_eq!(linked.verify(), Ok(()));
+    let func_names: Vec<&str> = linked.functions.iter().map(|f| f.name.as_str()).collect();
+    assert_eq!(func_names, vec!["helper", "w", "f", "helper.1", "g"]);
+    assert!(linked.func_declarations.is_empty());
+
+    // the weak definition from the first module is kept
+    let w = linked.get_func_by_name("w").unwrap();
+    assert_eq!(
+        w.basic_blocks[0].term,
+        Terminator::Ret(terminator::Ret {
+            return_operand: Some(Operand::ConstantOperand(ConstantRef::new(Constant::Int {
+                bits: 32,
+                value: 10
+            }))),
+            debugloc: None,
+        })
+    );
+    // the second module's `helper` is renamed, along with the call to it
+    let g = linked.get_func_by_name("g").unwrap();
+    let call: instruction::Call = g.basic_blocks[0].instrs[0].clone().try_into().unwrap();
+    assert_eq!(
+        call.function.clone().right().map(|op| op.to_string()),
+        Some("@helper.1".to_owned())
+    );
+
+    // the declaration of `s` is resolved against the definition
+    let s = linked.get_global_var_by_name(&Name::from("s")).unwrap();
+    assert!(s.initializer.is_some());
+    let arr = linked.get_global_var_by_name(&Name::from("arr")).unwrap();
+    assert_eq!(arr.value_type, linked.types.array_of(linked.types.i32(), 3));
+    match linked.types.named_struct_def("struct.S") {
+        Some(NamedStructDef::Defined(ty)) => {
+            assert_eq!(ty, &linked.types.struct_of(vec![linked.types.i32()], false))
+        },
+        def => panic!("Expected struct.S to be defined, got {:?}", def),
+    }
+
+    // two strong definitions of the same function conflict
+    let err = Module::link(parse(second), parse(second)).err();
+    assert_eq!(
+        err,
+        Some(LinkError::ConflictingDefinitions {
+            name: Name::from("g")
+        })
+    );
+
+    // the larger of two `common` definitions is kept, and a strong definition
+    // beats a `common` one, whatever their types
+    let first = r#"
+        @x = common global [4 x i8] zeroinitializer, align 4
+        @y = common global [8 x i8] zeroinitializer, align 1
+    "#;
+    let second = r#"
+        @x = common global [8 x i8] zeroinitializer, align 1
+        @y = global i32 7, align 4
+    "#;
+    let linked = Module::link(parse(first), parse(second)).expect("Failed to link");
+    assert_eq!(linked.verify(), Ok(()));
+    assert_eq!(linked.global_vars.len(), 2);
+    let x = linked.get_global_var_by_name(&Name::from("x")).unwrap();
+    assert_eq!(x.linkage, Linkage::Common);
+    assert_eq!(x.value_type, linked.types.array_of(linked.types.i8(), 8));
+    assert_eq!(x.alignment, 4);
+    let y = linked.get_global_var_by_name(&Name::from("y")).unwrap();
+    assert_eq!(y.linkage, Linkage::External);
+    assert_eq!(y.value_type, linked.types.i32());
+
+    // a struct defined differently in the second module is renamed there,
+    // along with the structs containing it
+    let first = r#"
+        %struct.S = type { i32 }
+        %struct.T = type { %struct.S }
+
+        @a = global %struct.T zeroinitializer
+    "#;
+    let second = r#"
+        %struct.S = type { i64 }
+        %struct.T = type { %struct.S }
+
+        @b = global %struct.T { %struct.S { i64 1 } }
+
+        define i64 @get(%struct.T* %t) {
+            %p = getelementptr %struct.T, %struct.T* %t, i32 0, i32 0, i32 0
+            %v = load i64, i64* %p
+            ret i64 %v
+        }
+    "#;
+    let linked = Module::link(parse(first), parse(second)).expect("Failed to link");
+    assert_eq!(linked.verify(), Ok(()));
+    let def = |name| match linked.types.named_struct_def(name) {
+        Some(NamedStructDef::Defined(ty)) => ty.clone(),
+        def => panic!("Expected {} to be defined, got {:?}", name, def),
+    };
+    assert_eq!(
+        def("struct.S"),
+        linked.types.struct_of(vec![linked.types.i32()], false)
+    );
+    assert_eq!(
+        def("struct.S.0"),
+        linked.types.struct_of(vec![linked.types.i64()], false)
+    );
+    assert_eq!(
+        def("struct.T.0"),
+        linked
+            .types
+            .struct_of(vec![linked.types.named_struct("struct.S.0")], false)
+    );
+    let a = linked.get_global_var_by_name(&Name::from("a")).unwrap();
+    assert_eq!(a.value_type, linked.types.named_struct("struct.T"));
+    let b = linked.get_global_var_by_name(&Name::from("b")).unwrap();
+    assert_eq!(b.value_type, linked.types.named_struct("struct.T.0"));
+    let get = linked.get_func_by_name("get").unwrap();
+    let gep: instruction::GetElementPtr = get.basic_blocks[0].instrs[0].clone().try_into().unwrap();
+    #[cfg(feature = "llvm-14-or-greater")]
+    assert_eq!(
+        gep.source_element_type,
+        linked.types.named_struct("struct.T.0")
+    );
+    #[cfg(feature = "llvm-14-or-lower")]
+    assert_eq!(get.parameters[0].ty.to_string(), "%struct.T.0*");
+}
+
+#[test]
+fn extract_functions() {
+    init_logging();
+    let ir = r#"
+        target triple = "x86_64-unknown-linux-gnu"
+
+        %pair = type { i32, i32 }
+        %other = type { i64 }
+
+        @p = global %pair { i32 1, i32 2 }
+        @counter = global i32 0
+        @unused = global %other zeroinitializer
+
+        declare void @ext(i32)
+
+        define internal i32 @h(i32 %x) {
+            %c = load i32, i32* @counter
+            %r = add i32 %x, %c
+            ret i32 %r
+        }
+
+        define i32 @f() {
+            %addr = getelementptr %pair, %pair* @p, i32 0, i32 1
+            %v = load i32, i32* %addr
+            %w = call i32 @h(i32 %v)
+            call void @ext(i32 %w)
+            ret i32 %w
+        }
+
+        define void @g() {
+            ret void
+        }
+
+        define i32 @second(%pair %p) {
+            %b = extractvalue %pair %p, 1
+            ret i32 %b
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+
+    let extracted = module.extract(&["f"], ExtractOptions::default());
+    assert_eq!(extracted.verify(), Ok(()));
+    assert_eq!(extracted.target_triple, module.target_triple);
+    let func_names: Vec<&str> = extracted
+        .functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(func_names, vec!["f"]);
+    let decl_names: Vec<&str> = extracted
+        .func_declarations
+        .iter()
+        .map(|d| d.name.as_str())
+        .collect();
+    assert_eq!(decl_names, vec!["ext", "h"]);
+    assert_eq!(
+        extracted.get_func_decl_by_name("h").unwrap().linkage,
+        Linkage::External
+    );
+    let global_names: Vec<&Name> = extracted.global_vars.iter().map(|g| &g.name).collect();
+    assert_eq!(global_names, vec![&Name::from("p")]);
+    assert!(extracted.types.named_struct_def("pair").is_some());
+    assert!(extracted.types.named_struct_def("other").is_none());
+
+    let options = ExtractOptions { copy_callees: true };
+    let extracted = module.extract(&["f"], options);
+    assert_eq!(extracted.verify(), Ok(()));
+    let func_names: Vec<&str> = extracted
+        .functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(func_names, vec!["h", "f"]);
+    let decl_names: Vec<&str> = extracted
+        .func_declarations
+        .iter()
+        .map(|d| d.name.as_str())
+        .collect();
+    assert_eq!(decl_names, vec!["ext"]);
+    let global_names: Vec<&Name> = extracted.global_vars.iter().map(|g| &g.name).collect();
+    assert_eq!(global_names, vec![&Name::from("p"), &Name::from("counter")]);
+
+    let extracted = module.extract(&["second"], ExtractOptions::default());
+    assert_eq!(extracted.verify(), Ok(()));
+    assert!(extracted.global_vars.is_empty());
+    assert!(extracted.types.named_struct_def("pair").is_some());
+
+    // a `blockaddress` refers to a block, so the function must be defined
+    let ir = r#"
+        define void @jumps() {
+        entry:
+            br label %dest
+        dest:
+            ret void
+        }
+
+        define i8* @address() {
+            ret i8* blockaddress(@jumps, %dest)
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let extracted = module.extract(&["address"], ExtractOptions::default());
+    assert_eq!(extracted.verify(), Ok(()));
+    let func_names: Vec<&str> = extracted
+        .functions
+        .iter()
+        .map(|f| f.name.as_str())
+        .collect();
+    assert_eq!(func_names, vec!["jumps", "address"]);
+    assert!(extracted.func_declarations.is_empty());
+}
+
+#[test]
+fn reduce_module() {
+    init_logging();
+    let ir = r#"
+        @g = global i32 0
+
+        define i32 @unrelated(i32 %x) {
+            %y = add i32 %x, 1
+            store i32 %y, i32* @g
+            ret i32 %y
+        }
+
+        define i32 @target(i32 %a, i32 %b) {
+        entry:
+            %c = icmp sgt i32 %a, %b
+            br i1 %c, label %then, label %else
+        then:
+            %m = mul i32 %a, %b
+            br label %exit
+        else:
+            %s = sub i32 %a, %b
+            br label %exit
+        exit:
+            %r = phi i32 [ %m, %then ], [ %s, %else ]
+            %u = call i32 @unrelated(i32 %r)
+            ret i32 %u
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let has_mul = |module: &Module| {
+        module
+            .functions
+            .iter()
+            .flat_map(|f| &f.basic_blocks)
+            .flat_map(|bb| &bb.instrs)
+            .any(|inst| matches!(inst, Instruction::Mul(_)))
+    };
+    let reduced = module.reduce(has_mul);
+    assert_eq!(reduced.verify(), Ok(()));
+    assert_eq!(reduced.functions.len(), 1);
+    assert!(reduced.func_declarations.is_empty());
+    assert!(reduced.global_vars.is_empty());
+    let func = &reduced.functions[0];
+    assert_eq!(func.name, "target");
+    assert!(func.parameters.is_empty());
+    let instrs: Vec<&Instruction> = func.basic_blocks.iter().flat_map(|bb| &bb.instrs).collect();
+    assert_eq!(instrs.len(), 1);
+    assert!(matches!(instrs[0], Instruction::Mul(_)));
+
+    let ir = r#"
+        %struct.P = type { i32, i64 }
+
+        define i32 @f(%struct.P %p) {
+            %a = extractvalue %struct.P %p, 0
+            %b = add i32 %a, 1
+            ret i32 %b
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let keeps_param = |module: &Module| match module.get_func_by_name("f") {
+        Some(f) => f.parameters.len() == 1,
+        None => false,
+    };
+    let reduced = module.reduce(keeps_param);
+    assert_eq!(reduced.verify(), Ok(()));
+    let func = reduced.get_func_by_name("f").unwrap();
+    assert!(func.basic_blocks.iter().all(|bb| bb.instrs.is_empty()));
+}
+
+#[test]
+fn pass_manager() {
+    use llvm_ir::pass::{AnalysisManager, FunctionAnalysis, FunctionPass, PassManager};
+    use llvm_ir::types::Types;
+    use llvm_ir::Function;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct InstructionCount;
+    impl FunctionAnalysis for InstructionCount {
+        type Result = usize;
+        fn run(function: &Function, _: &Types, _: &mut AnalysisManager) -> usize {
+            function.basic_blocks.iter().map(|bb| bb.instrs.len()).sum()
+        }
+    }
+
+    /// Records the instruction count of each function
+    struct Observe(Rc<RefCell<Vec<(String, usize)>>>);
+    impl FunctionPass for Observe {
+        fn run_on_function(
+            &mut self,
+            function: &mut Function,
+            types: &Types,
+            analyses: &mut AnalysisManager,
+        ) -> bool {
+            let count = analyses.get::<InstructionCount>(function, types);
+            self.0.borrow_mut().push((function.name.clone(), *count));
+            false
+        }
+    }
+
+    /// Deletes instructions whose results are unused
+    struct DeleteUnused;
+    impl FunctionPass for DeleteUnused {
+        fn name(&self) -> &str {
+            "delete-unused"
+        }
+
+        fn run_on_function(
+            &mut self,
+            function: &mut Function,
+            _: &Types,
+            _: &mut AnalysisManager,
+        ) -> bool {
+            let mut changed = false;
+            for b in 0 .. function.basic_blocks.len() {
+                for i in (0 .. function.basic_blocks[b].instrs.len()).rev() {
+                    let unused = match function.basic_blocks[b].instrs[i].try_get_result() {
+                        Some(name) => !function.has_uses(name),
+                        None => false,
+                    };
+                    if unused {
+                        let name = function.basic_blocks[b].name.clone();
+                        function.remove_instruction(&name, i);
+                        changed = true;
+                    }
+                }
+            }
+            changed
+        }
+    }
+
+    init_logging();
+    let ir = r#"
+        define i32 @f(i32 %x) {
+            %unused = add i32 %x, 1
+            %y = mul i32 %x, 2
+            ret i32 %y
+        }
+
+        define i32 @g(i32 %x) {
+            %y = mul i32 %x, 3
+            ret i32 %y
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let seen = Rc::new(RefCell::new(vec![]));
+    let mut passes = PassManager::new();
+    passes.add_function_pass(Observe(Rc::clone(&seen)));
+    passes.add_function_pass(DeleteUnused);
+    passes.add_function_pass(Observe(Rc::clone(&seen)));
+    assert!(passes.run(&mut module));
+
+    let expected = [("f", 2), ("g", 1), ("f", 1), ("g", 1)];
+    let expected: Vec<(String, usize)> = expected.iter().map(|&(f, n)| (f.to_owned(), n)).collect();
+    assert_eq!(*seen.borrow(), expected);
+    let delete_unused = &passes.statistics()[1];
+    assert_eq!(delete_unused.name, "delete-unused");
+    assert_eq!(delete_unused.runs, 2);
+    assert_eq!(delete_unused.changes, 1);
+    // only `f` had to be reanalyzed after `delete-unused`
+    let analysis_stats: Vec<_> = passes.analyses().statistics().cloned().collect();
+    assert_eq!(analysis_stats.len(), 1);
+    assert_eq!(analysis_stats[0].computations, 3);
+    assert_eq!(analysis_stats[0].cache_hits, 1);
+
+    // results cached for one module's `f` aren't reused for another's
+    let ir = r#"
+        define i32 @f(i32 %x) {
+            ret i32 %x
+        }
+    "#;
+    let mut other = Module::from_ir_str(ir).expect("Failed to parse module");
+    seen.borrow_mut().clear();
+    assert!(!passes.run(&mut other));
+    let expected = vec![("f".to_owned(), 0), ("f".to_owned(), 0)];
+    assert_eq!(*seen.borrow(), expected);
+}
+
+#[test]
+fn builtin_analyses() {
+    use llvm_ir::cfg::{ControlFlowGraph, DominatorTree};
+    use llvm_ir::pass::AnalysisManager;
+    use std::collections::BTreeSet;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<AnalysisManager>();
+
+    init_logging();
+    let ir = r#"
+        define i32 @f(i1 %c) {
+        entry:
+            br i1 %c, label %then, label %else
+        then:
+            br label %end
+        else:
+            br label %end
+        end:
+            %r = phi i32 [ 1, %then ], [ 2, %else ]
+            ret i32 %r
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let func = &module.functions[0];
+    let mut analyses = AnalysisManager::new();
+    let domtree = analyses.get::<DominatorTree>(func, &module.types);
+    let cfg = analyses
+        .get_cached::<ControlFlowGraph>("f")
+        .expect("the dominator tree is computed from the cached control-flow graph");
+    let end = cfg.index_of(&Name::from("end")).unwrap();
+    assert_eq!(end, 3);
+    assert_eq!(cfg.succs(0), &[1, 2]);
+    assert_eq!(cfg.preds(end), &[1, 2]);
+    assert_eq!(cfg.reverse_postorder()[0], 0);
+    assert!(domtree.dominates(0, end));
+    assert!(!domtree.dominates(1, end));
+    assert_eq!(domtree.children()[0], vec![1, 2, 3]);
+    let frontier: BTreeSet<usize> = [end].iter().copied().collect();
+    assert_eq!(domtree.dominance_frontiers(&cfg)[1], frontier);
+    analyses.get::<DominatorTree>(func, &module.types);
+    for stats in analyses.statistics() {
+        assert_eq!(stats.computations, 1, "{}", stats.name);
+    }
+    let cache_hits: usize = analyses.statistics().map(|stats| stats.cache_hits).sum();
+    assert_eq!(cache_hits, 1);
+}
+
+#[test]
+fn mem2reg() {
+    use llvm_ir::cfg::DominatorTree;
+    use llvm_ir::mem2reg::Mem2Reg;
+    use llvm_ir::pass::PassManager;
+
+    init_logging();
+    let ir = r#"
+        declare void @use(i32*)
+
+        define i32 @max(i32 %a, i32 %b) {
+        entry:
+            %retval = alloca i32
+            %a.addr = alloca i32
+            %b.addr = alloca i32
+            %escapes = alloca i32
+            store i32 %a, i32* %a.addr
+            store i32 %b, i32* %b.addr
+            call void @use(i32* %escapes)
+            %x = load i32, i32* %a.addr
+            %y = load i32, i32* %b.addr
+            %cmp = icmp sgt i32 %x, %y
+            br i1 %cmp, label %then, label %else
+        then:
+            %t = load i32, i32* %a.addr
+            store i32 %t, i32* %retval
+            br label %end
+        else:
+            %e = load i32, i32* %b.addr
+            store i32 %e, i32* %retval
+            br label %end
+        end:
+            %r = load i32, i32* %retval
+            ret i32 %r
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let types = module.types.clone();
+    let func = &mut module.functions[0];
+    assert!(func.mem2reg(&types));
+    assert!(!func.mem2reg(&types));
+    assert_eq!(module.verify(), Ok(()));
+
+    let func = &module.functions[0];
+    // only the escaping alloca is left
+    let entry = &func.basic_blocks[0];
+    assert_eq!(entry.instrs.len(), 3);
+    let alloca: instruction::Alloca = entry.instrs[0].clone().try_into().unwrap();
+    assert_eq!(alloca.dest, Name::from("escapes"));
+    assert!(matches!(entry.instrs[1], Instruction::Call(_)));
+    assert_eq!(
+        entry.instrs[2].to_string(),
+        "%cmp = icmp sgt i32 %a, i32 %b"
+    );
+    assert!(func.basic_blocks[1].instrs.is_empty());
+    assert!(func.basic_blocks[2].instrs.is_empty());
+    let end = &func.basic_blocks[3];
+    let phi: instruction::Phi = end.instrs[0].clone().try_into().unwrap();
+    assert_eq!(phi.dest, Name::from("retval.0"));
+    assert_eq!(
+        phi.incoming_values,
+        vec![
+            (
+                Operand::LocalOperand {
+                    name: Name::from("a"),
+                    ty: types.i32()
+                },
+                Name::from("then")
+            ),
+            (
+                Operand::LocalOperand {
+                    name: Name::from("b"),
+                    ty: types.i32()
+                },
+                Name::from("else")
+            ),
+        ]
+    );
+    assert_eq!(end.instrs.len(), 1);
+    assert_eq!(end.term.to_string(), "ret i32 %retval.0");
+
+    // as a pass, `Mem2Reg` gets the dominator tree from the `AnalysisManager`
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let mut passes = PassManager::new();
+    passes.add_function_pass(Mem2Reg);
+    assert!(passes.run(&mut module));
+    assert_eq!(module.verify(), Ok(()));
+    assert_eq!(module.functions[0].basic_blocks[3].instrs.len(), 1);
+    let analysis_stats: Vec<_> = passes.analyses().statistics().cloned().collect();
+    assert_eq!(analysis_stats.len(), 2);
+    assert!(analysis_stats.iter().all(|stats| stats.computations == 1));
+    // and they were discarded when it changed the function
+    assert!(passes
+        .analyses()
+        .get_cached::<DominatorTree>("max")
+        .is_none());
+
+    // a loop, whose header needs phis fed by the back edge, including one
+    // which only refers to itself along it, and by an unreachable block
+    let ir = r#"
+        define i32 @sum(i32 %n) {
+        entry:
+            %i = alloca i32
+            %s = alloca i32
+            %k = alloca i32
+            store i32 0, i32* %i
+            store i32 0, i32* %s
+            store i32 %n, i32* %k
+            br label %header
+        header:
+            %iv = load i32, i32* %i
+            %cmp = icmp slt i32 %iv, %n
+            br i1 %cmp, label %body, label %exit
+        body:
+            %sv = load i32, i32* %s
+            %add = add i32 %sv, %iv
+            store i32 %add, i32* %s
+            %inc = add i32 %iv, 1
+            store i32 %inc, i32* %i
+            %kv = load i32, i32* %k
+            store i32 %kv, i32* %k
+            br label %header
+        dead:
+            store i32 7, i32* %s
+            br label %header
+        exit:
+            %r = load i32, i32* %s
+            %kr = load i32, i32* %k
+            %res = add i32 %r, %kr
+            ret i32 %res
+        }
+    "#;
+    let mut module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let types = module.types.clone();
+    assert!(module.functions[0].mem2reg(&types));
+    assert_eq!(module.verify(), Ok(()));
+    let func = &module.functions[0];
+    assert!(func
+        .basic_blocks
+        .iter()
+        .all(|bb| bb.instrs.iter().all(|inst| !matches!(
+            inst,
+            Instruction::Alloca(_) | Instruction::Load(_) | Instruction::Store(_)
+        ))));
+    let header: Vec<String> = func.basic_blocks[1]
+        .instrs
+        .iter()
+        .map(|inst| inst.to_string())
+        .collect();
+    assert_eq!(
+        header,
+        vec![
+            "%i.0 = phi i32 [ i32 0, %entry ], [ i32 %inc, %body ], [ i32 undef, %dead ]",
+            "%s.0 = phi i32 [ i32 0, %entry ], [ i32 %add, %body ], [ i32 7, %dead ]",
+            "%k.0 = phi i32 [ i32 %n, %entry ], [ i32 %k.0, %body ], [ i32 undef, %dead ]",
+            "%cmp = icmp slt i32 %i.0, i32 %n",
+        ]
+    );
+    assert_eq!(
+        func.basic_blocks[2].instrs[0].to_string(),
+        "%add = add i32 %s.0, i32 %i.0"
+    );
+    assert_eq!(
+        func.basic_blocks[4].instrs[0].to_string(),
+        "%res = add i32 %s.0, i32 %k.0"
+    );
+}
+
+#[test]
+fn mem2reg_corpus() {
+    init_logging();
+    let dirs = [llvm_bc_dir(), cxx_llvm_bc_dir()];
+    let mut promoted = false;
+    for path in dirs.iter().flat_map(|dir| std::fs::read_dir(dir).unwrap()) {
+        let path = path.unwrap().path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("bc") {
+            continue;
+        }
+        let mut module = Module::from_bc_path(&path).expect("Failed to parse module");
+        assert_eq!(module.verify(), Ok(()), "{}", path.display());
+        let types = module.types.clone();
+        for func in &mut module.functions {
+            promoted |= func.mem2reg(&types);
+        }
+        assert_eq!(module.verify(), Ok(()), "{} after mem2reg", path.display());
+    }
+    // the corpus is mostly compiled without optimizations, so has allocas to
+    // promote
+    assert!(promoted);
+}
+
 #[test]
 fn parseir() -> Result<(), Box<dyn std::error::Error>> {
     let ir = "define void @f() { ret void }";
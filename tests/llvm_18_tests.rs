@@ -109,6 +109,78 @@ fn nneg_zext() {
     assert_eq!(nneg_zext.nneg, true);
 }
 
+#[test]
+fn operand_bundles() {
+    use llvm_ir::instruction;
+    use std::convert::TryInto;
+
+    let _ = env_logger::builder().is_test(true).try_init(); // capture log messages with test harness
+    let ir = r#"
+        declare void @g()
+        define void @f(i64 %x) {
+            call void @g() [ "deopt"(i32 1, i64 %x), "cold"() ]
+            ret void
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let func = module
+        .get_func_by_name("f")
+        .expect("Failed to find function");
+    let call: &instruction::Call = &func.basic_blocks[0]
+        .instrs[0]
+        .clone()
+        .try_into()
+        .expect("Expected a call");
+
+    assert_eq!(call.arguments.len(), 0);
+    assert_eq!(call.operand_bundles.len(), 2);
+    assert_eq!(call.operand_bundles[0].tag, "deopt");
+    assert_eq!(call.operand_bundles[0].inputs.len(), 2);
+    assert_eq!(call.operand_bundles[1].tag, "cold");
+    assert_eq!(call.operand_bundles[1].inputs.len(), 0);
+    assert_eq!(
+        &call.to_string(),
+        r#"call @g() [ "deopt"(i32 1, i64 %x), "cold"() ]"#
+    );
+}
+
+#[test]
+fn fast_math_flags_and_tail_calls() {
+    use llvm_ir::instruction::{self, TailCallKind};
+    use std::convert::TryInto;
+
+    let _ = env_logger::builder().is_test(true).try_init(); // capture log messages with test harness
+    let ir = r#"
+        declare float @g(float)
+        define float @f(float %x, float %y) {
+            %a = fadd fast float %x, %y
+            %b = fmul nnan ninf float %a, %y
+            %c = notail call nsz float @g(float %b)
+            ret float %c
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let func = module
+        .get_func_by_name("f")
+        .expect("Failed to find function");
+    let bb = &func.basic_blocks[0];
+
+    let fadd: &instruction::FAdd = &bb.instrs[0].clone().try_into().expect("Expected an fadd");
+    assert!(fadd.fast_math_flags.is_fast());
+    assert_eq!(&fadd.to_string(), "%a = fadd fast float %x, float %y");
+
+    let fmul: &instruction::FMul = &bb.instrs[1].clone().try_into().expect("Expected an fmul");
+    assert!(fmul.fast_math_flags.no_NaNs);
+    assert!(fmul.fast_math_flags.no_Infs);
+    assert!(!fmul.fast_math_flags.allow_reassoc);
+    assert_eq!(&fmul.to_string(), "%b = fmul nnan ninf float %a, float %y");
+
+    let call: &instruction::Call = &bb.instrs[2].clone().try_into().expect("Expected a call");
+    assert_eq!(call.tail_call_kind, Some(TailCallKind::NoTail));
+    assert!(call.fast_math_flags.no_signed_zeros);
+    assert_eq!(&call.to_string(), "%c = notail call nsz @g(float %b)");
+}
+
 llvm_test!(
     "tests/llvm_bc/compatibility-as-of-llvm-18.bc",
     compatibility_llvm_18
@@ -44,4 +44,91 @@ fn ptr_auth() {
     }
 }
 
+#[test]
+fn nowrap_flags() {
+    use llvm_ir::{constant, instruction};
+    use std::convert::TryInto;
+
+    let _ = env_logger::builder().is_test(true).try_init(); // capture log messages with test harness
+    let ir = r#"
+        @vt = constant { [4 x ptr] } zeroinitializer
+        @p = global ptr getelementptr inbounds inrange(-8, 16) ({ [4 x ptr] }, ptr @vt, i32 0, i32 0, i32 1)
+        define i32 @f(ptr %a, i64 %x) {
+            %p = getelementptr nusw nuw i8, ptr %a, i64 %x
+            %t = trunc nuw nsw i64 %x to i32
+            ret i32 %t
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let func = module
+        .get_func_by_name("f")
+        .expect("Failed to find function");
+
+    let gep: &instruction::GetElementPtr = &func.basic_blocks[0]
+        .instrs[0]
+        .clone()
+        .try_into()
+        .expect("Expected a GEP");
+    assert!(!gep.in_bounds);
+    assert!(gep.nusw);
+    assert!(gep.nuw);
+    assert_eq!(&gep.to_string(), "%p = getelementptr nusw nuw ptr %a, i64 %x");
+
+    let trunc: &instruction::Trunc = &func.basic_blocks[0]
+        .instrs[1]
+        .clone()
+        .try_into()
+        .expect("Expected a trunc");
+    assert!(trunc.nuw);
+    assert!(trunc.nsw);
+    assert_eq!(&trunc.to_string(), "%t = trunc nuw nsw i64 %x to i32");
+
+    let p = module.get_global_var_by_name(&"p".into()).expect("Failed to find global");
+    let init = p.initializer.clone().expect("p should be initialised");
+    if let Constant::GetElementPtr(constant::GetElementPtr { in_bounds, nusw, nuw, inrange, .. }) = init.as_ref() {
+        assert!(*in_bounds);
+        assert!(*nusw);
+        assert!(!*nuw);
+        assert_eq!(*inrange, Some((-8, 16)));
+    } else {
+        panic!("p initialised with non-GEP constant {:?}", init);
+    }
+}
+
+#[test]
+fn constant_range_attributes() {
+    use llvm_ir::function::ParameterAttribute;
+
+    let _ = env_logger::builder().is_test(true).try_init(); // capture log messages with test harness
+    let ir = r#"
+        define range(i8 -1, 5) i8 @f(i32 range(i32 0, 10) %x, ptr initializes((0, 4), (8, 12)) %p) {
+            ret i8 0
+        }
+    "#;
+    let module = Module::from_ir_str(ir).expect("Failed to parse module");
+    let func = module
+        .get_func_by_name("f")
+        .expect("Failed to find function");
+    assert_eq!(
+        func.return_attributes,
+        vec![ParameterAttribute::Range {
+            ty: module.types.i8(),
+            lower: -1,
+            upper: 5,
+        }]
+    );
+    assert_eq!(
+        func.parameters[0].attributes,
+        vec![ParameterAttribute::Range {
+            ty: module.types.i32(),
+            lower: 0,
+            upper: 10,
+        }]
+    );
+    assert_eq!(
+        func.parameters[1].attributes,
+        vec![ParameterAttribute::Initializes(vec![(0, 4), (8, 12)])]
+    );
+}
+
 // TODO: Other LLVM-19+ tests
@@ -317,12 +317,13 @@ fn DILocation_implicit_code_extra_checks() {
     );
     assert_eq!(landingpad.result_type, expected_landingpad_resultty);
     assert_eq!(landingpad.clauses.len(), 1);
+    assert!(matches!(landingpad.clauses[0], instruction::LandingPadClause::Catch(_)));
     assert_eq!(landingpad.cleanup, false);
     assert_eq!(landingpad.dest, Name::Number(1));
     #[cfg(feature = "llvm-14-or-lower")]
-    let expected_fmt = "%1 = landingpad { i8*, i32 } (with debugloc)";
+    let expected_fmt = "%1 = landingpad { i8*, i32 } catch bitcast (i8** @_ZTIi to i8*) (with debugloc)";
     #[cfg(feature = "llvm-15-or-greater")]
-    let expected_fmt = "%1 = landingpad { ptr, i32 } (with debugloc)";
+    let expected_fmt = "%1 = landingpad { ptr, i32 } catch ptr @_ZTIi (with debugloc)";
     assert_eq!(&format!("{}", landingpad), expected_fmt);
     let eval: &instruction::ExtractValue = &lpad.instrs[1]
         .clone()
@@ -57,7 +57,8 @@ fn callbr_parsing() {
     assert!(callbr.function.is_left());
     assert_eq!(callbr.return_label, Name::from("normal"));
     #[cfg(feature = "llvm-14-or-lower")]
-    let expected_fmt = "%0 = callbr <inline assembly>(i32 %x, blockaddr) to label %normal";
+    let expected_fmt =
+        "%0 = callbr <inline assembly>(i32 %x, blockaddress(@test_asm_goto, %fail)) to label %normal";
     #[cfg(feature = "llvm-15-or-greater")]
     let expected_fmt = "%0 = callbr <inline assembly>(i32 %x) to label %normal";
     assert_eq!(&format!("{}", callbr), expected_fmt);